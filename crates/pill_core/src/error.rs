@@ -56,11 +56,21 @@ pub enum EngineError<'a> {
     #[error("{} {} not found", "UpdatePhase".sobj_style(), .0.name_style())]
     SystemUpdatePhaseNotFound(String),
     
+    // UI
+    #[error("{} {} already exists", "UiCallback".gobj_style(), .0.name_style())]
+    UiCallbackAlreadyExists(String),
+    #[error("{} {} not found", "UiCallback".gobj_style(), .0.name_style())]
+    UiCallbackNotFound(String),
+    #[error("Failed to run {} {}", "UiCallback".gobj_style(), .0.name_style())]
+    UiCallbackFailed(String),
+    
     // Resource
     #[error("Path to {} is invalid: {}", "Asset".gobj_style(), .0.name_style())]
     InvalidAssetPath(String),
     #[error("{} format is not supported. Expected one of: {:?} but is .{}", "Asset".gobj_style(), .0, .1.name_style())]
     InvalidAssetFormat(&'a [&'a str], String),
+    #[error("{} for {} format .{} is not registered. Registered formats: {:?}", "AssetLoader".gobj_style(), .0.sobj_style(), .1.name_style(), .2)]
+    AssetLoaderNotRegistered(String, String, Vec<String>),
     #[error("{} {} is not registered", "Resource".gobj_style(), .0.sobj_style())]
     ResourceNotRegistered(String),
     #[error("{} {} {} already exists", "Resource".gobj_style(), .0.sobj_style(), .1.name_style())]
@@ -73,16 +83,38 @@ pub enum EngineError<'a> {
     InvalidModelFile(String),
     #[error("Invalid .obj file {}\nFiles with multiple meshes are not supported", .0.name_style())]
     InvalidModelFileMultipleMeshes(String),
+    #[error("Invalid {} data: {}", "Mesh".gobj_style(), .0)]
+    InvalidMeshData(String),
+    #[error("Invalid {} data: {}", "Texture".gobj_style(), .0)]
+    InvalidTextureData(String),
+    #[error("Invalid {} data: {}", "Shader".gobj_style(), .0)]
+    InvalidShaderData(String),
+    #[error("Invalid {} data: {}", "Sound".gobj_style(), .0)]
+    InvalidSoundData(String),
     #[error("Cannot remove default {} {}", "Resource".gobj_style(), .0.name_style())]
     RemoveDefaultResource(String),
     #[error("Cannot add {} with name {}. This name is reserved only for default engine resources", "Resource".gobj_style(), .0.name_style())]
     WrongResourceName(String),
     #[error("Cannot add {} {}. Maximum number of resources reached. \n\nSource: ", "Resource".gobj_style(), .0.sobj_style())]
     ResourceLimitReached(String),
+    #[error("{} {} {} is not loaded yet", "Resource".gobj_style(), .0.sobj_style(), .1.name_style())]
+    ResourceNotLoaded(String, String),
+    #[error("Cannot remove {} {} {}. It is still used by {} other resources or components", "Resource".gobj_style(), .0.sobj_style(), .1.name_style(), .2)]
+    ResourceInUse(String, String, usize),
+
+    // Archive
+    #[error("Invalid {} file {}", "Archive".gobj_style(), .0.name_style())]
+    InvalidArchive(String),
+    #[error("{} entry {} not found", "Archive".gobj_style(), .0.name_style())]
+    ArchiveEntryNotFound(String),
+    #[error("{} entry {} is corrupted", "Archive".gobj_style(), .0.name_style())]
+    ArchiveEntryCorrupted(String),
 
     // Material textures and parameters
     #[error("Cannot set {} to {}. Accepted range is {}", "RenderingOrder".sobj_style(), .0.name_style(), .1.name_style())]
     WrongRenderingOrder(String, String),
+    #[error("Cannot set {} to {}. Accepted range is {}", "AlphaCutoff".sobj_style(), .0.name_style(), .1.name_style())]
+    WrongAlphaCutoff(String, String),
     #[error("Cannot set {} of type {} to slot {} of type {}", "Texture".sobj_style(), .0.name_style(), .1.name_style(), .2.name_style())]
     WrongTextureType(String, String, String),
     #[error("{} slot {} of type {} does not exist", "MaterialParameter".sobj_style(), .0.name_style(), .1.sobj_style())]
@@ -90,6 +122,12 @@ pub enum EngineError<'a> {
     #[error("{} slot {} does not exist", "MaterialTexture".sobj_style(), .0.name_style())]
     MaterialTextureSlotNotFound(String),
 
+    // Rendering
+    #[error("{} sample count {} is not supported. Supported sample counts: {:?}", "MSAA".sobj_style(), .0.name_style(), .1)]
+    UnsupportedMsaaSampleCount(String, Vec<u32>),
+    #[error("{} {} is out of range, layers from 0 to {} are available", "RenderLayer".sobj_style(), .0.name_style(), .1)]
+    RenderLayerOutOfRange(String, u32),
+
     // Other
     #[error("{} error: {}", "Engine".mobj_style(), .0)]
     Other(String)
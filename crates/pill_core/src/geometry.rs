@@ -0,0 +1,205 @@
+use crate::{ Vector3f, Vector4f, Matrix4f };
+
+use cgmath::{ InnerSpace, Matrix };
+
+// --- Bounding box ---
+
+// Axis aligned box
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BoundingBox {
+    pub min: Vector3f,
+    pub max: Vector3f,
+}
+
+impl BoundingBox {
+    pub fn new(min: Vector3f, max: Vector3f) -> Self {
+        Self { 
+            min, 
+            max,
+        }
+    }
+
+    // Returns smallest box containing all points (None if there are no points)
+    pub fn from_points(mut points: impl Iterator<Item = Vector3f>) -> Option<Self> {
+        let first_point = points.next()?;
+        let bounding_box = points.fold(Self::new(first_point, first_point), |bounding_box, point| Self::new(
+            Vector3f::new(bounding_box.min.x.min(point.x), bounding_box.min.y.min(point.y), bounding_box.min.z.min(point.z)),
+            Vector3f::new(bounding_box.max.x.max(point.x), bounding_box.max.y.max(point.y), bounding_box.max.z.max(point.z)),
+        ));
+
+        Some(bounding_box)
+    }
+
+    pub fn get_center(&self) -> Vector3f {
+        (self.min + self.max) / 2.0
+    }
+
+    pub fn get_size(&self) -> Vector3f {
+        self.max - self.min
+    }
+
+    pub fn get_corners(&self) -> [Vector3f; 8] {
+        [
+            Vector3f::new(self.min.x, self.min.y, self.min.z),
+            Vector3f::new(self.max.x, self.min.y, self.min.z),
+            Vector3f::new(self.min.x, self.max.y, self.min.z),
+            Vector3f::new(self.max.x, self.max.y, self.min.z),
+            Vector3f::new(self.min.x, self.min.y, self.max.z),
+            Vector3f::new(self.max.x, self.min.y, self.max.z),
+            Vector3f::new(self.min.x, self.max.y, self.max.z),
+            Vector3f::new(self.max.x, self.max.y, self.max.z),
+        ]
+    }
+
+    // Returns axis aligned box containing this box transformed by the matrix
+    pub fn transform(&self, matrix: &Matrix4f) -> Self {
+        let corners = self.get_corners();
+        Self::from_points(corners.iter().map(|v| (matrix * v.extend(1.0)).truncate())).unwrap()
+    }
+}
+
+// --- Frustum ---
+
+// Volume visible by camera, defined by six planes with normals pointing inside
+#[derive(Clone, Copy, Debug)]
+pub struct Frustum {
+    planes: [Vector4f; 6], // Plane normal (x, y, z) and distance (w)
+}
+
+impl Frustum {
+    // Extracts planes from view-projection matrix (clip space depth from -1 to 1 as in OpenGL)
+    pub fn from_matrix(matrix: &Matrix4f) -> Self {
+        let matrix = matrix.transpose(); // Columns of transposed matrix are rows of original one
+        let planes = [
+            matrix.w + matrix.x, // Left
+            matrix.w - matrix.x, // Right
+            matrix.w + matrix.y, // Bottom
+            matrix.w - matrix.y, // Top
+            matrix.w + matrix.z, // Near
+            matrix.w - matrix.z, // Far
+        ];
+
+        Self { 
+            planes,
+        }
+    }
+
+    // Returns false only if box is completely outside of the frustum (may return true for some boxes near its corners)
+    pub fn intersects_bounding_box(&self, bounding_box: &BoundingBox) -> bool {
+        for plane in self.planes.iter() {
+            // Take corner of the box that is furthest along plane normal
+            let corner = Vector3f::new(
+                match plane.x >= 0.0 { true => bounding_box.max.x, false => bounding_box.min.x },
+                match plane.y >= 0.0 { true => bounding_box.max.y, false => bounding_box.min.y },
+                match plane.z >= 0.0 { true => bounding_box.max.z, false => bounding_box.min.z },
+            );
+
+            // If it is behind the plane whole box is outside
+            if plane.truncate().dot(corner) + plane.w < 0.0 {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+// --- Level of detail ---
+
+// Returns index of the level to use from thresholds sorted from the most detailed level (None if entity is too small to be rendered)
+// Thresholds are minimal fractions of screen height, hysteresis is relative margin around them that has to be crossed before level is switched
+pub fn select_lod_level(thresholds: impl Iterator<Item = f32>, hysteresis: f32, current_level: Option<usize>, screen_coverage: f32) -> Option<usize> {
+    thresholds.enumerate().position(|(index, threshold)| {
+        let threshold = match current_level {
+            // Switching to more detailed level requires entity to be bigger than its threshold
+            Some(v) if index < v => threshold * (1.0 + hysteresis),
+            // Current level is kept until entity is smaller than its threshold
+            Some(_) => threshold * (1.0 - hysteresis),
+            None => threshold,
+        };
+        screen_coverage >= threshold
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn create_frustum() -> Frustum {
+        // Camera at (0, 0, 5) looking towards -Z
+        let projection = cgmath::perspective(cgmath::Deg(60.0), 1.0, 0.1, 100.0);
+        let view = Matrix4f::look_to_rh(cgmath::Point3::new(0.0, 0.0, 5.0), -Vector3f::unit_z(), Vector3f::unit_y());
+        Frustum::from_matrix(&(projection * view))
+    }
+
+    #[test]
+    fn bounding_box_transform() {
+        let bounding_box = BoundingBox::new(Vector3f::new(-1.0, -1.0, -1.0), Vector3f::new(1.0, 1.0, 1.0));
+        let matrix = Matrix4f::from_translation(Vector3f::new(10.0, 0.0, 0.0)) * Matrix4f::from_scale(2.0);
+        let transformed_bounding_box = bounding_box.transform(&matrix);
+
+        assert_eq!(transformed_bounding_box.min, Vector3f::new(8.0, -2.0, -2.0));
+        assert_eq!(transformed_bounding_box.max, Vector3f::new(12.0, 2.0, 2.0));
+        assert_eq!(transformed_bounding_box.get_center(), Vector3f::new(10.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn frustum_intersects_bounding_box() {
+        let frustum = create_frustum();
+        let unit_box_at = |x: f32, y: f32, z: f32| BoundingBox::new(Vector3f::new(x - 0.5, y - 0.5, z - 0.5), Vector3f::new(x + 0.5, y + 0.5, z + 0.5));
+
+        assert!(frustum.intersects_bounding_box(&unit_box_at(0.0, 0.0, 0.0))); // In front of camera
+        assert!(frustum.intersects_bounding_box(&unit_box_at(0.0, 0.0, 5.0))); // Around camera
+        assert!(!frustum.intersects_bounding_box(&unit_box_at(0.0, 0.0, 10.0))); // Behind camera
+        assert!(!frustum.intersects_bounding_box(&unit_box_at(50.0, 0.0, 0.0))); // Far to the side
+        assert!(!frustum.intersects_bounding_box(&unit_box_at(0.0, 0.0, -200.0))); // Beyond far plane
+    }
+
+    #[test]
+    fn orthographic_frustum_intersects_bounding_box() {
+        // Camera at (0, 0, 5) looking towards -Z, visible area is 10 units high and wide
+        let projection = cgmath::ortho(-5.0, 5.0, -5.0, 5.0, 0.1, 100.0);
+        let view = Matrix4f::look_to_rh(cgmath::Point3::new(0.0, 0.0, 5.0), -Vector3f::unit_z(), Vector3f::unit_y());
+        let frustum = Frustum::from_matrix(&(projection * view));
+        let unit_box_at = |x: f32, y: f32, z: f32| BoundingBox::new(Vector3f::new(x - 0.5, y - 0.5, z - 0.5), Vector3f::new(x + 0.5, y + 0.5, z + 0.5));
+
+        assert!(frustum.intersects_bounding_box(&unit_box_at(4.0, 0.0, 0.0))); // Near the edge
+        assert!(frustum.intersects_bounding_box(&unit_box_at(4.0, 4.0, -90.0))); // Near the edge far away (does not widen with distance)
+        assert!(!frustum.intersects_bounding_box(&unit_box_at(6.0, 0.0, -90.0))); // Outside the edge far away
+        assert!(!frustum.intersects_bounding_box(&unit_box_at(0.0, 0.0, 10.0))); // Behind camera
+    }
+
+    #[test]
+    fn select_lod_level_without_current_level() {
+        let thresholds = [0.5, 0.2, 0.05];
+        let select = |screen_coverage: f32| select_lod_level(thresholds.iter().copied(), 0.1, None, screen_coverage);
+
+        assert_eq!(select(0.8), Some(0));
+        assert_eq!(select(0.5), Some(0));
+        assert_eq!(select(0.3), Some(1));
+        assert_eq!(select(0.1), Some(2));
+        assert_eq!(select(0.01), None); // Too small to be rendered
+        assert_eq!(select_lod_level(std::iter::empty(), 0.1, None, 1.0), None);
+    }
+
+    #[test]
+    fn select_lod_level_hysteresis() {
+        let thresholds = [0.5, 0.2, 0.05];
+        let select = |current_level: Option<usize>, screen_coverage: f32| select_lod_level(thresholds.iter().copied(), 0.1, current_level, screen_coverage);
+
+        // Current level is kept slightly below its threshold
+        assert_eq!(select(Some(0), 0.46), Some(0));
+        assert_eq!(select(Some(0), 0.44), Some(1));
+        assert_eq!(select(Some(2), 0.046), Some(2));
+        assert_eq!(select(Some(2), 0.044), None);
+
+        // More detailed level is used only when entity is clearly above its threshold
+        assert_eq!(select(Some(1), 0.54), Some(1));
+        assert_eq!(select(Some(1), 0.56), Some(0));
+        assert_eq!(select(None, 0.051), Some(2));
+
+        // Without hysteresis thresholds are used as they are
+        assert_eq!(select_lod_level(thresholds.iter().copied(), 0.0, Some(1), 0.5), Some(0));
+        assert_eq!(select_lod_level(thresholds.iter().copied(), 0.0, Some(0), 0.49), Some(1));
+    }
+}
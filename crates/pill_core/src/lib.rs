@@ -3,16 +3,28 @@
 
 mod error;
 mod math;
+mod geometry;
+mod render_layers;
 mod utils;
 mod pill_slotmap;
 mod pill_twinmap;
 mod pill_typemap;
 mod bitmask_utils;
+mod pill_archive;
+mod texture_compression;
 
 // --- Use ---
 
 pub use math::*;
 
+pub use geometry::{
+    BoundingBox,
+    Frustum,
+    select_lod_level,
+};
+
+pub use render_layers::RenderLayers;
+
 pub use error::EngineError;
 
 pub use pill_slotmap::{ 
@@ -30,6 +42,21 @@ pub use pill_typemap::{
     PillTypeMapKey,
 };
 
+pub use pill_archive::{
+    PillArchive,
+    PillArchiveEntry,
+    PillArchiveWriter,
+    get_archive_entry_name,
+};
+
+pub use texture_compression::{
+    TextureCompressionFormat,
+    decompress_texture,
+};
+
+// Used by archives and by engine for zlib supercompressed textures
+pub use miniz_oxide;
+
 pub use bitmask_utils::{
     create_bitmask_from_range,
     create_bitmask_with_one,
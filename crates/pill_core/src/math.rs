@@ -4,6 +4,8 @@ pub type Vector2i = cgmath::Vector2<i32>;
 
 pub type Vector3f = cgmath::Vector3<f32>;
 pub type Vector2f = cgmath::Vector2<f32>;
+pub type Vector4f = cgmath::Vector4<f32>;
 
 pub type Color = cgmath::Vector3<f32>;
 pub type Matrix3f = cgmath::Matrix3<f32>;
+pub type Matrix4f = cgmath::Matrix4<f32>;
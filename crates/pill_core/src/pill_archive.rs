@@ -0,0 +1,349 @@
+use crate::EngineError;
+
+use std::{
+    collections::HashMap,
+    convert::TryInto,
+    fs::{ self, File },
+    io::{ Read, Seek, SeekFrom, Write },
+    path::{ Path, PathBuf },
+    sync::Mutex,
+};
+use anyhow::{ Context, Result, Error };
+
+// Layout of the archive file (all numbers are little endian):
+// - Header: magic (8 bytes), version (u32), entry count (u32), index offset (u64)
+// - Data of all entries, one after another
+// - Index: for each entry name length (u32), name (UTF-8), offset (u64), stored size (u64), size (u64), compression (u8), hash (u32)
+
+pub const PILL_ARCHIVE_MAGIC: &[u8; 8] = b"PILLPAK\0";
+pub const PILL_ARCHIVE_VERSION: u32 = 1;
+
+const PILL_ARCHIVE_HEADER_SIZE: u64 = 8 + 4 + 4 + 8;
+const PILL_ARCHIVE_COMPRESSION_NONE: u8 = 0;
+const PILL_ARCHIVE_COMPRESSION_DEFLATE: u8 = 1;
+const PILL_ARCHIVE_COMPRESSION_LEVEL: u8 = 6;
+
+// Converts path used in development (e.g. "./res/textures/Stone.png") to the name of archive entry ("textures/Stone.png")
+pub fn get_archive_entry_name(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut name = path.as_str();
+    loop {
+        if let Some(v) = name.strip_prefix("./") {
+            name = v;
+        }
+        else if let Some(v) = name.strip_prefix("res/") {
+            name = v;
+        }
+        else {
+            break;
+        }
+    }
+    name.to_string()
+}
+
+// --- Entry ---
+
+#[derive(Clone, Debug)]
+pub struct PillArchiveEntry {
+    pub name: String,
+    pub offset: u64,
+    pub stored_size: u64, // Size of the data in the archive
+    pub size: u64, // Size of the data after decompression
+    pub compressed: bool,
+    pub hash: u32, // CRC32 of the decompressed data
+}
+
+// --- Archive ---
+
+// Read-only view of the archive file, entries are read on demand
+pub struct PillArchive {
+    path: PathBuf,
+    file: Mutex<File>,
+    entries: HashMap<String, PillArchiveEntry>,
+}
+
+impl PillArchive {
+    pub fn open(path: &Path) -> Result<Self> {
+        let invalid_archive = || Error::new(EngineError::InvalidArchive(path.display().to_string()));
+        let mut file = File::open(path).context(invalid_archive())?;
+
+        // Read header
+        let mut header = [0u8; PILL_ARCHIVE_HEADER_SIZE as usize];
+        file.read_exact(&mut header).context(invalid_archive())?;
+        if &header[0..8] != PILL_ARCHIVE_MAGIC || read_u32(&header[8..12]) != PILL_ARCHIVE_VERSION {
+            return Err(invalid_archive());
+        }
+        let entry_count = read_u32(&header[12..16]) as usize;
+        let index_offset = read_u64(&header[16..24]);
+        let archive_size = file.metadata().context(invalid_archive())?.len();
+        if index_offset < PILL_ARCHIVE_HEADER_SIZE || index_offset > archive_size {
+            return Err(invalid_archive());
+        }
+
+        // Read index
+        let mut index = Vec::<u8>::new();
+        file.seek(SeekFrom::Start(index_offset)).context(invalid_archive())?;
+        file.read_to_end(&mut index).context(invalid_archive())?;
+
+        let mut entries = HashMap::<String, PillArchiveEntry>::new(); // Not reserved up front, entry count is read from the file
+        let mut cursor = 0;
+        for _ in 0..entry_count {
+            let name_length = read_u32(index.get(cursor..cursor + 4).ok_or_else(invalid_archive)?) as usize;
+            cursor += 4;
+            let name = String::from_utf8(index.get(cursor..cursor + name_length).ok_or_else(invalid_archive)?.to_vec()).context(invalid_archive())?;
+            cursor += name_length;
+            let fields = index.get(cursor..cursor + 29).ok_or_else(invalid_archive)?;
+            cursor += 29;
+
+            let entry = PillArchiveEntry {
+                name: name.clone(),
+                offset: read_u64(&fields[0..8]),
+                stored_size: read_u64(&fields[8..16]),
+                size: read_u64(&fields[16..24]),
+                compressed: fields[24] == PILL_ARCHIVE_COMPRESSION_DEFLATE,
+                hash: read_u32(&fields[25..29]),
+            };
+
+            // Data of the entry has to be between header and index
+            let entry_end = entry.offset.checked_add(entry.stored_size).ok_or_else(invalid_archive)?;
+            if entry.offset < PILL_ARCHIVE_HEADER_SIZE || entry_end > index_offset {
+                return Err(invalid_archive());
+            }
+            entries.insert(name, entry);
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            file: Mutex::new(file),
+            entries,
+        })
+    }
+
+    pub fn get_path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.entries.contains_key(name)
+    }
+
+    pub fn get_entry(&self, name: &str) -> Option<&PillArchiveEntry> {
+        self.entries.get(name)
+    }
+
+    pub fn get_entry_names(&self) -> impl Iterator<Item = &String> {
+        self.entries.keys()
+    }
+
+    // Reads data of the entry, decompresses it and checks its hash
+    pub fn read(&self, name: &str) -> Result<Vec<u8>> {
+        let entry = self.entries.get(name).ok_or(Error::new(EngineError::ArchiveEntryNotFound(name.to_string())))?;
+
+        // Read stored data (only after checking that it fits in the archive, file might have changed since it was opened)
+        let stored_data = {
+            let mut file = self.file.lock().expect("Critical: Mutex is blocked");
+            let archive_size = file.metadata()?.len();
+            if entry.offset.saturating_add(entry.stored_size) > archive_size {
+                return Err(Error::new(EngineError::ArchiveEntryCorrupted(name.to_string())));
+            }
+
+            let mut stored_data = vec![0u8; entry.stored_size as usize];
+            file.seek(SeekFrom::Start(entry.offset))?;
+            file.read_exact(&mut stored_data)?;
+            stored_data
+        };
+
+        // Decompress (output is limited to expected size, so corrupted entry cannot inflate to arbitrary size)
+        let data = match entry.compressed {
+            true => miniz_oxide::inflate::decompress_to_vec_with_limit(&stored_data, entry.size as usize)
+                .map_err(|_| Error::new(EngineError::ArchiveEntryCorrupted(name.to_string())))?,
+            false => stored_data,
+        };
+
+        // Check if data is valid
+        if data.len() as u64 != entry.size || crc32fast::hash(&data) != entry.hash {
+            return Err(Error::new(EngineError::ArchiveEntryCorrupted(name.to_string())));
+        }
+
+        Ok(data)
+    }
+}
+
+// --- Archive writer ---
+
+pub struct PillArchiveWriter {
+    compress: bool,
+    entries: Vec<(String, Vec<u8>)>,
+}
+
+impl PillArchiveWriter {
+    pub fn new(compress: bool) -> Self {
+        Self {
+            compress,
+            entries: Vec::<(String, Vec<u8>)>::new(),
+        }
+    }
+
+    pub fn add_entry(&mut self, name: &str, data: Vec<u8>) {
+        self.entries.push((name.to_string(), data));
+    }
+
+    // Adds all files from the directory (recursively), entry names are paths relative to that directory
+    pub fn add_directory(&mut self, directory_path: &Path, exclude: &[&str]) -> Result<()> {
+        let mut directories = vec![directory_path.to_path_buf()];
+        while let Some(directory) = directories.pop() {
+            for dir_entry in fs::read_dir(&directory).context(format!("Cannot read directory {}", directory.display()))? {
+                let path = dir_entry?.path();
+                if path.is_dir() {
+                    directories.push(path);
+                    continue;
+                }
+
+                let name = path.strip_prefix(directory_path)?.to_string_lossy().replace('\\', "/");
+                if exclude.contains(&name.as_str()) {
+                    continue;
+                }
+                let data = fs::read(&path).context(format!("Cannot read file {}", path.display()))?;
+                self.add_entry(&name, data);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let mut file = File::create(path).context(format!("Cannot create archive {}", path.display()))?;
+
+        // Reserve space for header
+        file.write_all(&[0u8; PILL_ARCHIVE_HEADER_SIZE as usize])?;
+
+        // Write data of all entries
+        let mut offset = PILL_ARCHIVE_HEADER_SIZE;
+        let mut index = Vec::<u8>::new();
+        for (name, data) in self.entries.iter() {
+            let compressed_data = match self.compress {
+                true => Some(miniz_oxide::deflate::compress_to_vec(data, PILL_ARCHIVE_COMPRESSION_LEVEL)),
+                false => None,
+            };
+
+            // Keep data uncompressed if compression does not make it smaller (e.g. for PNG or MP3 files)
+            let (stored_data, compression) = match &compressed_data {
+                Some(v) if v.len() < data.len() => (v.as_slice(), PILL_ARCHIVE_COMPRESSION_DEFLATE),
+                _ => (data.as_slice(), PILL_ARCHIVE_COMPRESSION_NONE),
+            };
+            file.write_all(stored_data)?;
+
+            // Add entry to index
+            index.extend_from_slice(&(name.len() as u32).to_le_bytes());
+            index.extend_from_slice(name.as_bytes());
+            index.extend_from_slice(&offset.to_le_bytes());
+            index.extend_from_slice(&(stored_data.len() as u64).to_le_bytes());
+            index.extend_from_slice(&(data.len() as u64).to_le_bytes());
+            index.push(compression);
+            index.extend_from_slice(&crc32fast::hash(data).to_le_bytes());
+
+            offset += stored_data.len() as u64;
+        }
+
+        // Write index
+        file.write_all(&index)?;
+
+        // Write header
+        file.seek(SeekFrom::Start(0))?;
+        file.write_all(PILL_ARCHIVE_MAGIC)?;
+        file.write_all(&PILL_ARCHIVE_VERSION.to_le_bytes())?;
+        file.write_all(&(self.entries.len() as u32).to_le_bytes())?;
+        file.write_all(&offset.to_le_bytes())?;
+
+        Ok(())
+    }
+}
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes(bytes.try_into().unwrap())
+}
+
+fn read_u64(bytes: &[u8]) -> u64 {
+    u64::from_le_bytes(bytes.try_into().unwrap())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn pillarchive_entry_name() {
+        assert_eq!(get_archive_entry_name(Path::new("./res/models/Pill.obj")), "models/Pill.obj");
+        assert_eq!(get_archive_entry_name(Path::new("res\\textures\\Stone.png")), "textures/Stone.png");
+        assert_eq!(get_archive_entry_name(Path::new("audio/Music.mp3")), "audio/Music.mp3");
+    }
+
+    #[test]
+    fn pillarchive_write_read() {
+        let path = std::env::temp_dir().join(format!("pill_archive_test_{}.pak", std::process::id()));
+        let text = b"Pill Engine ".repeat(100);
+        let binary = (0..=255u8).collect::<Vec<u8>>();
+
+        for compress in [false, true] {
+            let mut writer = PillArchiveWriter::new(compress);
+            writer.add_entry("text.txt", text.clone());
+            writer.add_entry("data/binary.bin", binary.clone());
+            writer.write(&path).unwrap();
+
+            let archive = PillArchive::open(&path).unwrap();
+            assert_eq!(archive.get_entry("text.txt").unwrap().compressed, compress);
+            assert_eq!(archive.read("text.txt").unwrap(), text);
+            assert_eq!(archive.read("data/binary.bin").unwrap(), binary);
+            assert!(archive.read("missing.txt").is_err());
+        }
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn pillarchive_invalid_entry_size() {
+        let path = std::env::temp_dir().join(format!("pill_archive_invalid_test_{}.pak", std::process::id()));
+        let mut writer = PillArchiveWriter::new(false);
+        writer.add_entry("text.txt", b"Pill Engine".to_vec());
+        writer.write(&path).unwrap();
+        assert!(PillArchive::open(&path).is_ok());
+
+        // Stored size of the only entry is right after its name in the index
+        let mut bytes = fs::read(&path).unwrap();
+        let index_offset = read_u64(&bytes[16..24]) as usize;
+        let stored_size_offset = index_offset + 4 + "text.txt".len() + 8;
+        bytes[stored_size_offset..stored_size_offset + 8].copy_from_slice(&u64::MAX.to_le_bytes());
+        fs::write(&path, &bytes).unwrap();
+        assert!(PillArchive::open(&path).is_err());
+
+        // Index offset beyond the end of the file
+        let archive_size = bytes.len() as u64;
+        bytes[16..24].copy_from_slice(&(archive_size + 1).to_le_bytes());
+        fs::write(&path, &bytes).unwrap();
+        assert!(PillArchive::open(&path).is_err());
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn pillarchive_compressed_entry_size_limit() {
+        let path = std::env::temp_dir().join(format!("pill_archive_limit_test_{}.pak", std::process::id()));
+        let mut writer = PillArchiveWriter::new(true);
+        writer.add_entry("text.txt", b"Pill Engine ".repeat(100));
+        writer.write(&path).unwrap();
+
+        // Size of the only entry is right after its name, offset and stored size in the index
+        let mut bytes = fs::read(&path).unwrap();
+        let index_offset = read_u64(&bytes[16..24]) as usize;
+        let size_offset = index_offset + 4 + "text.txt".len() + 16;
+        bytes[size_offset..size_offset + 8].copy_from_slice(&100u64.to_le_bytes());
+        fs::write(&path, &bytes).unwrap();
+
+        // Entry inflates to more than its size so decompression is stopped
+        let archive = PillArchive::open(&path).unwrap();
+        assert!(archive.get_entry("text.txt").unwrap().compressed);
+        assert!(archive.read("text.txt").is_err());
+
+        fs::remove_file(&path).unwrap();
+    }
+}
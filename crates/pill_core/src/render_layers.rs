@@ -0,0 +1,114 @@
+use crate::EngineError;
+
+use anyhow::{ Result, Error };
+
+// --- Render layers ---
+
+// Set of layers mesh rendering component belongs to, or set of layers camera renders (e.g. minimap camera that sees only icons)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RenderLayers(u32);
+
+impl RenderLayers {
+    pub const MAX_LAYER_COUNT: u32 = 32;
+    pub const DEFAULT_LAYER: u32 = 0; // Mesh rendering components are added to this layer by default
+
+    pub fn none() -> Self {
+        Self(0)
+    }
+
+    pub fn all() -> Self {
+        Self(u32::MAX)
+    }
+
+    // Returns set with only given layer
+    pub fn layer(layer: u32) -> Result<Self> {
+        Self::none().with(layer)
+    }
+
+    pub fn from_bits(bits: u32) -> Self {
+        Self(bits)
+    }
+
+    pub fn bits(&self) -> u32 {
+        self.0
+    }
+
+    pub fn with(self, layer: u32) -> Result<Self> {
+        Ok(Self(self.0 | get_layer_bit(layer)?))
+    }
+
+    pub fn without(self, layer: u32) -> Result<Self> {
+        Ok(Self(self.0 & !get_layer_bit(layer)?))
+    }
+
+    pub fn contains(&self, layer: u32) -> bool {
+        layer < Self::MAX_LAYER_COUNT && self.0 & (1 << layer) != 0
+    }
+
+    // Returns true if both sets have at least one common layer
+    pub fn intersects(&self, other: &RenderLayers) -> bool {
+        self.0 & other.0 != 0
+    }
+}
+
+impl Default for RenderLayers {
+    fn default() -> Self {
+        Self(1 << Self::DEFAULT_LAYER)
+    }
+}
+
+fn get_layer_bit(layer: u32) -> Result<u32> {
+    match layer < RenderLayers::MAX_LAYER_COUNT {
+        true => Ok(1 << layer),
+        false => Err(Error::new(EngineError::RenderLayerOutOfRange(layer.to_string(), RenderLayers::MAX_LAYER_COUNT - 1))),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn render_layers_default() {
+        let layers = RenderLayers::default();
+        assert!(layers.contains(RenderLayers::DEFAULT_LAYER));
+        assert_eq!(layers.bits(), 1);
+        assert!(RenderLayers::all().intersects(&layers));
+        assert!(!RenderLayers::none().intersects(&layers));
+    }
+
+    #[test]
+    fn render_layers_with_without() {
+        let layers = RenderLayers::none().with(3).unwrap().with(31).unwrap();
+        assert!(layers.contains(3));
+        assert!(layers.contains(31));
+        assert!(!layers.contains(0));
+        assert_eq!(layers.bits(), (1 << 3) | (1 << 31));
+
+        let layers = layers.without(3).unwrap();
+        assert!(!layers.contains(3));
+        assert!(layers.contains(31));
+
+        // Removing layer that is not in the set does nothing
+        assert_eq!(layers.without(5).unwrap(), layers);
+    }
+
+    #[test]
+    fn render_layers_out_of_range() {
+        assert!(RenderLayers::layer(RenderLayers::MAX_LAYER_COUNT).is_err());
+        assert!(RenderLayers::all().with(40).is_err());
+        assert!(RenderLayers::all().without(32).is_err());
+        assert!(!RenderLayers::all().contains(32));
+    }
+
+    #[test]
+    fn render_layers_intersects() {
+        let icons = RenderLayers::layer(4).unwrap();
+        let world = RenderLayers::layer(0).unwrap().with(1).unwrap();
+        let minimap_mask = RenderLayers::from_bits(icons.bits());
+        assert!(minimap_mask.intersects(&icons));
+        assert!(!minimap_mask.intersects(&world));
+        assert!(RenderLayers::all().without(4).unwrap().intersects(&world));
+        assert!(!RenderLayers::all().without(4).unwrap().intersects(&icons));
+    }
+}
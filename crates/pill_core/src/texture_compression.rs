@@ -0,0 +1,808 @@
+use crate::EngineError;
+
+use anyhow::{ Result, Error };
+
+// Software decoder of block compressed (BCn) textures, used when GPU does not support them
+// Every block holds 4x4 pixels, blocks are stored row by row
+// Decompressed data is RGBA8 (unorm or snorm) for BC1-BC5 and BC7, and RGBA16 float for BC6H
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextureCompressionFormat {
+    Bc1,
+    Bc2,
+    Bc3,
+    Bc4,
+    Bc4Signed,
+    Bc5,
+    Bc5Signed,
+    Bc6h,
+    Bc6hSigned,
+    Bc7,
+}
+
+impl TextureCompressionFormat {
+    pub fn get_block_size(&self) -> usize {
+        match self {
+            Self::Bc1 | Self::Bc4 | Self::Bc4Signed => 8,
+            _ => 16,
+        }
+    }
+
+    pub fn is_hdr(&self) -> bool {
+        matches!(self, Self::Bc6h | Self::Bc6hSigned)
+    }
+
+    pub fn is_signed(&self) -> bool {
+        matches!(self, Self::Bc4Signed | Self::Bc5Signed | Self::Bc6hSigned)
+    }
+
+    // Size of one pixel after decompression
+    pub fn get_decompressed_pixel_size(&self) -> usize {
+        match self.is_hdr() {
+            true => 8,
+            false => 4,
+        }
+    }
+
+    pub fn get_compressed_size(&self, width: u32, height: u32) -> usize {
+        let block_count = (width as usize).div_ceil(4).max(1) * (height as usize).div_ceil(4).max(1);
+        block_count * self.get_block_size()
+    }
+}
+
+pub fn decompress_texture(format: TextureCompressionFormat, width: u32, height: u32, data: &[u8]) -> Result<Vec<u8>> {
+    let expected_size = format.get_compressed_size(width, height);
+    if data.len() < expected_size {
+        return Err(Error::new(EngineError::InvalidTextureData(format!("Expected {} bytes of {:?} data, got {}", expected_size, format, data.len()))));
+    }
+
+    let (width, height) = (width as usize, height as usize);
+    let pixel_size = format.get_decompressed_pixel_size();
+    let block_size = format.get_block_size();
+    let blocks_per_row = width.div_ceil(4).max(1);
+    let mut pixels = vec![0u8; width * height * pixel_size];
+
+    for (block_index, block) in data[..expected_size].chunks_exact(block_size).enumerate() {
+        let block_x = (block_index % blocks_per_row) * 4;
+        let block_y = (block_index / blocks_per_row) * 4;
+
+        // Decode block
+        let mut block_pixels = [[0u8; 8]; 16];
+        decompress_block(format, block, &mut block_pixels);
+
+        // Copy pixels that are inside of the texture (blocks on the edges can be partially outside)
+        for (i, block_pixel) in block_pixels.iter().enumerate() {
+            let (x, y) = (block_x + i % 4, block_y + i / 4);
+            if x < width && y < height {
+                let offset = (y * width + x) * pixel_size;
+                pixels[offset..offset + pixel_size].copy_from_slice(&block_pixel[..pixel_size]);
+            }
+        }
+    }
+
+    Ok(pixels)
+}
+
+fn decompress_block(format: TextureCompressionFormat, block: &[u8], pixels: &mut [[u8; 8]; 16]) {
+    match format {
+        TextureCompressionFormat::Bc1 => {
+            decompress_color_block(block, false, pixels);
+        },
+        TextureCompressionFormat::Bc2 => {
+            decompress_color_block(&block[8..16], true, pixels);
+            let alpha_bits = u64::from_le_bytes(block[0..8].try_into().unwrap());
+            for (i, pixel) in pixels.iter_mut().enumerate() {
+                pixel[3] = ((alpha_bits >> (4 * i)) & 0xF) as u8 * 17;
+            }
+        },
+        TextureCompressionFormat::Bc3 => {
+            decompress_color_block(&block[8..16], true, pixels);
+            decompress_alpha_block(&block[0..8], false, pixels, 3);
+        },
+        TextureCompressionFormat::Bc4 | TextureCompressionFormat::Bc4Signed => {
+            let signed = format.is_signed();
+            decompress_alpha_block(&block[0..8], signed, pixels, 0);
+            set_missing_channels(pixels, 1, signed);
+        },
+        TextureCompressionFormat::Bc5 | TextureCompressionFormat::Bc5Signed => {
+            let signed = format.is_signed();
+            decompress_alpha_block(&block[0..8], signed, pixels, 0);
+            decompress_alpha_block(&block[8..16], signed, pixels, 1);
+            set_missing_channels(pixels, 2, signed);
+        },
+        TextureCompressionFormat::Bc6h | TextureCompressionFormat::Bc6hSigned => {
+            decompress_bc6h_block(block, format.is_signed(), pixels);
+        },
+        TextureCompressionFormat::Bc7 => {
+            decompress_bc7_block(block, pixels);
+        },
+    }
+}
+
+// Channels not stored in the block are set to 0, alpha to 1 (same as sampling compressed texture on GPU)
+fn set_missing_channels(pixels: &mut [[u8; 8]; 16], channel_count: usize, signed: bool) {
+    let one = match signed {
+        true => 127,
+        false => 255,
+    };
+    for pixel in pixels.iter_mut() {
+        pixel[channel_count..3].fill(0);
+        pixel[3] = one;
+    }
+}
+
+// --- BC1-BC5 ---
+
+fn decompress_color_block(block: &[u8], four_color_mode_only: bool, pixels: &mut [[u8; 8]; 16]) {
+    let color_0 = u16::from_le_bytes([block[0], block[1]]);
+    let color_1 = u16::from_le_bytes([block[2], block[3]]);
+    let indices = u32::from_le_bytes(block[4..8].try_into().unwrap());
+
+    let (r0, g0, b0) = expand_rgb565(color_0);
+    let (r1, g1, b1) = expand_rgb565(color_1);
+    let interpolate = |a: u32, b: u32, weight_a: u32, weight_b: u32| ((a * weight_a + b * weight_b) / (weight_a + weight_b)) as u8;
+
+    // Palette has 4 colors, or 3 colors and transparent black if first color is not greater than second one (BC1 only)
+    let mut palette = [[r0 as u8, g0 as u8, b0 as u8, 255], [r1 as u8, g1 as u8, b1 as u8, 255], [0; 4], [0; 4]];
+    if color_0 > color_1 || four_color_mode_only {
+        palette[2] = [interpolate(r0, r1, 2, 1), interpolate(g0, g1, 2, 1), interpolate(b0, b1, 2, 1), 255];
+        palette[3] = [interpolate(r0, r1, 1, 2), interpolate(g0, g1, 1, 2), interpolate(b0, b1, 1, 2), 255];
+    }
+    else {
+        palette[2] = [interpolate(r0, r1, 1, 1), interpolate(g0, g1, 1, 1), interpolate(b0, b1, 1, 1), 255];
+    }
+
+    for i in 0..16 {
+        let color = palette[((indices >> (2 * i)) & 0x3) as usize];
+        pixels[i][..4].copy_from_slice(&color);
+    }
+}
+
+fn expand_rgb565(color: u16) -> (u32, u32, u32) {
+    let r = ((color >> 11) & 0x1F) as u32;
+    let g = ((color >> 5) & 0x3F) as u32;
+    let b = (color & 0x1F) as u32;
+    ((r << 3) | (r >> 2), (g << 2) | (g >> 4), (b << 3) | (b >> 2))
+}
+
+// Decodes single channel block (alpha of BC3, channels of BC4 and BC5) into given channel
+fn decompress_alpha_block(block: &[u8], signed: bool, pixels: &mut [[u8; 8]; 16], channel: usize) {
+    let indices = u64::from_le_bytes(block[0..8].try_into().unwrap()) >> 16;
+
+    // Signed values are in range from -127 to 127 (-128 is treated as -127)
+    let (value_0, value_1, min, max) = match signed {
+        true => ((block[0] as i8).max(-127) as i32, (block[1] as i8).max(-127) as i32, -127, 127),
+        false => (block[0] as i32, block[1] as i32, 0, 255),
+    };
+
+    // Palette has 8 values, or 6 values and minimum and maximum if first value is not greater than second one
+    let mut palette = [value_0, value_1, 0, 0, 0, 0, min, max];
+    if value_0 > value_1 {
+        for i in 1..7 {
+            palette[i + 1] = ((7 - i as i32) * value_0 + i as i32 * value_1) / 7;
+        }
+    }
+    else {
+        for i in 1..5 {
+            palette[i + 1] = ((5 - i as i32) * value_0 + i as i32 * value_1) / 5;
+        }
+    }
+
+    for i in 0..16 {
+        pixels[i][channel] = palette[((indices >> (3 * i)) & 0x7) as usize] as u8;
+    }
+}
+
+// --- Bit reader ---
+
+struct BlockBitReader {
+    bits: u128,
+    position: u32,
+}
+
+impl BlockBitReader {
+    fn new(block: &[u8]) -> Self {
+        Self {
+            bits: u128::from_le_bytes(block[0..16].try_into().unwrap()),
+            position: 0,
+        }
+    }
+
+    fn read(&mut self, count: u32) -> u32 {
+        if count == 0 {
+            return 0;
+        }
+        let value = ((self.bits >> self.position) & ((1u128 << count) - 1)) as u32;
+        self.position += count;
+        value
+    }
+}
+
+// --- BC6H and BC7 shared tables ---
+
+// Partitions of the block into two subsets, bit N is set if pixel N belongs to the second subset
+const PARTITIONS_2: [u16; 64] = [
+    0xCCCC, 0x8888, 0xEEEE, 0xECC8, 0xC880, 0xFEEC, 0xFEC8, 0xEC80,
+    0xC800, 0xFFEC, 0xFE80, 0xE800, 0xFFE8, 0xFF00, 0xFFF0, 0xF000,
+    0xF710, 0x008E, 0x7100, 0x08CE, 0x008C, 0x7310, 0x3100, 0x8CCE,
+    0x088C, 0x3110, 0x6666, 0x366C, 0x17E8, 0x0FF0, 0x718E, 0x399C,
+    0xAAAA, 0xF0F0, 0x5A5A, 0x33CC, 0x3C3C, 0x55AA, 0x9696, 0xA55A,
+    0x73CE, 0x13C8, 0x324C, 0x3BDC, 0x6996, 0xC33C, 0x9966, 0x0660,
+    0x0272, 0x04E4, 0x4E40, 0x2720, 0xC936, 0x936C, 0x39C6, 0x639C,
+    0x9336, 0x9CC6, 0x817E, 0xE718, 0xCCF0, 0x0FCC, 0x7744, 0xEE22,
+];
+
+// Partitions of the block into three subsets, each row is one partition
+const PARTITIONS_3: [[u8; 16]; 64] = [
+    [0, 0, 1, 1, 0, 0, 1, 1, 0, 2, 2, 1, 2, 2, 2, 2], [0, 0, 0, 1, 0, 0, 1, 1, 2, 2, 1, 1, 2, 2, 2, 1],
+    [0, 0, 0, 0, 2, 0, 0, 1, 2, 2, 1, 1, 2, 2, 1, 1], [0, 2, 2, 2, 0, 0, 2, 2, 0, 0, 1, 1, 0, 1, 1, 1],
+    [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 2, 2, 1, 1, 2, 2], [0, 0, 1, 1, 0, 0, 1, 1, 0, 0, 2, 2, 0, 0, 2, 2],
+    [0, 0, 2, 2, 0, 0, 2, 2, 1, 1, 1, 1, 1, 1, 1, 1], [0, 0, 1, 1, 0, 0, 1, 1, 2, 2, 1, 1, 2, 2, 1, 1],
+    [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2], [0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 1, 2, 2, 2, 2],
+    [0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 2, 2, 2, 2], [0, 0, 1, 2, 0, 0, 1, 2, 0, 0, 1, 2, 0, 0, 1, 2],
+    [0, 1, 1, 2, 0, 1, 1, 2, 0, 1, 1, 2, 0, 1, 1, 2], [0, 1, 2, 2, 0, 1, 2, 2, 0, 1, 2, 2, 0, 1, 2, 2],
+    [0, 0, 1, 1, 0, 1, 1, 2, 1, 1, 2, 2, 1, 2, 2, 2], [0, 0, 1, 1, 2, 0, 0, 1, 2, 2, 0, 0, 2, 2, 2, 0],
+    [0, 0, 0, 1, 0, 0, 1, 1, 0, 1, 1, 2, 1, 1, 2, 2], [0, 1, 1, 1, 0, 0, 1, 1, 2, 0, 0, 1, 2, 2, 0, 0],
+    [0, 0, 0, 0, 1, 1, 2, 2, 1, 1, 2, 2, 1, 1, 2, 2], [0, 0, 2, 2, 0, 0, 2, 2, 0, 0, 2, 2, 1, 1, 1, 1],
+    [0, 1, 1, 1, 0, 1, 1, 1, 0, 2, 2, 2, 0, 2, 2, 2], [0, 0, 0, 1, 0, 0, 0, 1, 2, 2, 2, 1, 2, 2, 2, 1],
+    [0, 0, 0, 0, 0, 0, 1, 1, 0, 1, 2, 2, 0, 1, 2, 2], [0, 0, 0, 0, 1, 1, 0, 0, 2, 2, 1, 0, 2, 2, 1, 0],
+    [0, 1, 2, 2, 0, 1, 2, 2, 0, 0, 1, 1, 0, 0, 0, 0], [0, 0, 1, 2, 0, 0, 1, 2, 1, 1, 2, 2, 2, 2, 2, 2],
+    [0, 1, 1, 0, 1, 2, 2, 1, 1, 2, 2, 1, 0, 1, 1, 0], [0, 0, 0, 0, 0, 1, 1, 0, 1, 2, 2, 1, 1, 2, 2, 1],
+    [0, 0, 2, 2, 1, 1, 0, 2, 1, 1, 0, 2, 0, 0, 2, 2], [0, 1, 1, 0, 0, 1, 1, 0, 2, 0, 0, 2, 2, 2, 2, 2],
+    [0, 0, 1, 1, 0, 1, 2, 2, 0, 1, 2, 2, 0, 0, 1, 1], [0, 0, 0, 0, 2, 0, 0, 0, 2, 2, 1, 1, 2, 2, 2, 1],
+    [0, 0, 0, 0, 0, 0, 0, 2, 1, 1, 2, 2, 1, 2, 2, 2], [0, 2, 2, 2, 0, 0, 2, 2, 0, 0, 1, 2, 0, 0, 1, 1],
+    [0, 0, 1, 1, 0, 0, 1, 2, 0, 0, 2, 2, 0, 2, 2, 2], [0, 1, 2, 0, 0, 1, 2, 0, 0, 1, 2, 0, 0, 1, 2, 0],
+    [0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 0, 0, 0, 0], [0, 1, 2, 0, 1, 2, 0, 1, 2, 0, 1, 2, 0, 1, 2, 0],
+    [0, 1, 2, 0, 2, 0, 1, 2, 1, 2, 0, 1, 0, 1, 2, 0], [0, 0, 1, 1, 2, 2, 0, 0, 1, 1, 2, 2, 0, 0, 1, 1],
+    [0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 0, 0, 0, 0, 1, 1], [0, 1, 0, 1, 0, 1, 0, 1, 2, 2, 2, 2, 2, 2, 2, 2],
+    [0, 0, 0, 0, 0, 0, 0, 0, 2, 1, 2, 1, 2, 1, 2, 1], [0, 0, 2, 2, 1, 1, 2, 2, 0, 0, 2, 2, 1, 1, 2, 2],
+    [0, 0, 2, 2, 0, 0, 1, 1, 0, 0, 2, 2, 0, 0, 1, 1], [0, 2, 2, 0, 1, 2, 2, 1, 0, 2, 2, 0, 1, 2, 2, 1],
+    [0, 1, 0, 1, 2, 2, 2, 2, 2, 2, 2, 2, 0, 1, 0, 1], [0, 0, 0, 0, 2, 1, 2, 1, 2, 1, 2, 1, 2, 1, 2, 1],
+    [0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 2, 2, 2, 2], [0, 2, 2, 2, 0, 1, 1, 1, 0, 2, 2, 2, 0, 1, 1, 1],
+    [0, 0, 0, 2, 1, 1, 1, 2, 0, 0, 0, 2, 1, 1, 1, 2], [0, 0, 0, 0, 2, 1, 1, 2, 2, 1, 1, 2, 2, 1, 1, 2],
+    [0, 2, 2, 2, 0, 1, 1, 1, 0, 1, 1, 1, 0, 2, 2, 2], [0, 0, 0, 2, 1, 1, 1, 2, 1, 1, 1, 2, 0, 0, 0, 2],
+    [0, 1, 1, 0, 0, 1, 1, 0, 0, 1, 1, 0, 2, 2, 2, 2], [0, 0, 0, 0, 0, 0, 0, 0, 2, 1, 1, 2, 2, 1, 1, 2],
+    [0, 1, 1, 0, 0, 1, 1, 0, 2, 2, 2, 2, 2, 2, 2, 2], [0, 0, 2, 2, 0, 0, 1, 1, 0, 0, 1, 1, 0, 0, 2, 2],
+    [0, 0, 2, 2, 1, 1, 2, 2, 1, 1, 2, 2, 0, 0, 2, 2], [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2, 1, 1, 2],
+    [0, 0, 0, 2, 0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0, 1], [0, 2, 2, 2, 1, 2, 2, 2, 0, 2, 2, 2, 1, 2, 2, 2],
+    [0, 1, 0, 1, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2], [0, 1, 1, 1, 2, 0, 1, 1, 2, 2, 0, 1, 2, 2, 2, 0],
+];
+
+// Pixels whose indices have implicit most significant bit equal to 0 (first pixel is anchor of the first subset)
+const ANCHORS_2_OF_2: [u8; 64] = [
+    15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15,
+    15, 2, 8, 2, 2, 8, 8, 15, 2, 8, 2, 2, 8, 8, 2, 2,
+    15, 15, 6, 8, 2, 8, 15, 15, 2, 8, 2, 2, 2, 15, 15, 6,
+    6, 2, 6, 8, 15, 15, 2, 2, 15, 15, 15, 15, 15, 2, 2, 15,
+];
+
+const ANCHORS_2_OF_3: [u8; 64] = [
+    3, 3, 15, 15, 8, 3, 15, 15, 8, 8, 6, 6, 6, 5, 3, 3,
+    3, 3, 8, 15, 3, 3, 6, 10, 5, 8, 8, 6, 8, 5, 15, 15,
+    8, 15, 3, 5, 6, 10, 8, 15, 15, 3, 15, 5, 15, 15, 15, 15,
+    3, 15, 5, 5, 5, 8, 5, 10, 5, 10, 8, 13, 15, 12, 3, 3,
+];
+
+const ANCHORS_3_OF_3: [u8; 64] = [
+    15, 8, 8, 3, 15, 15, 3, 8, 15, 15, 15, 15, 15, 15, 15, 8,
+    15, 8, 15, 3, 15, 8, 15, 8, 3, 15, 6, 10, 15, 15, 10, 8,
+    15, 3, 15, 10, 10, 8, 9, 10, 6, 15, 8, 15, 3, 6, 6, 8,
+    15, 3, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 3, 15, 15, 8,
+];
+
+const WEIGHTS_2: [u32; 4] = [0, 21, 43, 64];
+const WEIGHTS_3: [u32; 8] = [0, 9, 18, 27, 37, 46, 55, 64];
+const WEIGHTS_4: [u32; 16] = [0, 4, 9, 13, 17, 21, 26, 30, 34, 38, 43, 47, 51, 55, 60, 64];
+
+fn get_weights(index_bits: u32) -> &'static [u32] {
+    match index_bits {
+        2 => &WEIGHTS_2,
+        3 => &WEIGHTS_3,
+        _ => &WEIGHTS_4,
+    }
+}
+
+fn get_subset(subset_count: usize, partition: usize, pixel: usize) -> usize {
+    match subset_count {
+        2 => ((PARTITIONS_2[partition] >> pixel) & 1) as usize,
+        3 => PARTITIONS_3[partition][pixel] as usize,
+        _ => 0,
+    }
+}
+
+fn is_anchor(subset_count: usize, partition: usize, pixel: usize) -> bool {
+    match subset_count {
+        2 => pixel == 0 || pixel == ANCHORS_2_OF_2[partition] as usize,
+        3 => pixel == 0 || pixel == ANCHORS_2_OF_3[partition] as usize || pixel == ANCHORS_3_OF_3[partition] as usize,
+        _ => pixel == 0,
+    }
+}
+
+// Reads indices of all pixels, anchor pixels have one bit less
+fn read_indices(reader: &mut BlockBitReader, index_bits: u32, subset_count: usize, partition: usize) -> [u32; 16] {
+    let mut indices = [0u32; 16];
+    for (pixel, index) in indices.iter_mut().enumerate() {
+        let bit_count = match is_anchor(subset_count, partition, pixel) {
+            true => index_bits - 1,
+            false => index_bits,
+        };
+        *index = reader.read(bit_count);
+    }
+    indices
+}
+
+// --- BC7 ---
+
+struct Bc7Mode {
+    subset_count: usize,
+    partition_bits: u32,
+    rotation_bits: u32,
+    index_selection_bits: u32,
+    color_bits: u32,
+    alpha_bits: u32,
+    endpoint_p_bits: bool, // Unique P-bit for each endpoint
+    shared_p_bits: bool, // P-bit shared by both endpoints of a subset
+    index_bits: u32,
+    secondary_index_bits: u32,
+}
+
+const BC7_MODES: [Bc7Mode; 8] = [
+    Bc7Mode { subset_count: 3, partition_bits: 4, rotation_bits: 0, index_selection_bits: 0, color_bits: 4, alpha_bits: 0, endpoint_p_bits: true, shared_p_bits: false, index_bits: 3, secondary_index_bits: 0 },
+    Bc7Mode { subset_count: 2, partition_bits: 6, rotation_bits: 0, index_selection_bits: 0, color_bits: 6, alpha_bits: 0, endpoint_p_bits: false, shared_p_bits: true, index_bits: 3, secondary_index_bits: 0 },
+    Bc7Mode { subset_count: 3, partition_bits: 6, rotation_bits: 0, index_selection_bits: 0, color_bits: 5, alpha_bits: 0, endpoint_p_bits: false, shared_p_bits: false, index_bits: 2, secondary_index_bits: 0 },
+    Bc7Mode { subset_count: 2, partition_bits: 6, rotation_bits: 0, index_selection_bits: 0, color_bits: 7, alpha_bits: 0, endpoint_p_bits: true, shared_p_bits: false, index_bits: 2, secondary_index_bits: 0 },
+    Bc7Mode { subset_count: 1, partition_bits: 0, rotation_bits: 2, index_selection_bits: 1, color_bits: 5, alpha_bits: 6, endpoint_p_bits: false, shared_p_bits: false, index_bits: 2, secondary_index_bits: 3 },
+    Bc7Mode { subset_count: 1, partition_bits: 0, rotation_bits: 2, index_selection_bits: 0, color_bits: 7, alpha_bits: 8, endpoint_p_bits: false, shared_p_bits: false, index_bits: 2, secondary_index_bits: 2 },
+    Bc7Mode { subset_count: 1, partition_bits: 0, rotation_bits: 0, index_selection_bits: 0, color_bits: 7, alpha_bits: 7, endpoint_p_bits: true, shared_p_bits: false, index_bits: 4, secondary_index_bits: 0 },
+    Bc7Mode { subset_count: 2, partition_bits: 6, rotation_bits: 0, index_selection_bits: 0, color_bits: 5, alpha_bits: 5, endpoint_p_bits: true, shared_p_bits: false, index_bits: 2, secondary_index_bits: 0 },
+];
+
+fn decompress_bc7_block(block: &[u8], pixels: &mut [[u8; 8]; 16]) {
+    let mut reader = BlockBitReader::new(block);
+
+    // Mode is the number of zero bits before first one bit, blocks with reserved mode are transparent black
+    let mode_index = match block[0].trailing_zeros() {
+        v if v < 8 => v as usize,
+        _ => {
+            for pixel in pixels.iter_mut() {
+                pixel[..4].copy_from_slice(&[0; 4]);
+            }
+            return;
+        },
+    };
+    reader.read(mode_index as u32 + 1);
+    let mode = &BC7_MODES[mode_index];
+
+    let partition = reader.read(mode.partition_bits) as usize;
+    let rotation = reader.read(mode.rotation_bits);
+    let index_selection = reader.read(mode.index_selection_bits);
+
+    // Read endpoints (all red values first, then green, blue and alpha)
+    let endpoint_count = mode.subset_count * 2;
+    let mut endpoints = [[0u32; 4]; 6];
+    for channel in 0..4 {
+        let bits = match channel {
+            3 => mode.alpha_bits,
+            _ => mode.color_bits,
+        };
+        for endpoint in endpoints.iter_mut().take(endpoint_count) {
+            endpoint[channel] = reader.read(bits);
+        }
+    }
+
+    // Read P-bits and add them as least significant bits of endpoints
+    let mut p_bits = [0u32; 6];
+    if mode.endpoint_p_bits {
+        for p_bit in p_bits.iter_mut().take(endpoint_count) {
+            *p_bit = reader.read(1);
+        }
+    }
+    if mode.shared_p_bits {
+        for subset in 0..mode.subset_count {
+            let p_bit = reader.read(1);
+            p_bits[subset * 2] = p_bit;
+            p_bits[subset * 2 + 1] = p_bit;
+        }
+    }
+    let has_p_bits = mode.endpoint_p_bits || mode.shared_p_bits;
+
+    // Expand endpoints to 8 bits by replicating their most significant bits
+    for (endpoint_index, endpoint) in endpoints.iter_mut().take(endpoint_count).enumerate() {
+        for (channel, value) in endpoint.iter_mut().enumerate() {
+            let mut bits = match channel {
+                3 => mode.alpha_bits,
+                _ => mode.color_bits,
+            };
+            if bits == 0 {
+                *value = 255;
+                continue;
+            }
+            if has_p_bits {
+                *value = (*value << 1) | p_bits[endpoint_index];
+                bits += 1;
+            }
+            *value = (*value << (8 - bits)) | (*value >> (2 * bits - 8));
+        }
+    }
+
+    // Read indices, modes with secondary indices use them for alpha (or for color if index selection bit is set)
+    let indices = read_indices(&mut reader, mode.index_bits, mode.subset_count, partition);
+    let secondary_indices = match mode.secondary_index_bits {
+        0 => indices,
+        bits => read_indices(&mut reader, bits, 1, 0),
+    };
+    let (color_indices, color_index_bits, alpha_indices, alpha_index_bits) = match (mode.secondary_index_bits, index_selection) {
+        (0, _) => (indices, mode.index_bits, indices, mode.index_bits),
+        (_, 0) => (indices, mode.index_bits, secondary_indices, mode.secondary_index_bits),
+        _ => (secondary_indices, mode.secondary_index_bits, indices, mode.index_bits),
+    };
+    let color_weights = get_weights(color_index_bits);
+    let alpha_weights = get_weights(alpha_index_bits);
+
+    // Interpolate endpoints
+    for (pixel_index, pixel) in pixels.iter_mut().enumerate() {
+        let subset = get_subset(mode.subset_count, partition, pixel_index);
+        let (endpoint_0, endpoint_1) = (endpoints[subset * 2], endpoints[subset * 2 + 1]);
+        let interpolate = |channel: usize, weight: u32| ((64 - weight) * endpoint_0[channel] + weight * endpoint_1[channel] + 32) >> 6;
+
+        let color_weight = color_weights[color_indices[pixel_index] as usize];
+        let alpha_weight = alpha_weights[alpha_indices[pixel_index] as usize];
+        let mut color = [
+            interpolate(0, color_weight) as u8,
+            interpolate(1, color_weight) as u8,
+            interpolate(2, color_weight) as u8,
+            interpolate(3, alpha_weight) as u8,
+        ];
+
+        // Rotation swaps alpha with one of the color channels
+        if rotation > 0 {
+            color.swap(3, rotation as usize - 1);
+        }
+        pixel[..4].copy_from_slice(&color);
+    }
+}
+
+// --- BC6H ---
+
+// Fields of BC6H block: endpoints of the first subset (W, X), endpoints of the second subset (Y, Z) and partition (D)
+const RW: u8 = 0; const GW: u8 = 1; const BW: u8 = 2;
+const RX: u8 = 3; const GX: u8 = 4; const BX: u8 = 5;
+const RY: u8 = 6; const GY: u8 = 7; const BY: u8 = 8;
+const RZ: u8 = 9; const GZ: u8 = 10; const BZ: u8 = 11;
+const D: u8 = 12;
+
+struct Bc6hMode {
+    transformed: bool, // Endpoints other than the first one are stored as deltas
+    subset_count: usize,
+    endpoint_bits: u32,
+    delta_bits: [u32; 3],
+    layout: &'static [(u8, u8, u8)], // Field, first bit and bit count of each part of the block (after mode bits)
+}
+
+const BC6H_MODES: [Bc6hMode; 14] = [
+    Bc6hMode { transformed: true, subset_count: 2, endpoint_bits: 10, delta_bits: [5, 5, 5], layout: &[
+        (GY, 4, 1), (BY, 4, 1), (BZ, 4, 1), (RW, 0, 10), (GW, 0, 10), (BW, 0, 10), (RX, 0, 5), (GZ, 4, 1), (GY, 0, 4), (GX, 0, 5), (BZ, 0, 1),
+        (GZ, 0, 4), (BX, 0, 5), (BZ, 1, 1), (BY, 0, 4), (RY, 0, 5), (BZ, 2, 1), (RZ, 0, 5), (BZ, 3, 1), (D, 0, 5),
+    ] },
+    Bc6hMode { transformed: true, subset_count: 2, endpoint_bits: 7, delta_bits: [6, 6, 6], layout: &[
+        (GY, 5, 1), (GZ, 4, 1), (GZ, 5, 1), (RW, 0, 7), (BZ, 0, 1), (BZ, 1, 1), (BY, 4, 1), (GW, 0, 7), (BY, 5, 1), (BZ, 2, 1), (GY, 4, 1),
+        (BW, 0, 7), (BZ, 3, 1), (BZ, 5, 1), (BZ, 4, 1), (RX, 0, 6), (GY, 0, 4), (GX, 0, 6), (GZ, 0, 4), (BX, 0, 6), (BY, 0, 4), (RY, 0, 6),
+        (RZ, 0, 6), (D, 0, 5),
+    ] },
+    Bc6hMode { transformed: true, subset_count: 2, endpoint_bits: 11, delta_bits: [5, 4, 4], layout: &[
+        (RW, 0, 10), (GW, 0, 10), (BW, 0, 10), (RX, 0, 5), (RW, 10, 1), (GY, 0, 4), (GX, 0, 4), (GW, 10, 1), (BZ, 0, 1), (GZ, 0, 4), (BX, 0, 4),
+        (BW, 10, 1), (BZ, 1, 1), (BY, 0, 4), (RY, 0, 5), (BZ, 2, 1), (RZ, 0, 5), (BZ, 3, 1), (D, 0, 5),
+    ] },
+    Bc6hMode { transformed: true, subset_count: 2, endpoint_bits: 11, delta_bits: [4, 5, 4], layout: &[
+        (RW, 0, 10), (GW, 0, 10), (BW, 0, 10), (RX, 0, 4), (RW, 10, 1), (GZ, 4, 1), (GY, 0, 4), (GX, 0, 5), (GW, 10, 1), (GZ, 0, 4), (BX, 0, 4),
+        (BW, 10, 1), (BZ, 1, 1), (BY, 0, 4), (RY, 0, 4), (BZ, 0, 1), (BZ, 2, 1), (RZ, 0, 4), (GY, 4, 1), (BZ, 3, 1), (D, 0, 5),
+    ] },
+    Bc6hMode { transformed: true, subset_count: 2, endpoint_bits: 11, delta_bits: [4, 4, 5], layout: &[
+        (RW, 0, 10), (GW, 0, 10), (BW, 0, 10), (RX, 0, 4), (RW, 10, 1), (BY, 4, 1), (GY, 0, 4), (GX, 0, 4), (GW, 10, 1), (BZ, 0, 1), (GZ, 0, 4),
+        (BX, 0, 5), (BW, 10, 1), (BY, 0, 4), (RY, 0, 4), (BZ, 1, 1), (BZ, 2, 1), (RZ, 0, 4), (BZ, 4, 1), (BZ, 3, 1), (D, 0, 5),
+    ] },
+    Bc6hMode { transformed: true, subset_count: 2, endpoint_bits: 9, delta_bits: [5, 5, 5], layout: &[
+        (RW, 0, 9), (BY, 4, 1), (GW, 0, 9), (GY, 4, 1), (BW, 0, 9), (BZ, 4, 1), (RX, 0, 5), (GZ, 4, 1), (GY, 0, 4), (GX, 0, 5), (BZ, 0, 1),
+        (GZ, 0, 4), (BX, 0, 5), (BZ, 1, 1), (BY, 0, 4), (RY, 0, 5), (BZ, 2, 1), (RZ, 0, 5), (BZ, 3, 1), (D, 0, 5),
+    ] },
+    Bc6hMode { transformed: true, subset_count: 2, endpoint_bits: 8, delta_bits: [6, 5, 5], layout: &[
+        (RW, 0, 8), (GZ, 4, 1), (BY, 4, 1), (GW, 0, 8), (BZ, 2, 1), (GY, 4, 1), (BW, 0, 8), (BZ, 3, 1), (BZ, 4, 1), (RX, 0, 6), (GY, 0, 4),
+        (GX, 0, 5), (BZ, 0, 1), (GZ, 0, 4), (BX, 0, 5), (BZ, 1, 1), (BY, 0, 4), (RY, 0, 6), (RZ, 0, 6), (D, 0, 5),
+    ] },
+    Bc6hMode { transformed: true, subset_count: 2, endpoint_bits: 8, delta_bits: [5, 6, 5], layout: &[
+        (RW, 0, 8), (BZ, 0, 1), (BY, 4, 1), (GW, 0, 8), (GY, 5, 1), (GY, 4, 1), (BW, 0, 8), (GZ, 5, 1), (BZ, 4, 1), (RX, 0, 5), (GZ, 4, 1),
+        (GY, 0, 4), (GX, 0, 6), (GZ, 0, 4), (BX, 0, 5), (BZ, 1, 1), (BY, 0, 4), (RY, 0, 5), (BZ, 2, 1), (RZ, 0, 5), (BZ, 3, 1), (D, 0, 5),
+    ] },
+    Bc6hMode { transformed: true, subset_count: 2, endpoint_bits: 8, delta_bits: [5, 5, 6], layout: &[
+        (RW, 0, 8), (BZ, 1, 1), (BY, 4, 1), (GW, 0, 8), (BY, 5, 1), (GY, 4, 1), (BW, 0, 8), (BZ, 5, 1), (BZ, 4, 1), (RX, 0, 5), (GZ, 4, 1),
+        (GY, 0, 4), (GX, 0, 5), (BZ, 0, 1), (GZ, 0, 4), (BX, 0, 6), (BY, 0, 4), (RY, 0, 5), (BZ, 2, 1), (RZ, 0, 5), (BZ, 3, 1), (D, 0, 5),
+    ] },
+    Bc6hMode { transformed: false, subset_count: 2, endpoint_bits: 6, delta_bits: [6, 6, 6], layout: &[
+        (RW, 0, 6), (GZ, 4, 1), (BZ, 0, 1), (BZ, 1, 1), (BY, 4, 1), (GW, 0, 6), (GY, 5, 1), (BY, 5, 1), (BZ, 2, 1), (GY, 4, 1), (BW, 0, 6),
+        (GZ, 5, 1), (BZ, 3, 1), (BZ, 5, 1), (BZ, 4, 1), (RX, 0, 6), (GY, 0, 4), (GX, 0, 6), (GZ, 0, 4), (BX, 0, 6), (BY, 0, 4), (RY, 0, 6),
+        (RZ, 0, 6), (D, 0, 5),
+    ] },
+    Bc6hMode { transformed: false, subset_count: 1, endpoint_bits: 10, delta_bits: [10, 10, 10], layout: &[
+        (RW, 0, 10), (GW, 0, 10), (BW, 0, 10), (RX, 0, 10), (GX, 0, 10), (BX, 0, 10),
+    ] },
+    Bc6hMode { transformed: true, subset_count: 1, endpoint_bits: 11, delta_bits: [9, 9, 9], layout: &[
+        (RW, 0, 10), (GW, 0, 10), (BW, 0, 10), (RX, 0, 9), (RW, 10, 1), (GX, 0, 9), (GW, 10, 1), (BX, 0, 9), (BW, 10, 1),
+    ] },
+    Bc6hMode { transformed: true, subset_count: 1, endpoint_bits: 12, delta_bits: [8, 8, 8], layout: &[
+        (RW, 0, 10), (GW, 0, 10), (BW, 0, 10), (RX, 0, 8), (RW, 11, 1), (RW, 10, 1), (GX, 0, 8), (GW, 11, 1), (GW, 10, 1), (BX, 0, 8),
+        (BW, 11, 1), (BW, 10, 1),
+    ] },
+    Bc6hMode { transformed: true, subset_count: 1, endpoint_bits: 16, delta_bits: [4, 4, 4], layout: &[
+        (RW, 0, 10), (GW, 0, 10), (BW, 0, 10), (RX, 0, 4), (RW, 15, 1), (RW, 14, 1), (RW, 13, 1), (RW, 12, 1), (RW, 11, 1), (RW, 10, 1),
+        (GX, 0, 4), (GW, 15, 1), (GW, 14, 1), (GW, 13, 1), (GW, 12, 1), (GW, 11, 1), (GW, 10, 1), (BX, 0, 4), (BW, 15, 1), (BW, 14, 1),
+        (BW, 13, 1), (BW, 12, 1), (BW, 11, 1), (BW, 10, 1),
+    ] },
+];
+
+fn get_bc6h_mode_index(mode_bits: u32) -> Option<usize> {
+    match mode_bits {
+        0x00 => Some(0),
+        0x01 => Some(1),
+        0x02 => Some(2),
+        0x06 => Some(3),
+        0x0A => Some(4),
+        0x0E => Some(5),
+        0x12 => Some(6),
+        0x16 => Some(7),
+        0x1A => Some(8),
+        0x1E => Some(9),
+        0x03 => Some(10),
+        0x07 => Some(11),
+        0x0B => Some(12),
+        0x0F => Some(13),
+        _ => None,
+    }
+}
+
+fn sign_extend(value: i32, bits: u32) -> i32 {
+    let shift = 32 - bits;
+    (value << shift) >> shift
+}
+
+fn unquantize_bc6h(value: i32, bits: u32, signed: bool) -> i32 {
+    match signed {
+        false => {
+            if bits >= 15 || value == 0 {
+                value
+            }
+            else if value == (1 << bits) - 1 {
+                0xFFFF
+            }
+            else {
+                ((value << 16) + 0x8000) >> bits
+            }
+        },
+        true => {
+            if bits >= 16 {
+                return value;
+            }
+            let magnitude = value.abs();
+            let unquantized = if magnitude == 0 {
+                0
+            }
+            else if magnitude >= (1 << (bits - 1)) - 1 {
+                0x7FFF
+            }
+            else {
+                ((magnitude << 15) + 0x4000) >> (bits - 1)
+            };
+            match value < 0 {
+                true => -unquantized,
+                false => unquantized,
+            }
+        },
+    }
+}
+
+// Scales interpolated value to the range of half float and returns its bits
+fn finish_unquantize_bc6h(value: i32, signed: bool) -> u16 {
+    match signed {
+        false => ((value * 31) >> 6) as u16,
+        true => match value < 0 {
+            true => 0x8000 | (((-value) * 31) >> 5) as u16,
+            false => ((value * 31) >> 5) as u16,
+        },
+    }
+}
+
+fn decompress_bc6h_block(block: &[u8], signed: bool, pixels: &mut [[u8; 8]; 16]) {
+    let mut reader = BlockBitReader::new(block);
+
+    // Read mode (2 bits for first two modes, 5 bits for the others), blocks with reserved mode are black
+    let mut mode_bits = reader.read(2);
+    if mode_bits > 1 {
+        mode_bits |= reader.read(3) << 2;
+    }
+    let mode = match get_bc6h_mode_index(mode_bits) {
+        Some(v) => &BC6H_MODES[v],
+        None => {
+            for pixel in pixels.iter_mut() {
+                pixel.copy_from_slice(&[0, 0, 0, 0, 0, 0, 0x00, 0x3C]);
+            }
+            return;
+        },
+    };
+
+    // Read fields
+    let mut fields = [0i32; 13];
+    for (field, first_bit, bit_count) in mode.layout.iter() {
+        fields[*field as usize] |= (reader.read(*bit_count as u32) as i32) << first_bit;
+    }
+    let partition = fields[D as usize] as usize;
+    let mut endpoints = [
+        [fields[RW as usize], fields[GW as usize], fields[BW as usize]],
+        [fields[RX as usize], fields[GX as usize], fields[BX as usize]],
+        [fields[RY as usize], fields[GY as usize], fields[BY as usize]],
+        [fields[RZ as usize], fields[GZ as usize], fields[BZ as usize]],
+    ];
+    let endpoint_count = mode.subset_count * 2;
+
+    // Convert deltas to endpoints and sign extend them
+    let endpoint_mask = (1i64 << mode.endpoint_bits) as i32 - 1;
+    for channel in 0..3 {
+        if signed {
+            endpoints[0][channel] = sign_extend(endpoints[0][channel], mode.endpoint_bits);
+        }
+        for endpoint in endpoints.iter_mut().take(endpoint_count).skip(1) {
+            if mode.transformed {
+                let delta = sign_extend(endpoint[channel], mode.delta_bits[channel]);
+                endpoint[channel] = (fields[channel] + delta) & endpoint_mask;
+            }
+            if signed {
+                endpoint[channel] = sign_extend(endpoint[channel], mode.endpoint_bits);
+            }
+        }
+    }
+    for endpoint in endpoints.iter_mut().take(endpoint_count) {
+        for value in endpoint.iter_mut() {
+            *value = unquantize_bc6h(*value, mode.endpoint_bits, signed);
+        }
+    }
+
+    // Read indices and interpolate endpoints
+    let index_bits = match mode.subset_count {
+        1 => 4,
+        _ => 3,
+    };
+    let weights = get_weights(index_bits);
+    let indices = read_indices(&mut reader, index_bits, mode.subset_count, partition);
+    for (pixel_index, pixel) in pixels.iter_mut().enumerate() {
+        let subset = get_subset(mode.subset_count, partition, pixel_index);
+        let weight = weights[indices[pixel_index] as usize] as i32;
+        for channel in 0..3 {
+            let value = ((64 - weight) * endpoints[subset * 2][channel] + weight * endpoints[subset * 2 + 1][channel] + 32) >> 6;
+            let half = finish_unquantize_bc6h(value, signed);
+            pixel[channel * 2..channel * 2 + 2].copy_from_slice(&half.to_le_bytes());
+        }
+        pixel[6..8].copy_from_slice(&0x3C00u16.to_le_bytes()); // Alpha is always 1
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // Writes bits from least significant one, the same way they are read from blocks
+    struct BlockBitWriter {
+        bits: u128,
+        position: u32,
+    }
+
+    impl BlockBitWriter {
+        fn new() -> Self {
+            Self { bits: 0, position: 0 }
+        }
+
+        fn write(&mut self, value: u32, count: u32) {
+            self.bits |= ((value as u128) & ((1u128 << count) - 1)) << self.position;
+            self.position += count;
+        }
+
+        fn finish(self) -> [u8; 16] {
+            assert_eq!(self.position, 128);
+            self.bits.to_le_bytes()
+        }
+    }
+
+    #[test]
+    fn texture_compression_partition_anchors() {
+        // Anchor pixel of each subset has to belong to that subset
+        for partition in 0..64 {
+            assert_eq!(get_subset(2, partition, ANCHORS_2_OF_2[partition] as usize), 1);
+            assert_eq!(get_subset(3, partition, ANCHORS_2_OF_3[partition] as usize), 1);
+            assert_eq!(get_subset(3, partition, ANCHORS_3_OF_3[partition] as usize), 2);
+            assert_eq!(get_subset(2, partition, 0), 0);
+            assert_eq!(get_subset(3, partition, 0), 0);
+        }
+    }
+
+    #[test]
+    fn texture_compression_bc6h_layouts() {
+        // Each mode uses all bits of the block
+        for mode in BC6H_MODES.iter() {
+            let mode_bits = match mode.subset_count {
+                2 if mode.endpoint_bits == 10 || mode.endpoint_bits == 7 => 2,
+                _ => 5,
+            };
+            let index_bits = match mode.subset_count {
+                1 => 63,
+                _ => 46,
+            };
+            let layout_bits = mode.layout.iter().map(|v| v.2 as u32).sum::<u32>();
+            assert_eq!(mode_bits + layout_bits + index_bits, 128);
+        }
+    }
+
+    #[test]
+    fn texture_compression_bc1_bc3() {
+        // Red and blue endpoints, pixels use colors 0, 1, 2, 3 in order
+        let mut block = [0u8; 8];
+        block[0..2].copy_from_slice(&0xF800u16.to_le_bytes());
+        block[2..4].copy_from_slice(&0x001Fu16.to_le_bytes());
+        block[4..8].copy_from_slice(&0xE4E4E4E4u32.to_le_bytes());
+        let pixels = decompress_texture(TextureCompressionFormat::Bc1, 4, 4, &block).unwrap();
+        assert_eq!(&pixels[0..16], &[255, 0, 0, 255, 0, 0, 255, 255, 170, 0, 85, 255, 85, 0, 170, 255]);
+
+        // Swapped endpoints use three colors and transparent black
+        block[0..4].copy_from_slice(&[0x1F, 0x00, 0x00, 0xF8]);
+        let pixels = decompress_texture(TextureCompressionFormat::Bc1, 4, 4, &block).unwrap();
+        assert_eq!(&pixels[8..16], &[127, 0, 127, 255, 0, 0, 0, 0]);
+
+        // Alpha block with all pixels using second endpoint
+        let mut block = [0u8; 16];
+        block[0] = 10;
+        block[1] = 200;
+        block[2..8].copy_from_slice(&[0x49, 0x92, 0x24, 0x49, 0x92, 0x24]);
+        let pixels = decompress_texture(TextureCompressionFormat::Bc3, 4, 4, &block).unwrap();
+        assert!(pixels.chunks(4).all(|v| v[3] == 200));
+    }
+
+    #[test]
+    fn texture_compression_bc7() {
+        // Mode 6 block with single color endpoints and all indices set to 0
+        let mut writer = BlockBitWriter::new();
+        writer.write(1 << 6, 7);
+        for value in [100, 100, 50, 50, 25, 25, 127, 127] {
+            writer.write(value, 7);
+        }
+        writer.write(1, 1);
+        writer.write(0, 1);
+        writer.write(0, 63);
+        let block = writer.finish();
+
+        // Endpoint with P-bit is 8 bit value
+        let pixels = decompress_texture(TextureCompressionFormat::Bc7, 4, 4, &block).unwrap();
+        assert!(pixels.chunks(4).all(|v| v == [201, 101, 51, 255]));
+    }
+
+    #[test]
+    fn texture_compression_bc6h() {
+        // Mode 11 block with the same endpoints, maximal value is the largest finite half float
+        let mut writer = BlockBitWriter::new();
+        writer.write(0x03, 5);
+        for value in [1023, 0, 512, 1023, 0, 512] {
+            writer.write(value, 10);
+        }
+        writer.write(0, 63);
+        let block = writer.finish();
+
+        let pixels = decompress_texture(TextureCompressionFormat::Bc6h, 4, 4, &block).unwrap();
+        let halfs = pixels[0..8].chunks(2).map(|v| u16::from_le_bytes([v[0], v[1]])).collect::<Vec<u16>>();
+        assert_eq!(halfs, [0x7BFF, 0, 0x3E0F, 0x3C00]);
+    }
+
+    #[test]
+    fn texture_compression_partial_blocks() {
+        // Texture smaller than a block uses only part of it
+        let block = [0xFFu8, 0xFF, 0xFF, 0xFF, 0, 0, 0, 0];
+        let pixels = decompress_texture(TextureCompressionFormat::Bc1, 2, 3, &block).unwrap();
+        assert_eq!(pixels.len(), 2 * 3 * 4);
+        assert!(pixels.iter().all(|v| *v == 255));
+        assert!(decompress_texture(TextureCompressionFormat::Bc1, 8, 8, &block).is_err());
+    }
+}
@@ -1,30 +1,42 @@
 // This script is run by cargo on build
-use std::process::Command;
+#[path = "src/resources/shader_compiler.rs"]
+#[allow(dead_code)] // Only WGSL shaders are validated here
+mod shader_compiler;
 
-fn main() -> anyhow::Result<()> {
+use std::{ fs, path::Path };
+use anyhow::{ Context, Result };
+
+fn main() -> Result<()> {
 
     // This tells cargo to rerun this script if something in /res/shaders changes.
-    println!("cargo:rerun-if-changed=res/shaders/*");
+    println!("cargo:rerun-if-changed=res/shaders");
 
-    // Compile GLSL shaders into SPIR-V
-    let shader_compiler_path = "res\\shaders\\glslc.exe";
-    let shader_directory_path = "res\\shaders";
-    let built_shader_directory_path = "res\\shaders\\built";
+    // Validate WGSL shaders of the engine (shader errors fail the build and point to file and line of the problem)
+    // Shaders are embedded into executable as WGSL source, so nothing is written here
+    let shader_directory_path = Path::new("res").join("shaders");
 
-    let shaders_to_compile = [
-        "master.vert", 
+    let shaders_to_validate = [
+        "master.vert",
         "master.frag",
+        "skybox.vert",
+        "skybox.frag",
+        "clear.vert",
+        "clear.frag",
+        "post_process.vert",
+        "bloom_prefilter.frag",
+        "bloom_downsample.frag",
+        "bloom_upsample.frag",
+        "composite.frag",
+        "fxaa.frag",
         ];
 
-    for shader_to_compile in shaders_to_compile.iter() {
-        let compiled_shader_file_name = format!("{}{}", shader_to_compile, ".spv");
-        let command = format!("{} {}\\{} -o {}\\{}", shader_compiler_path, shader_directory_path, shader_to_compile, built_shader_directory_path, compiled_shader_file_name);
+    for shader_to_validate in shaders_to_validate.iter() {
+        let shader_path = shader_directory_path.join(format!("{}{}", shader_to_validate, ".wgsl"));
+        println!("cargo:rerun-if-changed={}", shader_path.display());
 
-        Command::new("cmd")
-        .args(["/C", command.as_ref()])
-        .output()
-        .expect(format!("Failed to compile shader: {}", shader_to_compile).as_ref());
+        let source = fs::read_to_string(&shader_path).context(format!("Failed to read shader: {}", shader_path.display()))?;
+        shader_compiler::parse_wgsl(&source, &shader_path.display().to_string())?;
     }
 
     Ok(())
-}
\ No newline at end of file
+}
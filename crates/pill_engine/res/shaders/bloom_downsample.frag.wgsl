@@ -0,0 +1,17 @@
+// Input source data (previous bloom mip level)
+@group(0) @binding(0) var source_texture: texture_2d<f32>;
+@group(0) @binding(1) var source_sampler: sampler;
+
+@fragment
+fn main(
+    // Input vertex data
+    @location(0) texture_coordinates: vec2<f32>,
+) -> @location(0) vec4<f32> {
+    // Average 4x4 texels of the source with 4 bilinear samples
+    let texel_size = 1.0 / vec2<f32>(textureDimensions(source_texture));
+    var color = textureSample(source_texture, source_sampler, texture_coordinates + vec2<f32>(-1.0, -1.0) * texel_size).rgb;
+    color += textureSample(source_texture, source_sampler, texture_coordinates + vec2<f32>(1.0, -1.0) * texel_size).rgb;
+    color += textureSample(source_texture, source_sampler, texture_coordinates + vec2<f32>(-1.0, 1.0) * texel_size).rgb;
+    color += textureSample(source_texture, source_sampler, texture_coordinates + vec2<f32>(1.0, 1.0) * texel_size).rgb;
+    return vec4<f32>(color * 0.25, 1.0);
+}
@@ -0,0 +1,35 @@
+// Input post-process settings
+struct Settings {
+    exposure: f32,
+    tonemapping: u32, // 0 - None, 1 - Reinhard, 2 - ACES
+    bloom_threshold: f32,
+    bloom_intensity: f32, // Zero if bloom is disabled
+    vignette_intensity: f32, // Zero if vignette is disabled
+    vignette_smoothness: f32,
+    color_grading_intensity: f32, // Zero if color grading is disabled
+    color_grading_lut_size: f32,
+}
+
+// Input source data (HDR image of the scene)
+@group(0) @binding(0) var source_texture: texture_2d<f32>;
+@group(0) @binding(1) var source_sampler: sampler;
+@group(1) @binding(0) var<uniform> settings: Settings;
+
+@fragment
+fn main(
+    // Input vertex data
+    @location(0) texture_coordinates: vec2<f32>,
+) -> @location(0) vec4<f32> {
+    // Average 4x4 texels of the source with 4 bilinear samples
+    let texel_size = 1.0 / vec2<f32>(textureDimensions(source_texture));
+    var color = textureSample(source_texture, source_sampler, texture_coordinates + vec2<f32>(-1.0, -1.0) * texel_size).rgb;
+    color += textureSample(source_texture, source_sampler, texture_coordinates + vec2<f32>(1.0, -1.0) * texel_size).rgb;
+    color += textureSample(source_texture, source_sampler, texture_coordinates + vec2<f32>(-1.0, 1.0) * texel_size).rgb;
+    color += textureSample(source_texture, source_sampler, texture_coordinates + vec2<f32>(1.0, 1.0) * texel_size).rgb;
+    color *= 0.25;
+
+    // Keep only part of the color above threshold (ratio is used so that hue does not change)
+    let brightness = max(color.r, max(color.g, color.b));
+    let contribution = max(brightness - settings.bloom_threshold, 0.0) / max(brightness, 0.0001);
+    return vec4<f32>(color * contribution, 1.0);
+}
@@ -0,0 +1,22 @@
+// Input source data (smaller bloom mip level, result is added to the bigger one)
+@group(0) @binding(0) var source_texture: texture_2d<f32>;
+@group(0) @binding(1) var source_sampler: sampler;
+
+@fragment
+fn main(
+    // Input vertex data
+    @location(0) texture_coordinates: vec2<f32>,
+) -> @location(0) vec4<f32> {
+    // Blur with 3x3 tent filter
+    let texel_size = 1.0 / vec2<f32>(textureDimensions(source_texture));
+    var color = textureSample(source_texture, source_sampler, texture_coordinates).rgb * 4.0;
+    color += textureSample(source_texture, source_sampler, texture_coordinates + vec2<f32>(-1.0, 0.0) * texel_size).rgb * 2.0;
+    color += textureSample(source_texture, source_sampler, texture_coordinates + vec2<f32>(1.0, 0.0) * texel_size).rgb * 2.0;
+    color += textureSample(source_texture, source_sampler, texture_coordinates + vec2<f32>(0.0, -1.0) * texel_size).rgb * 2.0;
+    color += textureSample(source_texture, source_sampler, texture_coordinates + vec2<f32>(0.0, 1.0) * texel_size).rgb * 2.0;
+    color += textureSample(source_texture, source_sampler, texture_coordinates + vec2<f32>(-1.0, -1.0) * texel_size).rgb;
+    color += textureSample(source_texture, source_sampler, texture_coordinates + vec2<f32>(1.0, -1.0) * texel_size).rgb;
+    color += textureSample(source_texture, source_sampler, texture_coordinates + vec2<f32>(-1.0, 1.0) * texel_size).rgb;
+    color += textureSample(source_texture, source_sampler, texture_coordinates + vec2<f32>(1.0, 1.0) * texel_size).rgb;
+    return vec4<f32>(color / 16.0, 1.0);
+}
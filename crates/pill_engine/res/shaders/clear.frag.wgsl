@@ -0,0 +1,5 @@
+// Output color is ignored, clear color is set as blend constant of the render pass
+@fragment
+fn main() -> @location(0) vec4<f32> {
+    return vec4<f32>(0.0, 0.0, 0.0, 0.0);
+}
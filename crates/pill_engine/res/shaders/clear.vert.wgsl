@@ -0,0 +1,9 @@
+@vertex
+fn main(@builtin(vertex_index) vertex_index: u32) -> @builtin(position) vec4<f32> {
+    // Create triangle covering whole viewport from vertex index (no vertex buffer is used)
+    let index = i32(vertex_index);
+    let position = vec2<f32>(f32((index << 1u) & 2), f32(index & 2)) * 2.0 - 1.0;
+
+    // Triangle is placed on far plane so that depth is cleared to 1
+    return vec4<f32>(position, 1.0, 1.0);
+}
@@ -0,0 +1,79 @@
+// Input post-process settings
+struct Settings {
+    exposure: f32,
+    tonemapping: u32, // 0 - None, 1 - Reinhard, 2 - ACES
+    bloom_threshold: f32,
+    bloom_intensity: f32, // Zero if bloom is disabled
+    vignette_intensity: f32, // Zero if vignette is disabled
+    vignette_smoothness: f32,
+    color_grading_intensity: f32, // Zero if color grading is disabled
+    color_grading_lut_size: f32,
+}
+
+// Input source data
+@group(0) @binding(0) var scene_texture: texture_2d<f32>; // HDR image of the scene
+@group(0) @binding(1) var bloom_texture: texture_2d<f32>;
+@group(0) @binding(2) var color_grading_lut_texture: texture_2d<f32>;
+@group(0) @binding(3) var linear_sampler: sampler;
+@group(1) @binding(0) var<uniform> settings: Settings;
+
+// Fitted ACES filmic curve (Krzysztof Narkowicz)
+fn tonemap_aces(color: vec3<f32>) -> vec3<f32> {
+    return clamp((color * (2.51 * color + 0.03)) / (color * (2.43 * color + 0.59) + 0.14), vec3<f32>(0.0), vec3<f32>(1.0));
+}
+
+fn tonemap(color: vec3<f32>) -> vec3<f32> {
+    switch settings.tonemapping {
+        case 1u: { return color / (color + vec3<f32>(1.0)); }
+        case 2u: { return tonemap_aces(color); }
+        default: { return clamp(color, vec3<f32>(0.0), vec3<f32>(1.0)); }
+    }
+}
+
+// Converts linear color to sRGB encoding which is used to index color grading LUT
+fn linear_to_srgb(color: vec3<f32>) -> vec3<f32> {
+    let low = color * 12.92;
+    let high = 1.055 * pow(color, vec3<f32>(1.0 / 2.4)) - 0.055;
+    return select(high, low, color <= vec3<f32>(0.0031308));
+}
+
+// LUT has N slices of NxN texels placed horizontally, red goes along X in slice, green along Y and blue selects slice
+fn sample_color_grading_lut(color: vec3<f32>) -> vec3<f32> {
+    let size = settings.color_grading_lut_size;
+    let blue = color.b * (size - 1.0);
+    let slice = floor(blue);
+    let next_slice = min(slice + 1.0, size - 1.0);
+
+    // Texel centers are sampled so that neighbouring slices do not blend
+    let x = (color.r * (size - 1.0) + 0.5) / (size * size);
+    let y = (color.g * (size - 1.0) + 0.5) / size;
+    let slice_color = textureSampleLevel(color_grading_lut_texture, linear_sampler, vec2<f32>(x + slice / size, y), 0.0).rgb;
+    let next_slice_color = textureSampleLevel(color_grading_lut_texture, linear_sampler, vec2<f32>(x + next_slice / size, y), 0.0).rgb;
+    return mix(slice_color, next_slice_color, blue - slice);
+}
+
+@fragment
+fn main(
+    // Input vertex data
+    @location(0) texture_coordinates: vec2<f32>,
+) -> @location(0) vec4<f32> {
+    // Bloom
+    var color = textureSample(scene_texture, linear_sampler, texture_coordinates).rgb;
+    color += textureSample(bloom_texture, linear_sampler, texture_coordinates).rgb * settings.bloom_intensity;
+
+    // Exposure and tonemapping
+    color = tonemap(color * settings.exposure);
+
+    // Color grading (LUT is sampled as sRGB texture so graded color is linear again)
+    if settings.color_grading_intensity > 0.0 {
+        let graded_color = sample_color_grading_lut(linear_to_srgb(color));
+        color = mix(color, graded_color, settings.color_grading_intensity);
+    }
+
+    // Vignette (distance is 0 in center and 1 in corners)
+    let distance = length(texture_coordinates - 0.5) * sqrt(2.0);
+    let vignette = smoothstep(1.0 - settings.vignette_smoothness, 1.0 + 0.0001, distance);
+    color *= 1.0 - vignette * settings.vignette_intensity;
+
+    return vec4<f32>(color, 1.0);
+}
@@ -0,0 +1,62 @@
+// Fast approximate anti-aliasing (simplified FXAA by Timothy Lottes), edges are blurred along their direction
+
+const FXAA_SPAN_MAX: f32 = 8.0;
+const FXAA_REDUCE_MUL: f32 = 0.125;
+const FXAA_REDUCE_MIN: f32 = 0.0078125;
+
+// Input source data (tonemapped image)
+@group(0) @binding(0) var source_texture: texture_2d<f32>;
+@group(0) @binding(1) var source_sampler: sampler;
+
+// Perceived brightness (source is sampled as linear so it is converted to approximately gamma space)
+fn get_luma(color: vec3<f32>) -> f32 {
+    return sqrt(dot(color, vec3<f32>(0.299, 0.587, 0.114)));
+}
+
+fn sample_source(texture_coordinates: vec2<f32>) -> vec3<f32> {
+    return textureSampleLevel(source_texture, source_sampler, texture_coordinates, 0.0).rgb;
+}
+
+@fragment
+fn main(
+    // Input vertex data
+    @location(0) texture_coordinates: vec2<f32>,
+) -> @location(0) vec4<f32> {
+    let texel_size = 1.0 / vec2<f32>(textureDimensions(source_texture));
+
+    // Get brightness of the texel and its diagonal neighbours
+    let color_middle = sample_source(texture_coordinates);
+    let luma_middle = get_luma(color_middle);
+    let luma_north_west = get_luma(sample_source(texture_coordinates + vec2<f32>(-1.0, -1.0) * texel_size));
+    let luma_north_east = get_luma(sample_source(texture_coordinates + vec2<f32>(1.0, -1.0) * texel_size));
+    let luma_south_west = get_luma(sample_source(texture_coordinates + vec2<f32>(-1.0, 1.0) * texel_size));
+    let luma_south_east = get_luma(sample_source(texture_coordinates + vec2<f32>(1.0, 1.0) * texel_size));
+    let luma_min = min(luma_middle, min(min(luma_north_west, luma_north_east), min(luma_south_west, luma_south_east)));
+    let luma_max = max(luma_middle, max(max(luma_north_west, luma_north_east), max(luma_south_west, luma_south_east)));
+
+    // Get direction along the edge
+    var direction = vec2<f32>(
+        -((luma_north_west + luma_north_east) - (luma_south_west + luma_south_east)),
+        (luma_north_west + luma_south_west) - (luma_north_east + luma_south_east)
+    );
+    let direction_reduce = max((luma_north_west + luma_north_east + luma_south_west + luma_south_east) * 0.25 * FXAA_REDUCE_MUL, FXAA_REDUCE_MIN);
+    let direction_scale = 1.0 / (min(abs(direction.x), abs(direction.y)) + direction_reduce);
+    direction = clamp(direction * direction_scale, vec2<f32>(-FXAA_SPAN_MAX), vec2<f32>(FXAA_SPAN_MAX)) * texel_size;
+
+    // Blur along the edge with two and four samples
+    let color_near = 0.5 * (
+        sample_source(texture_coordinates + direction * (1.0 / 3.0 - 0.5)) +
+        sample_source(texture_coordinates + direction * (2.0 / 3.0 - 0.5))
+    );
+    let color_far = color_near * 0.5 + 0.25 * (
+        sample_source(texture_coordinates + direction * -0.5) +
+        sample_source(texture_coordinates + direction * 0.5)
+    );
+
+    // Wider blur is used unless it reaches past the edge (its brightness is out of neighbourhood range)
+    let luma_far = get_luma(color_far);
+    if luma_far < luma_min || luma_far > luma_max {
+        return vec4<f32>(color_near, 1.0);
+    }
+    return vec4<f32>(color_far, 1.0);
+}
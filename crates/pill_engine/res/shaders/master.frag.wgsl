@@ -0,0 +1,139 @@
+// Metallic-roughness material model with GGX specular BRDF and image based ambient lighting
+// Textures and factors follow glTF (metallic is read from blue channel and roughness from green channel, occlusion from red channel)
+
+const PI: f32 = 3.14159265359;
+
+// Input material data
+struct Properties {
+    base_color: vec3<f32>,
+    base_color_alpha: f32,
+    emissive: vec3<f32>,
+    metallic: f32,
+    roughness: f32,
+    normal_scale: f32,
+    occlusion_strength: f32,
+    alpha_cutoff: f32, // Set from blend mode of the material (zero unless it uses alpha cutout)
+}
+
+@group(0) @binding(0) var base_color_texture: texture_2d<f32>;
+@group(0) @binding(1) var base_color_sampler: sampler;
+@group(0) @binding(2) var metallic_roughness_texture: texture_2d<f32>;
+@group(0) @binding(3) var metallic_roughness_sampler: sampler;
+@group(0) @binding(4) var normal_texture: texture_2d<f32>;
+@group(0) @binding(5) var normal_sampler: sampler;
+@group(0) @binding(6) var occlusion_texture: texture_2d<f32>;
+@group(0) @binding(7) var occlusion_sampler: sampler;
+@group(0) @binding(8) var emissive_texture: texture_2d<f32>;
+@group(0) @binding(9) var emissive_sampler: sampler;
+@group(0) @binding(10) var environment_texture: texture_cube<f32>;
+@group(0) @binding(11) var environment_sampler: sampler;
+@group(1) @binding(0) var<uniform> properties: Properties;
+
+// Input camera data
+struct Camera {
+    camera_position: vec3<f32>,
+    camera_view_projection: mat4x4<f32>,
+}
+
+@group(2) @binding(0) var<uniform> camera: Camera;
+
+// Normal distribution function (Trowbridge-Reitz GGX)
+fn get_normal_distribution(n_dot_h: f32, alpha: f32) -> f32 {
+    let alpha_squared = alpha * alpha;
+    let denominator = n_dot_h * n_dot_h * (alpha_squared - 1.0) + 1.0;
+    return alpha_squared / (PI * denominator * denominator);
+}
+
+// Height-correlated Smith visibility function (geometry term divided by 4 * n_dot_l * n_dot_v)
+fn get_visibility(n_dot_v: f32, n_dot_l: f32, alpha: f32) -> f32 {
+    let alpha_squared = alpha * alpha;
+    let view_term = n_dot_l * sqrt(n_dot_v * n_dot_v * (1.0 - alpha_squared) + alpha_squared);
+    let light_term = n_dot_v * sqrt(n_dot_l * n_dot_l * (1.0 - alpha_squared) + alpha_squared);
+    return 0.5 / max(view_term + light_term, 0.0001);
+}
+
+// Fresnel term (Schlick approximation)
+fn get_fresnel(v_dot_h: f32, f0: vec3<f32>) -> vec3<f32> {
+    return f0 + (vec3<f32>(1.0) - f0) * pow(1.0 - v_dot_h, 5.0);
+}
+
+// Analytic approximation of prefiltered environment BRDF (replaces lookup texture of split sum approximation)
+fn get_environment_brdf(f0: vec3<f32>, roughness: f32, n_dot_v: f32) -> vec3<f32> {
+    let c0 = vec4<f32>(-1.0, -0.0275, -0.572, 0.022);
+    let c1 = vec4<f32>(1.0, 0.0425, 1.04, -0.04);
+    let r = roughness * c0 + c1;
+    let a004 = min(r.x * r.x, exp2(-9.28 * n_dot_v)) * r.x + r.y;
+    let scale_bias = vec2<f32>(-1.04, 1.04) * a004 + r.zw;
+    return f0 * scale_bias.x + scale_bias.y;
+}
+
+@fragment
+fn main(
+    // Input vertex data
+    @location(0) world_position: vec3<f32>,
+    @location(1) vertex_texture_coordinates: vec2<f32>,
+    @location(2) world_tangent: vec3<f32>,
+    @location(3) world_bitangent: vec3<f32>,
+    @location(4) world_normal: vec3<f32>,
+) -> @location(0) vec4<f32> {
+
+    // Settings
+    let light_position = vec3<f32>(-10.0, 10.0, -10.0);
+    let light_color = vec3<f32>(1.0, 1.0, 1.0);
+    let light_intensity = 3.0;
+
+    // Textures
+    let base_color_sample = textureSample(base_color_texture, base_color_sampler, vertex_texture_coordinates);
+    let metallic_roughness_sample = textureSample(metallic_roughness_texture, metallic_roughness_sampler, vertex_texture_coordinates);
+    let normal_sample = textureSample(normal_texture, normal_sampler, vertex_texture_coordinates);
+    let occlusion_sample = textureSample(occlusion_texture, occlusion_sampler, vertex_texture_coordinates);
+    let emissive_sample = textureSample(emissive_texture, emissive_sampler, vertex_texture_coordinates);
+
+    // Alpha cutout
+    let alpha = base_color_sample.a * properties.base_color_alpha;
+    if alpha < properties.alpha_cutoff {
+        discard;
+    }
+
+    // Material properties (roughness is limited so that highlights do not disappear)
+    let base_color = base_color_sample.rgb * properties.base_color;
+    let metallic = clamp(metallic_roughness_sample.b * properties.metallic, 0.0, 1.0);
+    let roughness = clamp(metallic_roughness_sample.g * properties.roughness, 0.03, 1.0);
+    let alpha_roughness = roughness * roughness;
+    let occlusion = 1.0 + properties.occlusion_strength * (occlusion_sample.r - 1.0);
+    let emissive = emissive_sample.rgb * properties.emissive;
+
+    // Normal (scale is applied to tangent space X and Y as in glTF)
+    let tangent_normal = (normal_sample.rgb * 2.0 - 1.0) * vec3<f32>(properties.normal_scale, properties.normal_scale, 1.0);
+    let TBN_matrix = mat3x3<f32>(normalize(world_tangent), normalize(world_bitangent), normalize(world_normal));
+    let normal = normalize(TBN_matrix * tangent_normal);
+
+    // Reflectance at normal incidence (dielectrics reflect 4% of light, metals reflect their base color and have no diffuse)
+    let f0 = mix(vec3<f32>(0.04), base_color, metallic);
+    let diffuse_color = base_color * (1.0 - metallic);
+
+    // Direct lighting
+    let view_direction = normalize(camera.camera_position - world_position);
+    let light_direction = normalize(light_position - world_position);
+    let half_direction = normalize(view_direction + light_direction);
+    let n_dot_v = max(dot(normal, view_direction), 0.0001);
+    let n_dot_l = max(dot(normal, light_direction), 0.0);
+    let n_dot_h = max(dot(normal, half_direction), 0.0);
+    let v_dot_h = max(dot(view_direction, half_direction), 0.0);
+
+    let fresnel = get_fresnel(v_dot_h, f0);
+    let specular = fresnel * get_normal_distribution(n_dot_h, alpha_roughness) * get_visibility(n_dot_v, n_dot_l, alpha_roughness);
+    let diffuse = (vec3<f32>(1.0) - fresnel) * diffuse_color / PI;
+    let direct_light = (diffuse + specular) * light_color * light_intensity * n_dot_l;
+
+    // Image based ambient lighting (rougher surfaces sample blurrier mip levels of environment, lowest mip level approximates irradiance)
+    let environment_max_mip_level = f32(textureNumLevels(environment_texture) - 1u);
+    let irradiance = textureSampleLevel(environment_texture, environment_sampler, normal, environment_max_mip_level).rgb;
+    let reflection_direction = reflect(-view_direction, normal);
+    let prefiltered_color = textureSampleLevel(environment_texture, environment_sampler, reflection_direction, roughness * environment_max_mip_level).rgb;
+    let ambient_light = (diffuse_color * irradiance + prefiltered_color * get_environment_brdf(f0, roughness, n_dot_v)) * occlusion;
+
+    // Final color
+    let final_color = direct_light + ambient_light + emissive;
+    return vec4<f32>(final_color, alpha); // Alpha is used by transparent blend modes
+}
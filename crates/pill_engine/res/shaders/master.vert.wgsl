@@ -0,0 +1,64 @@
+// Input camera data
+struct Camera {
+    camera_position: vec3<f32>,
+    camera_view_projection: mat4x4<f32>,
+}
+
+@group(2) @binding(0) var<uniform> camera: Camera;
+
+// Output data (lighting is calculated in world space)
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) world_position: vec3<f32>,
+    @location(1) vertex_texture_coordinates: vec2<f32>,
+    @location(2) world_tangent: vec3<f32>,
+    @location(3) world_bitangent: vec3<f32>,
+    @location(4) world_normal: vec3<f32>,
+}
+
+@vertex
+fn main(
+    // Input vertex data
+    @location(0) vertex_position: vec3<f32>,
+    @location(1) vertex_texture_coordinates: vec2<f32>,
+    @location(2) vertex_normal: vec3<f32>,
+    @location(3) vertex_tangent: vec3<f32>,
+    @location(4) vertex_bitangent: vec3<f32>,
+
+    // Input model data
+    @location(5) model_matrix_0: vec4<f32>,
+    @location(6) model_matrix_1: vec4<f32>,
+    @location(7) model_matrix_2: vec4<f32>,
+    @location(8) model_matrix_3: vec4<f32>,
+) -> VertexOutput {
+    let model_matrix = mat4x4<f32>(
+        model_matrix_0,
+        model_matrix_1,
+        model_matrix_2,
+        model_matrix_3
+    );
+
+    // Create normal matrix (inverse transpose of model matrix is built from cofactors, inverse is not supported by shader reflection)
+    let model_rotation_scale = mat3x3<f32>(model_matrix_0.xyz, model_matrix_1.xyz, model_matrix_2.xyz);
+    let normal_matrix = mat3x3<f32>(
+        cross(model_rotation_scale[1], model_rotation_scale[2]),
+        cross(model_rotation_scale[2], model_rotation_scale[0]),
+        cross(model_rotation_scale[0], model_rotation_scale[1])
+    ) * (1.0 / determinant(model_rotation_scale));
+
+    // Transform tangent space vectors to world space (they are normalized again in fragment shader after interpolation)
+    var out: VertexOutput;
+    out.world_tangent = normalize(normal_matrix * vertex_tangent);
+    out.world_bitangent = normalize(normal_matrix * vertex_bitangent);
+    out.world_normal = normalize(normal_matrix * vertex_normal);
+
+    // Calculate vertex position in world space
+    let world_position = model_matrix * vec4<f32>(vertex_position, 1.0);
+    out.world_position = world_position.xyz;
+
+    // Just forward texture coordinates
+    out.vertex_texture_coordinates = vertex_texture_coordinates;
+
+    out.position = camera.camera_view_projection * world_position;
+    return out;
+}
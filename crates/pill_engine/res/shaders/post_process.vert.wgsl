@@ -0,0 +1,19 @@
+// Output data
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) texture_coordinates: vec2<f32>,
+}
+
+@vertex
+fn main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    // Create triangle covering whole screen from vertex index (no vertex buffer is used)
+    let index = i32(vertex_index);
+    let position = vec2<f32>(f32((index << 1u) & 2), f32(index & 2)) * 2.0 - 1.0;
+
+    // Texture coordinates go from top left corner of the screen
+    var out: VertexOutput;
+    out.texture_coordinates = vec2<f32>(position.x * 0.5 + 0.5, 0.5 - position.y * 0.5);
+
+    out.position = vec4<f32>(position, 0.0, 1.0);
+    return out;
+}
@@ -0,0 +1,12 @@
+// Input skybox data
+@group(0) @binding(0) var skybox_texture: texture_cube<f32>;
+@group(0) @binding(1) var skybox_sampler: sampler;
+
+@fragment
+fn main(
+    // Input vertex data
+    @location(0) direction: vec3<f32>,
+) -> @location(0) vec4<f32> {
+    let skybox_color = textureSample(skybox_texture, skybox_sampler, normalize(direction)).rgb;
+    return vec4<f32>(skybox_color, 1.0);
+}
@@ -0,0 +1,28 @@
+// Input camera data
+struct Camera {
+    camera_position: vec3<f32>,
+    camera_view_projection: mat4x4<f32>,
+    camera_inverse_skybox_view_projection: mat4x4<f32>,
+}
+
+@group(1) @binding(0) var<uniform> camera: Camera;
+
+// Output data
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) direction: vec3<f32>,
+}
+
+@vertex
+fn main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    // Create triangle covering whole screen from vertex index (no vertex buffer is used)
+    let index = i32(vertex_index);
+    let position = vec2<f32>(f32((index << 1u) & 2), f32(index & 2)) * 2.0 - 1.0;
+
+    // Get direction to the point on far plane (it is not divided by w so that it can be interpolated linearly)
+    var out: VertexOutput;
+    out.direction = (camera.camera_inverse_skybox_view_projection * vec4<f32>(position, 1.0, 1.0)).xyz;
+
+    out.position = vec4<f32>(position, 1.0, 1.0);
+    return out;
+}
@@ -1,7 +1,7 @@
 use crate::{
-    ecs::{ AudioManagerComponent, DeferredUpdateComponent, EguiManagerComponent, InputComponent, TimeComponent }, 
-    graphics::{ RendererMaterialHandle, RendererTextureHandle }, 
-    resources::{ MaterialHandle, TextureHandle, TextureType }
+    ecs::{ AudioManagerComponent, DeferredUpdateComponent, EguiManagerComponent, InputComponent, ProfilerComponent, TimeComponent }, 
+    graphics::{ RendererMaterialHandle, RendererPipelineHandle, RendererTextureHandle }, 
+    resources::{ MaterialHandle, ShaderHandle, TextureHandle, TextureType }
 };
 
 use pill_core::PillSlotMapKeyData;
@@ -12,6 +12,36 @@ use lazy_static::lazy_static;
 // --- General ---
 
 pub const PANIC_ON_GAME_ERRORS: bool = true;
+pub const HOT_RELOAD_ENABLED: bool = true; // Only used in debug builds with hot-reload feature
+
+// --- Profiler ---
+
+pub const PROFILER_ENABLED: bool = false;
+pub const PROFILER_OVERLAY: bool = false;
+pub const PROFILER_HISTORY_LENGTH: usize = 240;
+pub const PROFILER_TRACE_FRAME_COUNT: usize = 600;
+
+// --- Rendering ---
+
+pub const FRUSTUM_CULLING_ENABLED: bool = true;
+pub const DEFAULT_LOD_HYSTERESIS: f32 = 0.1;
+pub const VSYNC_MODE: &str = "Off"; // "On", "Off" or "Adaptive"
+pub const MSAA_SAMPLE_COUNT: u32 = 1; // 1 disables multisampling
+pub const MAX_FRAME_RATE: u32 = 0; // 0 means frame rate is not limited
+
+// --- Post-processing ---
+
+pub const POST_PROCESS_EXPOSURE: f32 = 1.0;
+pub const POST_PROCESS_TONEMAPPING: &str = "Aces"; // "None", "Reinhard" or "Aces"
+pub const POST_PROCESS_BLOOM_ENABLED: bool = true;
+pub const POST_PROCESS_BLOOM_THRESHOLD: f32 = 1.0;
+pub const POST_PROCESS_BLOOM_INTENSITY: f32 = 0.1;
+pub const POST_PROCESS_BLOOM_MIP_COUNT: usize = 6; // Number of downsampled images bloom is blurred with (more gives wider bloom)
+pub const POST_PROCESS_FXAA_ENABLED: bool = true;
+pub const POST_PROCESS_VIGNETTE_ENABLED: bool = false;
+pub const POST_PROCESS_VIGNETTE_INTENSITY: f32 = 0.3;
+pub const POST_PROCESS_VIGNETTE_SMOOTHNESS: f32 = 0.5;
+pub const POST_PROCESS_COLOR_GRADING_INTENSITY: f32 = 1.0;
 
 // --- ECS ---
 
@@ -26,33 +56,62 @@ pub const RESOURCE_VERSION_LIMIT: usize = 255;
 
 pub const MAX_PIPELINES: usize = 10;
 pub const MAX_TEXTURES: usize = 10;
+pub const MAX_SHADERS: usize = 10;
 pub const MAX_MATERIALS: usize = 10;
 pub const MAX_MESHES: usize = 10;
 pub const MAX_SOUNDS: usize = 10;
 
+pub const RESOURCE_LOADING_THREAD_COUNT: usize = 2;
+pub const RESOURCE_REMOVAL_POLICY: &str = "Cascade"; // "Cascade" or "Refuse"
+pub const RESOURCE_AUTO_UNLOAD: bool = false;
+pub const RESOURCE_AUTO_UNLOAD_INTERVAL: f32 = 1.0; // Seconds between scans for unused resources (each scan checks all components in all scenes)
+pub const RESOURCE_ARCHIVE: &str = "res.pak"; // Mounted on start if it exists next to the executable
+
 // Convention: All resource names starting with "PillDefault" are restricted, cannot be added and removed from game
 pub const DEFAULT_RESOURCE_PREFIX: &str = "PillDefault";
 pub const DEFAULT_COLOR_TEXTURE_NAME: &str = "PillDefaultColor";
 pub const DEFAULT_NORMAL_TEXTURE_NAME: &str = "PillDefaultNormal";
+pub const DEFAULT_CUBEMAP_TEXTURE_NAME: &str = "PillDefaultCubemap";
+pub const DEFAULT_SHADER_NAME: &str = "PillDefaultShader";
 pub const DEFAULT_MATERIAL_NAME: &str = "PillDefaultMaterial";
-
-// Master material
-pub const MASTER_SHADER_COLOR_TEXTURE_SLOT: &str = "Color";
+pub const DEFAULT_CUBE_MESH_NAME: &str = "PillDefaultCube";
+pub const DEFAULT_SPHERE_MESH_NAME: &str = "PillDefaultSphere";
+pub const DEFAULT_PLANE_MESH_NAME: &str = "PillDefaultPlane";
+pub const DEFAULT_CYLINDER_MESH_NAME: &str = "PillDefaultCylinder";
+pub const DEFAULT_CAPSULE_MESH_NAME: &str = "PillDefaultCapsule";
+pub const DEFAULT_TORUS_MESH_NAME: &str = "PillDefaultTorus";
+
+// Master material (metallic-roughness model, slots map one to one to glTF material properties)
+pub const MASTER_SHADER_BASE_COLOR_TEXTURE_SLOT: &str = "BaseColor";
+pub const MASTER_SHADER_METALLIC_ROUGHNESS_TEXTURE_SLOT: &str = "MetallicRoughness"; // Color texture with metallic in blue and roughness in green channel (should use linear color space)
 pub const MASTER_SHADER_NORMAL_TEXTURE_SLOT: &str = "Normal";
-pub const MASTER_SHADER_TINT_PARAMETER_SLOT: &str = "Tint";
-pub const MASTER_SHADER_SPECULARITY_PARAMETER_SLOT: &str = "Specularity";
+pub const MASTER_SHADER_OCCLUSION_TEXTURE_SLOT: &str = "Occlusion"; // Color texture with occlusion in red channel (should use linear color space)
+pub const MASTER_SHADER_EMISSIVE_TEXTURE_SLOT: &str = "Emissive";
+pub const MASTER_SHADER_ENVIRONMENT_TEXTURE_SLOT: &str = "Environment"; // Cubemap used for ambient lighting and reflections
+pub const MASTER_SHADER_BASE_COLOR_PARAMETER_SLOT: &str = "BaseColor";
+pub const MASTER_SHADER_BASE_COLOR_ALPHA_PARAMETER_SLOT: &str = "BaseColorAlpha"; // Alpha component of glTF base color factor
+pub const MASTER_SHADER_EMISSIVE_PARAMETER_SLOT: &str = "Emissive";
+pub const MASTER_SHADER_METALLIC_PARAMETER_SLOT: &str = "Metallic";
+pub const MASTER_SHADER_ROUGHNESS_PARAMETER_SLOT: &str = "Roughness";
+pub const MASTER_SHADER_NORMAL_SCALE_PARAMETER_SLOT: &str = "NormalScale";
+pub const MASTER_SHADER_OCCLUSION_STRENGTH_PARAMETER_SLOT: &str = "OcclusionStrength";
+pub const MASTER_SHADER_ALPHA_CUTOFF_PARAMETER_SLOT: &str = "AlphaCutoff"; // Set from blend mode of the material (other shaders can declare it too)
+pub const MASTER_SHADER_DEFAULT_ROUGHNESS: f32 = 0.5; // Unlike glTF (fully rough metal) default material is half rough dielectric
 
 // Render queue key
 pub type RenderQueueKeyType = u64; // Defines size of renderer queue key (Should be u8, u16, u32, or u64)
 
-pub const RENDER_QUEUE_KEY_ITEMS_LENGTH: [RenderQueueKeyType; 5] = [5, 8, 8, 8, 8]; // Defines size of next render queue key parts (bits from left to right)
+pub const RENDER_QUEUE_KEY_ITEMS_LENGTH: [RenderQueueKeyType; 8] = [1, 5, 16, 8, 8, 8, 8, 8]; // Defines size of next render queue key parts (bits from left to right)
 
 // Indices of render queue key parts (maps RENDER_QUEUE_KEY_ITEMS_LENGTH)
-pub const RENDER_QUEUE_KEY_ORDER_IDX: u8 = 0;
-pub const RENDER_QUEUE_KEY_MATERIAL_INDEX_IDX: u8 = 1;
-pub const RENDER_QUEUE_KEY_MATERIAL_VERSION_IDX: u8 = 2;
-pub const RENDER_QUEUE_KEY_MESH_INDEX_IDX: u8 = 3;
-pub const RENDER_QUEUE_KEY_MESH_VERSION_IDX: u8 = 4;
+pub const RENDER_QUEUE_KEY_PASS_IDX: u8 = 0;
+pub const RENDER_QUEUE_KEY_ORDER_IDX: u8 = 1;
+pub const RENDER_QUEUE_KEY_DEPTH_IDX: u8 = 2;
+pub const RENDER_QUEUE_KEY_PIPELINE_INDEX_IDX: u8 = 3;
+pub const RENDER_QUEUE_KEY_MATERIAL_INDEX_IDX: u8 = 4;
+pub const RENDER_QUEUE_KEY_MATERIAL_VERSION_IDX: u8 = 5;
+pub const RENDER_QUEUE_KEY_MESH_INDEX_IDX: u8 = 6;
+pub const RENDER_QUEUE_KEY_MESH_VERSION_IDX: u8 = 7;
 
 // Default resource handle - Color texture
 pub const DEFAULT_COLOR_TEXTURE_HANDLE: TextureHandle = TextureHandle { 
@@ -70,13 +129,29 @@ pub const DEFAULT_RENDERER_NORMAL_TEXTURE_HANDLE: RendererTextureHandle = Render
     0: PillSlotMapKeyData { index: 2, version: unsafe { std::num::NonZeroU32::new_unchecked(1) } } 
 };
 
+// Default resource handle - Cubemap texture
+pub const DEFAULT_CUBEMAP_TEXTURE_HANDLE: TextureHandle = TextureHandle { 
+    0: PillSlotMapKeyData { index: 3, version: unsafe { std::num::NonZeroU32::new_unchecked(1) } } 
+};
+pub const DEFAULT_RENDERER_CUBEMAP_TEXTURE_HANDLE: RendererTextureHandle = RendererTextureHandle { 
+    0: PillSlotMapKeyData { index: 3, version: unsafe { std::num::NonZeroU32::new_unchecked(1) } } 
+};
+
 pub fn get_default_texture_handles(texture_type: TextureType) -> (TextureHandle, RendererTextureHandle) {
     match texture_type {
         TextureType::Color => (DEFAULT_COLOR_TEXTURE_HANDLE, DEFAULT_RENDERER_COLOR_TEXTURE_HANDLE),
         TextureType::Normal => (DEFAULT_NORMAL_TEXTURE_HANDLE, DEFAULT_RENDERER_NORMAL_TEXTURE_HANDLE),
+        TextureType::Cubemap => (DEFAULT_CUBEMAP_TEXTURE_HANDLE, DEFAULT_RENDERER_CUBEMAP_TEXTURE_HANDLE),
     }
 }
 
+// Default resource handle - Shader (its pipeline is created together with default material)
+pub const DEFAULT_SHADER_HANDLE: ShaderHandle = ShaderHandle { 
+    0: PillSlotMapKeyData { index: 1, version: unsafe { std::num::NonZeroU32::new_unchecked(1) } } 
+};
+pub const DEFAULT_RENDERER_PIPELINE_HANDLE: RendererPipelineHandle = RendererPipelineHandle { 
+    0: PillSlotMapKeyData { index: 1, version: unsafe { std::num::NonZeroU32::new_unchecked(1) } } 
+};
 
 // Default resource handle - Material
 pub const DEFAULT_MATERIAL_HANDLE: MaterialHandle = MaterialHandle { 
@@ -96,6 +171,7 @@ lazy_static! {
         TypeId::of::<TimeComponent>(),
         TypeId::of::<AudioManagerComponent>(),
         TypeId::of::<DeferredUpdateComponent>(),
-        TypeId::of::<EguiManagerComponent>()
+        TypeId::of::<EguiManagerComponent>(),
+        TypeId::of::<ProfilerComponent>()
     );
 }
\ No newline at end of file
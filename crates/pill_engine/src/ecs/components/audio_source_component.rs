@@ -230,7 +230,13 @@ impl Component for AudioSourceComponent {
                 // Get data from sound resource
                 let sound_handle = self.sound_handle.unwrap();
                 let sound = (&*engine).get_resource::<Sound>(&sound_handle)?;
-                let sound_data = sound.sound_data.as_ref().unwrap().get_source_sound();
+                let sound_data = match sound.sound_data.as_ref() {
+                    Some(v) => v.get_source_sound().context(format!("Playing {} {} failed", "Sound".sobj_style(), sound.name.name_style()))?,
+                    None => {
+                        warn!("Cannot play {} {}, it is not loaded yet", "Sound".sobj_style(), sound.name.name_style());
+                        return Ok(())
+                    },
+                };
 
                 // Get free sink, set its volume and play
                 let audio_manager = engine.get_global_component_mut::<AudioManagerComponent>()?;
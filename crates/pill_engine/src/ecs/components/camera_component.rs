@@ -1,15 +1,16 @@
 use crate::{
     engine::Engine, 
-    graphics::{ RenderQueueKey, compose_render_queue_key, RendererCameraHandle }, 
-    resources::{ Material, MaterialHandle, Mesh, MeshHandle },
-    ecs::{ Component, ComponentStorage, EntityHandle, SceneHandle, DeferredUpdateManagerPointer, DeferredUpdateComponentRequest },
+    graphics::{ RenderQueueKey, compose_render_queue_key, RendererCameraHandle, PostProcessSettings, RenderLayers }, 
+    resources::{ Material, MaterialHandle, Mesh, MeshHandle, TextureHandle },
+    ecs::{ Component, ComponentStorage, EntityHandle, SceneHandle, DeferredUpdateManagerPointer, DeferredUpdateComponentRequest, TransformComponent },
 };
 
-use pill_core::{ PillSlotMapKey, Color, PillStyle, get_type_name };
+use pill_core::{ PillSlotMapKey, Color, PillStyle, Matrix4f, Vector2f, Vector3f, Vector4f, get_type_name };
 
 use anyhow::{Result, Context, Error};
 use pill_core::{ PillTypeMap, PillTypeMapKey };
 use std::ops::Range;
+use cgmath::{ InnerSpace, SquareMatrix };
 
 
 pub enum CameraAspectRatio {
@@ -26,6 +27,40 @@ impl CameraAspectRatio {
     }
 }
 
+// Defines how visible volume of the camera is projected to the screen
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CameraProjection {
+    Perspective, // Field of view of the camera defines visible volume, distant objects are smaller
+    Orthographic(f32), // Height of visible area in world units, objects keep their size regardless of distance (e.g. 2D and isometric games)
+}
+
+// Part of the window or render target camera renders to, in normalized coordinates from top left corner
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CameraViewport {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl CameraViewport {
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self { x, y, width, height }
+    }
+
+    pub fn full() -> Self {
+        Self::new(0.0, 0.0, 1.0, 1.0)
+    }
+}
+
+// Defines what is cleared in camera viewport before camera renders
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CameraClearMode {
+    ColorAndDepth, // Viewport is filled with clear color or skybox
+    Depth, // Image of cameras rendered before stays visible, but everything is drawn over it (e.g. first person weapon)
+    None, // Image and depth of cameras rendered before are kept
+}
+
 // --- Builder ---
 
 pub struct CameraComponentBuilder {
@@ -44,6 +79,11 @@ impl CameraComponentBuilder {
         self
     }
 
+    pub fn projection(mut self, projection: CameraProjection) -> Self {
+        self.component.projection = projection;
+        self
+    }
+
     pub fn fov(mut self, fov: f32) -> Self {
         self.component.fov = fov;
         self
@@ -59,6 +99,41 @@ impl CameraComponentBuilder {
         self
     }
 
+    pub fn skybox(mut self, skybox: TextureHandle) -> Self {
+        self.component.skybox = Some(skybox);
+        self
+    }
+
+    pub fn viewport(mut self, viewport: CameraViewport) -> Self {
+        self.component.viewport = viewport;
+        self
+    }
+
+    pub fn priority(mut self, priority: i32) -> Self {
+        self.component.priority = priority;
+        self
+    }
+
+    pub fn clear_mode(mut self, clear_mode: CameraClearMode) -> Self {
+        self.component.clear_mode = clear_mode;
+        self
+    }
+
+    pub fn render_target(mut self, render_target: TextureHandle) -> Self {
+        self.component.render_target = Some(render_target);
+        self
+    }
+
+    pub fn culling_mask(mut self, culling_mask: RenderLayers) -> Self {
+        self.component.culling_mask = culling_mask;
+        self
+    }
+
+    pub fn post_process(mut self, post_process: PostProcessSettings) -> Self {
+        self.component.post_process = Some(post_process);
+        self
+    }
+
     pub fn enabled(mut self, enabled: bool) -> Self {
         self.component.enabled = enabled;
         self
@@ -73,9 +148,17 @@ impl CameraComponentBuilder {
 
 pub struct CameraComponent {
     pub aspect: CameraAspectRatio,
-    pub fov: f32,
+    pub projection: CameraProjection,
+    pub fov: f32, // Vertical field of view in degrees (used only by perspective projection)
     pub range: Range<f32>,
     pub clear_color: Color,
+    pub skybox: Option<TextureHandle>, // Cubemap texture drawn as background instead of clear color (clear color is used until it is loaded)
+    pub viewport: CameraViewport,
+    pub priority: i32, // Enabled cameras are rendered from lowest to highest priority, so cameras with higher priority are drawn over others
+    pub clear_mode: CameraClearMode,
+    pub render_target: Option<TextureHandle>, // Texture created with Texture::new_render_target that camera renders to instead of the window (camera is skipped until it is created)
+    pub culling_mask: RenderLayers, // Layers of mesh rendering components that camera renders
+    pub post_process: Option<PostProcessSettings>, // Overrides global post-process settings from config (only settings of the first camera rendering to the window are used, a warning is logged if other cameras rendering to it set different ones)
     pub enabled: bool,
     pub(crate) renderer_resource_handle: Option<RendererCameraHandle>,
 }
@@ -88,13 +171,96 @@ impl CameraComponent {
     pub fn new() -> Self {
         Self { 
             aspect: CameraAspectRatio::Automatic(1.0),
+            projection: CameraProjection::Perspective,
             fov: 60.0,
             range: 0.1..100.0,
             clear_color: Color::new(0.15, 0.15, 0.15),
+            skybox: None,
+            viewport: CameraViewport::full(),
+            priority: 0,
+            clear_mode: CameraClearMode::ColorAndDepth,
+            render_target: None,
+            culling_mask: RenderLayers::all(),
+            post_process: None,
             renderer_resource_handle: None,
             enabled: false,
         }
     }
+
+    // Camera looks along Z axis rotated by rotation of the transform
+    pub fn get_direction(&self, transform_component: &TransformComponent) -> Vector3f {
+        let roll_matrix  = cgmath::Matrix3::from_angle_z(cgmath::Deg(transform_component.rotation.z));
+        let yaw_matrix  = cgmath::Matrix3::from_angle_y(cgmath::Deg(transform_component.rotation.y));
+        let pitch_matrix  = cgmath::Matrix3::from_angle_x(cgmath::Deg(transform_component.rotation.x));
+        let rotation_matrix = yaw_matrix * pitch_matrix * roll_matrix;
+
+        rotation_matrix * Vector3f::unit_z()
+    }
+
+    pub fn get_view_matrix(&self, transform_component: &TransformComponent) -> Matrix4f {
+        let position = cgmath::Point3::new(transform_component.position.x, transform_component.position.y, transform_component.position.z);
+        let direction = self.get_direction(transform_component);
+
+        Matrix4f::look_to_rh(position, direction, Vector3f::unit_y())
+    }
+
+    // Projection with clip space depth from -1 to 1 (as in OpenGL)
+    pub fn get_projection_matrix(&self) -> Matrix4f {
+        match self.projection {
+            CameraProjection::Perspective => cgmath::perspective(cgmath::Deg(self.fov), self.aspect.get_value(), self.range.start, self.range.end),
+            CameraProjection::Orthographic(size) => {
+                let half_height = size / 2.0;
+                let half_width = half_height * self.aspect.get_value();
+                cgmath::ortho(-half_width, half_width, -half_height, half_height, self.range.start, self.range.end)
+            },
+        }
+    }
+
+    pub fn get_view_projection_matrix(&self, transform_component: &TransformComponent) -> Matrix4f {
+        self.get_projection_matrix() * self.get_view_matrix(transform_component)
+    }
+
+    // Returns ray (origin on near plane and normalized direction) going through point on the window
+    // Screen position is normalized from top left corner of the window, so mouse position has to be divided by window size
+    // Rays of perspective camera start near camera position, rays of orthographic camera are parallel to camera direction
+    pub fn get_screen_ray(&self, transform_component: &TransformComponent, screen_position: Vector2f) -> Option<(Vector3f, Vector3f)> {
+        let inverse_view_projection_matrix = self.get_view_projection_matrix(transform_component).invert()?;
+
+        // Convert screen position to position in camera viewport and then to clip space
+        let x = (screen_position.x - self.viewport.x) / self.viewport.width * 2.0 - 1.0;
+        let y = 1.0 - (screen_position.y - self.viewport.y) / self.viewport.height * 2.0;
+
+        // Get points on near and far planes
+        let near_point = inverse_view_projection_matrix * Vector4f::new(x, y, -1.0, 1.0);
+        let far_point = inverse_view_projection_matrix * Vector4f::new(x, y, 1.0, 1.0);
+        let near_point = near_point.truncate() / near_point.w;
+        let far_point = far_point.truncate() / far_point.w;
+
+        Some((near_point, (far_point - near_point).normalize()))
+    }
+
+    pub(crate) fn uses_texture(&self, texture_handle: &TextureHandle) -> bool {
+        self.skybox == Some(*texture_handle) 
+            || self.render_target == Some(*texture_handle)
+            || self.post_process.as_ref().map_or(false, |v| v.color_grading_lut == Some(*texture_handle))
+    }
+
+    // Removes texture from all fields that use it
+    pub(crate) fn remove_texture_handle(&mut self, texture_handle: &TextureHandle) {
+        if self.skybox == Some(*texture_handle) {
+            self.skybox = None;
+        }
+        // Camera would start rendering to the window without its render target so it is disabled
+        if self.render_target == Some(*texture_handle) {
+            self.render_target = None;
+            self.enabled = false;
+        }
+        if let Some(post_process) = self.post_process.as_mut() {
+            if post_process.color_grading_lut == Some(*texture_handle) {
+                post_process.color_grading_lut = None;
+            }
+        }
+    }
 }
 
 // This needed so that renderer can get renderer camera handle from camera component while it is still hidden in game API
@@ -2,52 +2,130 @@ use crate::{
     ecs::components::{ Component, GlobalComponentStorage, GlobalComponent }, engine::Engine
 };
 
-use pill_core::PillTypeMapKey;
+use pill_core::{ EngineError, PillTypeMapKey };
 
 use anyhow::{Result, Error, Context};
+use indexmap::IndexMap;
+
+pub const ENGINE_UI_CALLBACK_NAME: &str = "PillEngine";
+
+pub type UiCallbackFunction = fn(engine: &mut Engine, context: &egui::Context) -> Result<()>;
+
+pub struct UiCallback {
+    pub(crate) name: String,
+    pub(crate) callback_function: UiCallbackFunction,
+    pub(crate) enabled: bool,
+}
 
 pub struct EguiManagerComponent {
+    pub(crate) ui_callbacks: IndexMap<String, UiCallback>,
+    pub(crate) wants_pointer_input: bool,
+    pub(crate) wants_keyboard_input: bool,
 }
 
 impl EguiManagerComponent {
     pub fn new() -> Self {
-        Self { 
-          
+        let mut component = Self {
+            ui_callbacks: IndexMap::<String, UiCallback>::new(),
+            wants_pointer_input: false,
+            wants_keyboard_input: false,
+        };
+
+        // Register built-in engine window
+        component.add_ui_callback(ENGINE_UI_CALLBACK_NAME, engine_ui).expect("Critical: Cannot add engine UI callback");
+
+        component
+    }
+
+    pub fn add_ui_callback(&mut self, name: &str, callback_function: UiCallbackFunction) -> Result<()> {
+        // Check if callback with that name already exists
+        if self.ui_callbacks.contains_key(name) {
+            return Err(Error::new(EngineError::UiCallbackAlreadyExists(name.to_string())))
         }
+
+        // Create callback object
+        let callback = UiCallback {
+            name: name.to_string(),
+            callback_function,
+            enabled: true,
+        };
+
+        // Add callback
+        self.ui_callbacks.insert(name.to_string(), callback);
+
+        Ok(())
     }
 
-    pub fn get_ui(engine: &mut Engine) -> Box<dyn Fn(&egui::Context)> {
-
-        let ff = engine.frame_delta_time;
-        let x = Box::new(move |ui: &egui::Context| {
-            egui::Window::new("PillEngine")
-                .default_open(true)
-                .resizable(true)
-                .anchor(egui::Align2::LEFT_TOP, [0.0, 0.0])
-                .show(ui, |ui| {
-                    if ui.add(egui::Button::new("Click me")).clicked() {
-                        println!("PRESSED");
-                    }
-                    ui.add(egui::Label::new(format!("FPS {}", 1000.0 / ff) ));
-                    //ui.add(egui::Label::new(format!("Entities {}", entity_count)));
-                    //ui.add(egui::Label::new(format!("Entities {}", engine.scene_manager.get_active_scene().unwrap().entities.len()) ));
-                });
-        });
+    pub fn remove_ui_callback(&mut self, name: &str) -> Result<()> {
+        // Remove callback if it exists
+        self.ui_callbacks.shift_remove(name).ok_or(Error::new(EngineError::UiCallbackNotFound(name.to_string())))?;
+
+        Ok(())
+    }
 
-        x
+    pub fn toggle_ui_callback(&mut self, name: &str, enabled: bool) -> Result<()> {
+        // Check if callback with that name exists
+        let callback = self.ui_callbacks.get_mut(name).ok_or(Error::new(EngineError::UiCallbackNotFound(name.to_string())))?;
+
+        // Set callback state
+        callback.enabled = enabled;
+
+        Ok(())
+    }
+
+    /// Returns true if egui is using the pointer in this frame (e.g. it is hovering over or dragging UI element)
+    ///
+    /// Game input systems should ignore mouse input when it is true
+    pub fn wants_pointer_input(&self) -> bool {
+        self.wants_pointer_input
+    }
+
+    /// Returns true if egui is using the keyboard in this frame (e.g. text field has focus)
+    ///
+    /// Game input systems should ignore keyboard input when it is true
+    pub fn wants_keyboard_input(&self) -> bool {
+        self.wants_keyboard_input
+    }
+
+    pub(crate) fn get_enabled_ui_callbacks(&self) -> Vec<(String, UiCallbackFunction)> {
+        self.ui_callbacks.values()
+            .filter(|callback| callback.enabled)
+            .map(|callback| (callback.name.clone(), callback.callback_function))
+            .collect()
     }
 
-    pub(crate) fn update(&mut self, delta_time: f32) -> Result<()> {
-       
-        
+    pub(crate) fn update(&mut self, context: &egui::Context) -> Result<()> {
+        self.wants_pointer_input = context.wants_pointer_input();
+        self.wants_keyboard_input = context.wants_keyboard_input();
+
         Ok(())
     }
 }
 
 impl PillTypeMapKey for EguiManagerComponent {
-    type Storage = GlobalComponentStorage<EguiManagerComponent>; 
+    type Storage = GlobalComponentStorage<EguiManagerComponent>;
 }
 
 impl GlobalComponent for EguiManagerComponent {
-   
+
+}
+
+// Built-in engine window showing basic frame statistics
+fn engine_ui(engine: &mut Engine, context: &egui::Context) -> Result<()> {
+    let frame_delta_time = engine.frame_delta_time;
+    let entity_count = match engine.scene_manager.get_active_scene() {
+        Ok(scene) => scene.entities.len(),
+        Err(_) => 0,
+    };
+
+    egui::Window::new(ENGINE_UI_CALLBACK_NAME)
+        .default_open(true)
+        .resizable(true)
+        .anchor(egui::Align2::LEFT_TOP, [0.0, 0.0])
+        .show(context, |ui| {
+            ui.add(egui::Label::new(format!("FPS {:.0}", 1000.0 / frame_delta_time)));
+            ui.add(egui::Label::new(format!("Entities {}", entity_count)));
+        });
+
+    Ok(())
 }
\ No newline at end of file
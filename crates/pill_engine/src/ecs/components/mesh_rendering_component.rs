@@ -1,6 +1,6 @@
 use crate::{
     engine::Engine,
-    graphics::{ RenderQueueKey, compose_render_queue_key }, 
+    graphics::{ RenderQueueKey, LodGroup, RenderLayers, compose_render_queue_key }, 
     resources::{ Material, MaterialHandle, Mesh, MeshHandle, ResourceManager },
     ecs::{ EntityHandle, ComponentStorage, Component, SceneHandle, DeferredUpdateComponentRequest, DeferredUpdateManagerPointer, DeferredUpdateComponent }, 
     config::DEFAULT_MATERIAL_HANDLE,
@@ -15,6 +15,7 @@ use anyhow::{ Result, Context, Error };
 const DEFERRED_REQUEST_VARIANT_UPDATE_RENDER_QUEUE: usize = 0;
 const DEFERRED_REQUEST_VARIANT_SET_MATERIAL: usize = 1;
 const DEFERRED_REQUEST_VARIANT_SET_MESH: usize = 2;
+const DEFERRED_REQUEST_VARIANT_SET_LOD_GROUP: usize = 3;
 
 // --- Builder ---
 
@@ -39,6 +40,17 @@ impl MeshRenderingComponentBuilder {
         self
     }
 
+    // If LOD group is set its meshes are used instead of the mesh set directly
+    pub fn lod_group(mut self, lod_group: LodGroup) -> Self {
+        self.component.lod_group = Some(lod_group);
+        self
+    }
+
+    pub fn layers(mut self, layers: RenderLayers) -> Self {
+        self.component.layers = layers;
+        self
+    }
+
     pub fn build(self) -> MeshRenderingComponent {
         self.component
     }
@@ -52,7 +64,12 @@ pub struct MeshRenderingComponent {
     pub mesh_handle: Option<MeshHandle>,
     #[readonly]
     pub material_handle: Option<MaterialHandle>,
+    #[readonly]
+    pub lod_group: Option<LodGroup>,
+    pub layers: RenderLayers, // Component is rendered only by cameras whose culling mask contains at least one of its layers
     pub(crate) render_queue_key: Option<RenderQueueKey>, 
+    pub(crate) lod_render_queue_keys: Vec<Option<RenderQueueKey>>, // Render queue key for each level of LOD group
+    pub(crate) current_lod: Option<usize>,
 
     entity_handle: Option<EntityHandle>,
     scene_handle: Option<SceneHandle>,
@@ -68,7 +85,11 @@ impl MeshRenderingComponent {
         Self { 
             mesh_handle: None,
             material_handle: None,
+            lod_group: None,
+            layers: RenderLayers::default(),
             render_queue_key: None,
+            lod_render_queue_keys: Vec::<Option<RenderQueueKey>>::new(),
+            current_lod: None,
             entity_handle: None,
             scene_handle: None,
             deferred_update_manager: None,
@@ -95,6 +116,18 @@ impl MeshRenderingComponent {
         self.post_deferred_update_request(DEFERRED_REQUEST_VARIANT_UPDATE_RENDER_QUEUE);
     }
 
+    pub fn set_lod_group(&mut self, lod_group: LodGroup) {
+        self.lod_group = Some(lod_group);
+        self.current_lod = None;
+        self.post_deferred_update_request(DEFERRED_REQUEST_VARIANT_SET_LOD_GROUP);
+    }
+
+    pub fn remove_lod_group(&mut self) {
+        self.lod_group = None;
+        self.current_lod = None;
+        self.post_deferred_update_request(DEFERRED_REQUEST_VARIANT_UPDATE_RENDER_QUEUE);
+    }
+
     pub(crate) fn set_material_handle(&mut self, material_handle: Option<MaterialHandle>) {
         self.material_handle = material_handle;
     }
@@ -103,14 +136,55 @@ impl MeshRenderingComponent {
         self.mesh_handle = mesh_handle;
     }
 
+    // Removes mesh from the component and from its LOD group
+    pub(crate) fn remove_mesh_handle(&mut self, mesh_handle: &MeshHandle) {
+        if self.mesh_handle == Some(*mesh_handle) {
+            self.mesh_handle = None;
+        }
+        if let Some(lod_group) = self.lod_group.as_mut() {
+            lod_group.remove_mesh(mesh_handle);
+            self.current_lod = None;
+        }
+    }
+
+    // Checks if mesh is used by the component directly or by its LOD group
+    pub(crate) fn uses_mesh(&self, mesh_handle: &MeshHandle) -> bool {
+        self.mesh_handle == Some(*mesh_handle) || 
+            self.lod_group.as_ref().map_or(false, |v| v.get_levels().iter().any(|level| level.mesh_handle == *mesh_handle))
+    }
+
+    // Returns mesh whose bounds are used for culling and LOD selection (the most detailed level of LOD group if it is set)
+    pub(crate) fn get_bounds_mesh_handle(&self) -> Option<MeshHandle> {
+        match &self.lod_group {
+            Some(lod_group) => lod_group.get_levels().first().map(|v| v.mesh_handle),
+            None => self.mesh_handle,
+        }
+    }
+
+    // Returns render queue key of the currently selected LOD level or of the mesh if there is no LOD group
+    pub(crate) fn get_render_queue_key(&self) -> Option<RenderQueueKey> {
+        match &self.lod_group {
+            Some(_) => self.current_lod.and_then(|v| self.lod_render_queue_keys.get(v).copied().flatten()),
+            None => self.render_queue_key,
+        }
+    }
+
     pub(crate) fn update_render_queue_key(&mut self, resource_manager: &ResourceManager) -> Result<()> {
-        if self.mesh_handle.is_some() {
-            // Use default material if no material is set
-            let material_handle = match self.material_handle {
-                Some(v) => v,
-                None => DEFAULT_MATERIAL_HANDLE,
-            };
+        // Use default material if no material is set
+        let material_handle = match self.material_handle {
+            Some(v) => v,
+            None => DEFAULT_MATERIAL_HANDLE,
+        };
+
+        // Compose render queue keys of LOD levels
+        self.lod_render_queue_keys = match &self.lod_group {
+            Some(lod_group) => lod_group.get_levels().iter()
+                .map(|v| compose_render_queue_key(resource_manager, &material_handle, &v.mesh_handle).ok())
+                .collect(),
+            None => Vec::<Option<RenderQueueKey>>::new(),
+        };
 
+        if self.mesh_handle.is_some() {
             // Compose render queue key and set it
             if let Ok(render_queue_key) = compose_render_queue_key(resource_manager, &material_handle, &self.mesh_handle.unwrap()) 
             {
@@ -128,6 +202,11 @@ impl MeshRenderingComponent {
         Ok(())
     }
 
+    // Render queue key is updated by DeferredUpdateSystem, after resources used by this component are back in their storages
+    pub(crate) fn request_render_queue_key_update(&mut self) {
+        self.post_deferred_update_request(DEFERRED_REQUEST_VARIANT_UPDATE_RENDER_QUEUE);
+    }
+
     fn post_deferred_update_request(&mut self, request_variant: usize) {
         if self.deferred_update_manager.is_some() {
             let entity_handle = self.entity_handle.expect("Critical: Cannot post deferred update request. No EntityHandle set in Component");
@@ -160,6 +239,14 @@ impl Component for MeshRenderingComponent {
                 .context(format!("Creating {} {} failed", "Component".gobj_style(), get_type_name::<Self>().sobj_style()))?;
         }
 
+        // Check if LOD group mesh handles are valid
+        if let Some(lod_group) = &self.lod_group {
+            for level in lod_group.get_levels() {
+                engine.get_resource::<Mesh>(&level.mesh_handle)
+                    .context(format!("Creating {} {} failed", "Component".gobj_style(), get_type_name::<Self>().sobj_style()))?;
+            }
+        }
+
         // Update mesh rendering queue
         self.update_render_queue_key(&engine.resource_manager)?;
 
@@ -189,6 +276,18 @@ impl Component for MeshRenderingComponent {
 
                 self.update_render_queue_key(&engine.resource_manager)?;
             },
+            DEFERRED_REQUEST_VARIANT_SET_LOD_GROUP =>
+            {
+                // Check if LOD group mesh handles are valid
+                if let Some(lod_group) = &self.lod_group {
+                    for level in lod_group.get_levels() {
+                        engine.get_resource::<Mesh>(&level.mesh_handle)
+                            .context(format!("Setting {} {} failed", "LOD group".gobj_style(), "Mesh".sobj_style()))?;
+                    }
+                }
+
+                self.update_render_queue_key(&engine.resource_manager)?;
+            },
             DEFERRED_REQUEST_VARIANT_UPDATE_RENDER_QUEUE => 
             {
                 // Update mesh rendering queue
@@ -0,0 +1,260 @@
+use crate::{
+    ecs::components::{ GlobalComponent, GlobalComponentStorage },
+    engine::Engine,
+};
+
+use pill_core::PillTypeMapKey;
+
+use std::{
+    collections::VecDeque,
+    fmt::Write as FmtWrite,
+    fs::File,
+    io::Write,
+    path::Path,
+    time::Instant,
+};
+use anyhow::{ Result, Context, Error };
+use indexmap::IndexMap;
+
+pub const PROFILER_UI_CALLBACK_NAME: &str = "Profiler";
+pub const PROFILER_FRAME_SAMPLE_NAME: &str = "Frame";
+pub const PROFILER_RENDERER_SAMPLE_NAME: &str = "Renderer";
+pub const PROFILER_GPU_SAMPLE_NAME: &str = "GPU";
+
+// Categories used to group samples in the overlay and in the exported trace
+pub const PROFILER_CATEGORY_FRAME: &str = "Frame";
+pub const PROFILER_CATEGORY_PHASE: &str = "Phase";
+pub const PROFILER_CATEGORY_SYSTEM: &str = "System";
+pub const PROFILER_CATEGORY_RENDERER: &str = "Renderer";
+pub const PROFILER_CATEGORY_GPU: &str = "GPU";
+
+// --- Sample ---
+
+#[derive(Clone, Debug)]
+pub struct ProfilerSample {
+    pub name: String,
+    pub category: &'static str,
+    pub start: f64, // Microseconds since the profiler was created
+    pub duration: f64, // Microseconds
+}
+
+// --- Profiler Component ---
+
+pub struct ProfilerComponent {
+    pub enabled: bool,
+    pub show_overlay: bool,
+    origin: Instant,
+    history_length: usize,
+    trace_frame_count: usize,
+    current_frame: Vec<ProfilerSample>,
+    trace: VecDeque<Vec<ProfilerSample>>, // Samples of last frames used for trace export
+    history: IndexMap<String, (&'static str, VecDeque<f32>)>, // Rolling durations in milliseconds per sample name
+}
+
+impl ProfilerComponent {
+    pub fn new(enabled: bool, show_overlay: bool, history_length: usize, trace_frame_count: usize) -> Self {
+        Self {
+            enabled,
+            show_overlay,
+            origin: Instant::now(),
+            history_length: history_length.max(1),
+            trace_frame_count,
+            current_frame: Vec::<ProfilerSample>::new(),
+            trace: VecDeque::<Vec<ProfilerSample>>::with_capacity(trace_frame_count),
+            history: IndexMap::<String, (&'static str, VecDeque<f32>)>::new(),
+        }
+    }
+
+    /// Returns rolling durations (in milliseconds) of the sample with given name, oldest first
+    pub fn get_history(&self, name: &str) -> Option<&VecDeque<f32>> {
+        self.history.get(name).map(|(_, durations)| durations)
+    }
+
+    /// Returns duration (in milliseconds) of the sample with given name in the last finished frame
+    pub fn get_last_duration(&self, name: &str) -> Option<f32> {
+        self.get_history(name).and_then(|durations| durations.back().cloned())
+    }
+
+    /// Returns names of all recorded samples with their categories
+    pub fn get_sample_names(&self) -> impl Iterator<Item = (&String, &'static str)> {
+        self.history.iter().map(|(name, (category, _))| (name, *category))
+    }
+
+    /// Clears all recorded data
+    pub fn clear(&mut self) {
+        self.current_frame.clear();
+        self.trace.clear();
+        self.history.clear();
+    }
+
+    /// Writes recorded frames to the file in Chrome trace event format (open it in chrome://tracing or Perfetto)
+    pub fn export_chrome_trace(&self, path: &Path) -> Result<()> {
+        let mut json = String::from("{\"traceEvents\":[\n");
+        let mut first = true;
+        for sample in self.trace.iter().flatten() {
+            if !first {
+                json.push_str(",\n");
+            }
+            first = false;
+
+            // GPU samples are placed on separate track
+            let thread_id = if sample.category == PROFILER_CATEGORY_GPU { 1 } else { 0 };
+            write!(json,
+                "{{\"name\":\"{}\",\"cat\":\"{}\",\"ph\":\"X\",\"ts\":{:.3},\"dur\":{:.3},\"pid\":0,\"tid\":{}}}",
+                escape_json_string(&sample.name), sample.category, sample.start, sample.duration, thread_id
+            )?;
+        }
+        json.push_str("\n],\"displayTimeUnit\":\"ms\"}\n");
+
+        let mut file = File::create(path).context(format!("Cannot create trace file {}", path.display()))?;
+        file.write_all(json.as_bytes())?;
+
+        Ok(())
+    }
+
+    pub(crate) fn record(&mut self, name: &str, category: &'static str, start: Instant, end: Instant) {
+        if !self.enabled {
+            return;
+        }
+
+        let sample = ProfilerSample {
+            name: name.to_string(),
+            category,
+            start: start.saturating_duration_since(self.origin).as_secs_f64() * 1_000_000.0,
+            duration: end.saturating_duration_since(start).as_secs_f64() * 1_000_000.0,
+        };
+        self.current_frame.push(sample);
+    }
+
+    pub(crate) fn end_frame(&mut self, frame_start: Instant, gpu_frame_time: Option<f32>) {
+        if !self.enabled {
+            return;
+        }
+
+        let frame_end = Instant::now();
+        self.record(PROFILER_FRAME_SAMPLE_NAME, PROFILER_CATEGORY_FRAME, frame_start, frame_end);
+
+        // GPU time is reported with delay so it is only placed at the start of the frame in the trace
+        if let Some(gpu_frame_time) = gpu_frame_time {
+            let start = frame_start.saturating_duration_since(self.origin).as_secs_f64() * 1_000_000.0;
+            self.current_frame.push(ProfilerSample {
+                name: PROFILER_GPU_SAMPLE_NAME.to_string(),
+                category: PROFILER_CATEGORY_GPU,
+                start,
+                duration: gpu_frame_time as f64 * 1000.0,
+            });
+        }
+
+        // Update rolling history
+        let frame = std::mem::take(&mut self.current_frame);
+        for sample in frame.iter() {
+            let (_, durations) = self.history.entry(sample.name.clone())
+                .or_insert_with(|| (sample.category, VecDeque::<f32>::with_capacity(self.history_length)));
+            if durations.len() >= self.history_length {
+                durations.pop_front();
+            }
+            durations.push_back((sample.duration / 1000.0) as f32);
+        }
+
+        // Keep frame for trace export
+        if self.trace_frame_count > 0 {
+            if self.trace.len() >= self.trace_frame_count {
+                self.trace.pop_front();
+            }
+            self.trace.push_back(frame);
+        }
+    }
+}
+
+impl PillTypeMapKey for ProfilerComponent {
+    type Storage = GlobalComponentStorage<ProfilerComponent>;
+}
+
+impl GlobalComponent for ProfilerComponent {
+
+}
+
+fn escape_json_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for character in value.chars() {
+        match character {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// --- Overlay ---
+
+const PROFILER_GRAPH_SIZE: [f32; 2] = [260.0, 40.0];
+const PROFILER_SMALL_GRAPH_SIZE: [f32; 2] = [120.0, 14.0];
+
+pub fn profiler_ui(engine: &mut Engine, context: &egui::Context) -> Result<()> {
+    let profiler = engine.get_global_component::<ProfilerComponent>()?;
+    if !profiler.enabled || !profiler.show_overlay {
+        return Ok(());
+    }
+
+    egui::Window::new(PROFILER_UI_CALLBACK_NAME)
+        .default_open(true)
+        .resizable(true)
+        .anchor(egui::Align2::RIGHT_TOP, [0.0, 0.0])
+        .show(context, |ui| {
+            // Frame and GPU graphs
+            for name in [PROFILER_FRAME_SAMPLE_NAME, PROFILER_GPU_SAMPLE_NAME] {
+                if let Some(durations) = profiler.get_history(name) {
+                    let (average, max) = get_average_and_max(durations);
+                    ui.label(format!("{} {:.2}ms (max {:.2}ms)", name, average, max));
+                    draw_graph(ui, durations, max, PROFILER_GRAPH_SIZE, egui::Color32::LIGHT_GREEN);
+                }
+            }
+
+            // Per phase, system and renderer timings
+            egui::Grid::new("profiler_grid").striped(true).show(ui, |ui| {
+                for (name, category) in profiler.get_sample_names() {
+                    if category == PROFILER_CATEGORY_FRAME || category == PROFILER_CATEGORY_GPU {
+                        continue;
+                    }
+                    let durations = profiler.get_history(name).unwrap();
+                    let (average, max) = get_average_and_max(durations);
+                    ui.label(category);
+                    ui.label(name.as_str());
+                    ui.label(format!("{:.3}ms", average));
+                    ui.label(format!("{:.3}ms", max));
+                    draw_graph(ui, durations, max, PROFILER_SMALL_GRAPH_SIZE, egui::Color32::LIGHT_BLUE);
+                    ui.end_row();
+                }
+            });
+        });
+
+    Ok(())
+}
+
+fn get_average_and_max(durations: &VecDeque<f32>) -> (f32, f32) {
+    let max = durations.iter().cloned().fold(0.0, f32::max);
+    let average = match durations.len() {
+        0 => 0.0,
+        n => durations.iter().sum::<f32>() / n as f32,
+    };
+    (average, max)
+}
+
+fn draw_graph(ui: &mut egui::Ui, durations: &VecDeque<f32>, max: f32, size: [f32; 2], color: egui::Color32) {
+    let (response, painter) = ui.allocate_painter(egui::Vec2::from(size), egui::Sense::hover());
+    let rect = response.rect;
+    painter.rect_filled(rect, 0.0, egui::Color32::from_black_alpha(100));
+
+    if durations.len() < 2 || max <= 0.0 {
+        return;
+    }
+
+    let step = rect.width() / (durations.len() - 1) as f32;
+    let points = durations.iter().enumerate()
+        .map(|(i, duration)| egui::pos2(rect.left() + i as f32 * step, rect.bottom() - (duration / max) * rect.height()))
+        .collect::<Vec<egui::Pos2>>();
+    painter.add(egui::Shape::line(points, egui::Stroke::new(1.0, color)));
+}
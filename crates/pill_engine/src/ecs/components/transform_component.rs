@@ -2,7 +2,7 @@ use crate::{
     ecs::{ Component, ComponentStorage },
 };
 
-use pill_core::{ PillTypeMap, PillTypeMapKey, Vector3f };
+use pill_core::{ PillTypeMap, PillTypeMapKey, Vector3f, Matrix4f };
 
 use cgmath::Zero;
 
@@ -60,6 +60,19 @@ impl TransformComponent {
             scale: Vector3f::new(1.0, 1.0, 1.0),
         }
     }
+
+    // Returns matrix transforming from local to world space (scale, then rotation around X, Y and Z axis, then translation)
+    pub fn get_model_matrix(&self) -> Matrix4f {
+        Matrix4f::from_translation(self.position) * 
+        self.get_rotation_matrix() * 
+        Matrix4f::from_nonuniform_scale(self.scale.x, self.scale.y, self.scale.z)
+    }
+
+    pub fn get_rotation_matrix(&self) -> Matrix4f {
+        Matrix4f::from_angle_z(cgmath::Deg(self.rotation.z)) *
+        Matrix4f::from_angle_y(cgmath::Deg(self.rotation.y)) * 
+        Matrix4f::from_angle_x(cgmath::Deg(self.rotation.x))
+    }
 }
 
 impl PillTypeMapKey for TransformComponent {
@@ -22,6 +22,9 @@ pub use components:: {
 pub use components::camera_component::{
     CameraComponent,
     CameraAspectRatio,
+    CameraViewport,
+    CameraProjection,
+    CameraClearMode,
     get_renderer_resource_handle_from_camera_component,
 };
 
@@ -40,6 +43,18 @@ pub use components::audio_source_component::{
 
 pub use components::egui_manager_component::{
     EguiManagerComponent,
+    UiCallbackFunction,
+};
+
+pub use components::profiler_component::{
+    ProfilerComponent,
+    ProfilerSample,
+    profiler_ui,
+    PROFILER_UI_CALLBACK_NAME,
+    PROFILER_RENDERER_SAMPLE_NAME,
+    PROFILER_CATEGORY_PHASE,
+    PROFILER_CATEGORY_SYSTEM,
+    PROFILER_CATEGORY_RENDERER,
 };
 
 pub use components::deferred_update_component::{
@@ -95,6 +110,19 @@ pub use systems::audio_system::{
     audio_system,
 };
 
+#[cfg(all(debug_assertions, feature = "hot-reload"))]
+pub use systems::hot_reload_system::{
+    hot_reload_system,
+};
+
+pub use systems::resource_loading_system::{
+    resource_loading_system,
+};
+
+pub use systems::resource_unloading_system::{
+    resource_unloading_system,
+};
+
 // - Other
 
 pub use entity::{
@@ -0,0 +1,74 @@
+use crate::{
+    engine::Engine,
+    resources::{ LoadState, Resource, ResourceStorage, Texture, Mesh, Sound, Shader },
+};
+
+use pill_core::{ PillStyle, get_type_name };
+
+use std::{ collections::HashSet, path::PathBuf };
+use anyhow::{ Result, Context, Error };
+use log::{ info, error };
+
+pub fn hot_reload_system(engine: &mut Engine) -> Result<()> {
+    // Get paths of files that changed on disk since last frame
+    let changed_paths = match &engine.resource_watcher {
+        Some(resource_watcher) => resource_watcher.get_changed_paths(),
+        None => return Ok(()),
+    };
+
+    if changed_paths.is_empty() {
+        return Ok(())
+    }
+
+    // Reload resources loaded from changed files
+    reload_resources::<Texture>(engine, &changed_paths);
+    reload_resources::<Mesh>(engine, &changed_paths);
+    reload_resources::<Sound>(engine, &changed_paths);
+    reload_resources::<Shader>(engine, &changed_paths);
+
+    Ok(())
+}
+
+fn reload_resources<T>(engine: &mut Engine, changed_paths: &HashSet<PathBuf>) 
+    where T: Resource<Storage = ResourceStorage::<T>>
+{
+    // Find handles of resources whose files changed
+    let resource_storage = engine.resource_manager.get_resource_storage::<T>().expect("Critical: Resource not registered");
+    let resource_handles = resource_storage.data.iter()
+        .filter_map(|(resource_handle, resource)| {
+            let source_paths = resource.as_ref()?.get_source_paths();
+            source_paths.iter()
+                .filter_map(|source_path| source_path.canonicalize().ok())
+                .any(|source_path| changed_paths.contains(&source_path))
+                .then(|| resource_handle)
+        })
+        .collect::<Vec<T::Handle>>();
+
+    for resource_handle in resource_handles {
+        // Take resource from slot
+        let resource_slot = engine.resource_manager.get_resource_slot_mut::<T>(&resource_handle).expect("Critical: Resource not registered");
+        let mut resource = resource_slot.take().expect("Critical: Resource is None");
+
+        // Reload (failed reload keeps previous version of the resource so it is only reported)
+        let resource_name = resource.get_name();
+        let reloaded = match resource.reload(engine, resource_handle) {
+            Ok(_) => {
+                info!("{} {} {} reloaded", "Resource".gobj_style(), get_type_name::<T>().sobj_style(), resource_name.name_style());
+                true
+            },
+            Err(error) => {
+                error!("{:?}", error);
+                false
+            },
+        };
+
+        // Put resource back to slot
+        let resource_slot = engine.resource_manager.get_resource_slot_mut::<T>(&resource_handle).expect("Critical: Resource not registered");
+        let _ = resource_slot.insert(resource);
+
+        // Resource that failed to load is loaded now that its file is fixed
+        if reloaded && engine.resource_manager.get_resource_load_state::<T>(&resource_handle).expect("Critical: Resource not registered") == LoadState::Failed {
+            engine.resource_manager.set_resource_load_state::<T>(&resource_handle, LoadState::Loaded).expect("Critical: Resource not registered");
+        }
+    }
+}
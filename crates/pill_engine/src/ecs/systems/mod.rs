@@ -6,6 +6,10 @@ pub(crate) mod deferred_update_system;
 pub(crate) mod input_system;
 pub(crate) mod time_system;
 pub(crate) mod audio_system;
+#[cfg(all(debug_assertions, feature = "hot-reload"))]
+pub(crate) mod hot_reload_system;
+pub(crate) mod resource_loading_system;
+pub(crate) mod resource_unloading_system;
 
 // --- Use ---
 
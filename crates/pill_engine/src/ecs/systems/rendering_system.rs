@@ -1,83 +1,283 @@
 use crate::{
-    ecs::{ scene, CameraAspectRatio, CameraComponent, Component, ComponentStorage, EguiManagerComponent, EntityHandle, MeshRenderingComponent, TransformComponent }, 
-    engine::Engine, graphics::{ compose_render_queue_key, RenderQueueItem, RenderQueueKey, RendererError }, 
-    resources::{ Material, MaterialHandle, Mesh, MeshHandle, ResourceManager }
+    ecs::{ scene, CameraAspectRatio, CameraProjection, CameraComponent, Component, ComponentStorage, EguiManagerComponent, EntityHandle, MeshRenderingComponent, TransformComponent, PROFILER_RENDERER_SAMPLE_NAME, PROFILER_CATEGORY_RENDERER }, 
+    engine::Engine, graphics::{ compose_render_queue_key, is_render_queue_key_transparent, set_render_queue_key_depth, PostProcessSettings, RenderQueueItem, RenderQueueKey, RenderStatistics, RenderView, RenderLayers, RendererError, RendererTextureHandle }, 
+    resources::{ Material, MaterialHandle, Mesh, MeshHandle, ResourceManager, Texture, TextureHandle, TextureType }
 };
 
-use pill_core::{ EngineError, PillStyle, PillSlotMapKey };
+use pill_core::{ BoundingBox, EngineError, Frustum, PillStyle, PillSlotMapKey, Vector3f, get_game_error_message };
 
-use std::{ ops::Range };
+use std::{ collections::{ HashMap, HashSet }, ops::Range, time::Instant };
 use anyhow::{ Result, Context, Error };
+use cgmath::InnerSpace;
 use boolinator::Boolinator;
-use log::{ debug };
+use log::{ debug, error, warn };
+
+// Camera that scene is rendered from in this frame
+struct RenderingCamera {
+    entity_handle: EntityHandle,
+    priority: i32,
+    culling_mask: RenderLayers,
+    frustum: Frustum,
+    position: Vector3f,
+    direction: Vector3f,
+    projection: CameraProjection,
+    fov: f32,
+    far: f32,
+    skybox: Option<TextureHandle>,
+    render_target: Option<(TextureHandle, RendererTextureHandle)>, // None if camera renders to the window
+    post_process: Option<PostProcessSettings>,
+}
 
 pub fn rendering_system(engine: &mut Engine) -> Result<()> {
     let active_scene_handle = engine.scene_manager.get_active_scene_handle()?;
-    let mut active_camera_entity_handle_result: Option<EntityHandle> = None;
+    let mut cameras = Vec::<RenderingCamera>::new();
     
     {
         let active_scene = engine.scene_manager.get_active_scene_mut()?;
 
-        // - Find active camera and update its aspect ratio if needed
+        // - Find enabled cameras and update their aspect ratios if needed
 
-        // Find first enabled camera and use it as active
-        for (entity_handle, camera_component) in active_scene.get_one_component_iterator_mut::<CameraComponent>()? {
+        for (entity_handle, transform_component, camera_component) in active_scene.get_two_component_iterator_mut::<TransformComponent, CameraComponent>()? {
             if camera_component.enabled {
-                // Update active camera aspect ratio if it is set to automatic
+                // Get texture camera renders to and its size (skip camera if texture is not a render target or it is not created yet)
+                let (render_target, target_size) = match camera_component.render_target {
+                    Some(texture_handle) => {
+                        let render_target = engine.resource_manager.get_resource::<Texture>(&texture_handle).ok()
+                            .and_then(|v| v.render_target_size.zip(v.renderer_resource_handle));
+                        match render_target {
+                            Some((size, renderer_texture_handle)) => (Some((texture_handle, renderer_texture_handle)), size),
+                            None => {
+                                debug!("Invalid render target of camera");
+                                continue;
+                            },
+                        }
+                    },
+                    None => (None, (engine.window_size.width, engine.window_size.height)),
+                };
+
+                // Update camera aspect ratio if it is set to automatic (it matches part of the window or render target covered by camera viewport)
                 if let CameraAspectRatio::Automatic(_) = camera_component.aspect {
-                    let aspect_ratio = engine.window_size.width as f32 / engine.window_size.height as f32;
-                    camera_component.aspect = CameraAspectRatio::Automatic(aspect_ratio);
+                    let viewport_width = target_size.0 as f32 * camera_component.viewport.width;
+                    let viewport_height = target_size.1 as f32 * camera_component.viewport.height;
+                    if viewport_width > 0.0 && viewport_height > 0.0 {
+                        camera_component.aspect = CameraAspectRatio::Automatic(viewport_width / viewport_height);
+                    }
                 }
-                active_camera_entity_handle_result = Some(entity_handle);
-                break;
+
+                // Get volume visible by the camera
+                let frustum = Frustum::from_matrix(&camera_component.get_view_projection_matrix(transform_component));
+                cameras.push(RenderingCamera {
+                    entity_handle,
+                    priority: camera_component.priority,
+                    culling_mask: camera_component.culling_mask,
+                    frustum,
+                    position: transform_component.position,
+                    direction: camera_component.get_direction(transform_component),
+                    projection: camera_component.projection,
+                    fov: camera_component.fov,
+                    far: camera_component.range.end,
+                    skybox: camera_component.skybox,
+                    render_target,
+                    post_process: camera_component.post_process.clone(),
+                });
             }
         }
     }
 
-    let active_camera_entity_handle = active_camera_entity_handle_result.ok_or(Error::new(EngineError::NoActiveCamera))?.clone();
+    // Render cameras from lowest to highest priority (cameras with the same priority keep their order)
+    if cameras.is_empty() {
+        return Err(Error::new(EngineError::NoActiveCamera));
+    }
+    cameras.sort_by_key(|v| v.priority);
+
+    // Get post-process settings of the first camera rendering to the window or global ones, and renderer texture of color grading LUT (it is None if texture is not a color texture or is still loading)
+    let post_process_settings = cameras.iter().find(|v| v.render_target.is_none())
+        .and_then(|v| v.post_process.clone())
+        .unwrap_or_else(|| engine.post_process_settings.clone());
+
+    // Post-process is applied once to the whole window so settings of other cameras rendering to it cannot be used, report it once until it is resolved
+    let has_post_process_conflict = cameras.iter()
+        .filter(|v| v.render_target.is_none())
+        .skip(1)
+        .any(|v| v.post_process.as_ref().map_or(false, |v| *v != post_process_settings));
+    if has_post_process_conflict && !engine.post_process_conflict_reported {
+        warn!("Cameras rendering to the window have different post-process settings, only settings of the first one are used");
+    }
+    engine.post_process_conflict_reported = has_post_process_conflict;
+    let color_grading_lut_handle = post_process_settings.color_grading_lut.as_ref()
+        .and_then(|v| engine.resource_manager.get_resource::<Texture>(v).ok())
+        .filter(|v| matches!(v.texture_type, TextureType::Color))
+        .and_then(|v| v.renderer_resource_handle);
 
     // - Prepare rendering data
 
-    // Clear the render queue
-    engine.render_queue.clear();
+    // Prepare one render view per camera (render queues are cleared but their memory is reused)
+    engine.render_views.truncate(cameras.len());
+    for (camera_index, camera) in cameras.iter().enumerate() {
+        // Get renderer texture of the skybox (it is None if texture is not a cubemap or is still loading)
+        let skybox_texture_handle = camera.skybox.as_ref()
+            .and_then(|v| engine.resource_manager.get_resource::<Texture>(v).ok())
+            .filter(|v| matches!(v.texture_type, TextureType::Cubemap))
+            .and_then(|v| v.renderer_resource_handle);
+
+        let render_target_texture_handle = camera.render_target.map(|v| v.1);
+
+        match engine.render_views.get_mut(camera_index) {
+            Some(render_view) => {
+                render_view.camera_entity_handle = camera.entity_handle;
+                render_view.skybox_texture_handle = skybox_texture_handle;
+                render_view.render_target_texture_handle = render_target_texture_handle;
+                render_view.render_queue.clear();
+            },
+            None => engine.render_views.push(RenderView::new(camera.entity_handle, skybox_texture_handle, render_target_texture_handle)),
+        }
+    }
+
+    let mut render_statistics = RenderStatistics::default();
+    let mut visible_camera_indices = Vec::<usize>::with_capacity(cameras.len());
+
+    // Render targets sampled by materials are only needed if some camera renders to a render target
+    let render_target_cameras_exist = cameras.iter().any(|v| v.render_target.is_some());
+    let mut material_render_targets = HashMap::<MaterialHandle, Vec<TextureHandle>>::new();
+    let mut render_target_dependencies = HashSet::<(TextureHandle, TextureHandle)>::new(); // Render target and render target sampled while rendering it
+
     // Iterate mesh rendering components
     for (entity_handle, transform_component, mesh_rendering_component) in
-        engine.scene_manager.get_two_component_iterator::<TransformComponent, MeshRenderingComponent>(active_scene_handle)?
+        engine.scene_manager.get_two_component_iterator_mut::<TransformComponent, MeshRenderingComponent>(active_scene_handle)?
     {
-        // Add valid mesh rendering components to render queue
-        if let Some(render_queue_key) = mesh_rendering_component.render_queue_key {
+        // Get bounding box of the mesh in world space
+        let bounding_box = mesh_rendering_component.get_bounds_mesh_handle().as_ref()
+            .and_then(|v| engine.resource_manager.get_resource::<Mesh>(v).ok())
+            .and_then(|v| v.get_mesh_data())
+            .map(|v| v.bounding_box.transform(&transform_component.get_model_matrix()));
+
+        // Find cameras that see mesh rendering component and skip it if it is outside of view of all of them (each camera gets its own filtered render queue)
+        visible_camera_indices.clear();
+        for (camera_index, camera) in cameras.iter().enumerate() {
+            if !camera.culling_mask.intersects(&mesh_rendering_component.layers) {
+                continue;
+            }
+            let is_visible = match (engine.frustum_culling_enabled, bounding_box.as_ref()) {
+                (true, Some(bounding_box)) => camera.frustum.intersects_bounding_box(bounding_box),
+                _ => true,
+            };
+            if is_visible {
+                visible_camera_indices.push(camera_index);
+            }
+        }
+        if visible_camera_indices.is_empty() {
+            render_statistics.culled_count += 1;
+            continue;
+        }
+
+        // Select LOD level for camera that it covers the biggest part of and skip mesh rendering components that are too small to be rendered
+        if let Some(lod_group) = mesh_rendering_component.lod_group.as_ref() {
+            let screen_coverage = bounding_box.as_ref()
+                .map(|v| visible_camera_indices.iter()
+                    .map(|camera_index| get_screen_coverage(v, &cameras[*camera_index]))
+                    .fold(0.0, f32::max))
+                .unwrap_or(1.0);
+            mesh_rendering_component.current_lod = lod_group.select_level(mesh_rendering_component.current_lod, screen_coverage);
+            if mesh_rendering_component.current_lod.is_none() {
+                render_statistics.lod_culled_count += 1;
+                continue;
+            }
+        }
+
+        // Skip invalid mesh rendering components
+        let render_queue_key = match mesh_rendering_component.get_render_queue_key() {
+            Some(v) => v,
+            None => {
+                debug!("Invalid render queue key");
+                continue;
+            },
+        };
+
+        // Get render targets sampled by material of mesh rendering component
+        let sampled_render_targets = match (render_target_cameras_exist, mesh_rendering_component.material_handle) {
+            (true, Some(material_handle)) => material_render_targets.entry(material_handle)
+                .or_insert_with(|| get_sampled_render_targets(&engine.resource_manager, material_handle))
+                .as_slice(),
+            _ => &[],
+        };
+
+        // Add mesh rendering component to render queues of cameras that see it
+        for camera_index in visible_camera_indices.iter() {
+            let camera = &cameras[*camera_index];
+
+            // Skip mesh rendering components sampling render target of the camera (texture cannot be sampled while it is rendered to)
+            if let Some((render_target, _)) = camera.render_target {
+                if sampled_render_targets.contains(&render_target) {
+                    continue;
+                }
+                render_target_dependencies.extend(sampled_render_targets.iter().map(|v| (render_target, *v)));
+            }
+
+            // Set distance to the camera for transparent items so that they are sorted back to front
+            let mut render_queue_key = render_queue_key;
+            if is_render_queue_key_transparent(render_queue_key) {
+                let center = bounding_box.as_ref().map(|v| v.get_center()).unwrap_or(transform_component.position);
+                render_queue_key = set_render_queue_key_depth(render_queue_key, get_depth(center, camera), camera.far);
+            }
+
             let render_queue_item = RenderQueueItem {
                 key: render_queue_key,
                 entity_index: entity_handle.data().index as u32,
             };
-            engine.render_queue.push(render_queue_item);
-        } else {
-            debug!("Invalid render queue key");
-            continue;
+            engine.render_views[*camera_index].render_queue.push(render_queue_item);
         }
+        render_statistics.drawn_count += 1;
     }
+    engine.render_statistics = render_statistics;
 
-    // Sort render queue
-    engine.render_queue.sort();
+    // Sort render queues
+    for render_view in engine.render_views.iter_mut() {
+        render_view.render_queue.sort();
+    }
 
-    let egui_ui = EguiManagerComponent::get_ui(engine);// egui_manager_component.get_ui(engine);
+    // Order render views so that render targets are rendered before views that sample them
+    if render_target_cameras_exist {
+        let mut render_views = engine.render_views.drain(..).map(Some).collect::<Vec<Option<RenderView>>>();
+        for camera_index in get_render_view_order(&cameras, &render_target_dependencies) {
+            engine.render_views.push(render_views[camera_index].take().unwrap());
+        }
+    }
+
+    // - Build UI
+
+    // Start egui frame and run all enabled UI callbacks
+    let egui_context = engine.renderer.begin_egui_frame();
+    let ui_callbacks = engine.get_global_component::<EguiManagerComponent>()?.get_enabled_ui_callbacks();
+    for (ui_callback_name, ui_callback_function) in ui_callbacks {
+        let result = ui_callback_function(engine, &egui_context).context(EngineError::UiCallbackFailed(ui_callback_name));
+        if let Some(message) = get_game_error_message(result) {
+            error!("{}", message);
+        }
+    }
+
+    // Store information about UI focus so that game can ignore input consumed by UI
+    engine.get_global_component_mut::<EguiManagerComponent>()?.update(&egui_context)?;
 
     let active_scene = engine.scene_manager.get_active_scene_mut()?;
     // Get storages
     let camera_component_storage = active_scene.get_component_storage::<CameraComponent>()
-        .context(format!("{}: Cannot get active {}", "RenderingSystem".sobj_style(), "Camera".gobj_style()))?;
+        .context(format!("{}: Cannot get {}", "RenderingSystem".sobj_style(), "CameraComponents".sobj_style()))?;
     let transform_component_storage = active_scene.get_component_storage::<TransformComponent>()
         .context(format!("{}: Cannot get {}", "RenderingSystem".sobj_style(), "TransformComponents".sobj_style())).unwrap();
 
 
     // Render
-    match engine.renderer.render(
-        active_camera_entity_handle, 
-        &engine.render_queue, 
+    let render_start = Instant::now();
+    let render_result = engine.renderer.render(
+        &engine.render_views,
+        &post_process_settings,
+        color_grading_lut_handle,
         camera_component_storage,
         transform_component_storage,
-        egui_ui
-    ) {
+    );
+    engine.record_profiler_sample(PROFILER_RENDERER_SAMPLE_NAME, PROFILER_CATEGORY_RENDERER, render_start);
+
+    match render_result {
         Ok(_) => Ok(()),
         // Recreate lost surface
         Err(RendererError::SurfaceLost) => Ok(engine.renderer.resize(engine.window_size)),
@@ -86,4 +286,74 @@ pub fn rendering_system(engine: &mut Engine) -> Result<()> {
         // All other errors (Outdated, Timeout)
         Err(renderer_error) => Err(Error::new(renderer_error)),
     }
+}
+
+// Returns fraction of viewport height covered by sphere enclosing the bounding box
+fn get_screen_coverage(bounding_box: &BoundingBox, camera: &RenderingCamera) -> f32 {
+    let radius = bounding_box.get_size().magnitude() / 2.0;
+    match camera.projection {
+        CameraProjection::Perspective => {
+            let distance = (bounding_box.get_center() - camera.position).magnitude();
+            if distance <= radius {
+                return 1.0;
+            }
+            (radius / (distance * (camera.fov.to_radians() / 2.0).tan())).min(1.0)
+        },
+        // Size of objects does not depend on distance
+        CameraProjection::Orthographic(size) => (radius * 2.0 / size).min(1.0),
+    }
+}
+
+// Returns distance of the point from the camera, orthographic camera measures it along its direction since all its rays are parallel
+fn get_depth(point: Vector3f, camera: &RenderingCamera) -> f32 {
+    match camera.projection {
+        CameraProjection::Perspective => (point - camera.position).magnitude(),
+        CameraProjection::Orthographic(_) => (point - camera.position).dot(camera.direction).max(0.0),
+    }
+}
+
+// Returns render targets set in texture slots of the material
+fn get_sampled_render_targets(resource_manager: &ResourceManager, material_handle: MaterialHandle) -> Vec<TextureHandle> {
+    let material = match resource_manager.get_resource::<Material>(&material_handle) {
+        Ok(v) => v,
+        Err(_) => return Vec::new(),
+    };
+
+    material.textures.data.values()
+        .filter_map(|v| v.texture_handle)
+        .filter(|v| resource_manager.get_resource::<Texture>(v).map_or(false, |v| v.render_target_size.is_some()))
+        .collect()
+}
+
+// Returns indices of cameras in order they should be rendered in, cameras rendering to the same render target follow each other and cameras rendering to the window are last
+// Each render target is rendered after render targets it samples, if they sample each other the one rendered first shows content of the previous frame
+fn get_render_view_order(cameras: &[RenderingCamera], render_target_dependencies: &HashSet<(TextureHandle, TextureHandle)>) -> Vec<usize> {
+    // Get render targets in order of their first cameras
+    let mut render_targets = Vec::<TextureHandle>::new();
+    for camera in cameras.iter() {
+        if let Some((render_target, _)) = camera.render_target {
+            if !render_targets.contains(&render_target) {
+                render_targets.push(render_target);
+            }
+        }
+    }
+
+    // Take first render target that does not sample any of remaining ones (or the first one if all of them depend on each other)
+    let mut camera_order = Vec::<usize>::with_capacity(cameras.len());
+    while !render_targets.is_empty() {
+        let render_target_index = render_targets.iter()
+            .position(|render_target| !render_targets.iter().any(|v| render_target_dependencies.contains(&(*render_target, *v))))
+            .unwrap_or(0);
+        let render_target = render_targets.remove(render_target_index);
+        camera_order.extend(cameras.iter().enumerate()
+            .filter(|(_, camera)| camera.render_target.map(|v| v.0) == Some(render_target))
+            .map(|(camera_index, _)| camera_index));
+    }
+
+    // Add cameras rendering to the window
+    camera_order.extend(cameras.iter().enumerate()
+        .filter(|(_, camera)| camera.render_target.is_none())
+        .map(|(camera_index, _)| camera_index));
+
+    camera_order
 }
\ No newline at end of file
@@ -0,0 +1,15 @@
+use crate::engine::Engine;
+
+use anyhow::{ Result, Context, Error };
+
+pub fn resource_loading_system(engine: &mut Engine) -> Result<()> {
+    // Get loads finished on worker threads since last frame
+    let finished_loads = engine.resource_loader.get_finished_loads();
+
+    // Complete them on main thread (uploads data to the renderer)
+    for finished_load in finished_loads {
+        finished_load(engine);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use crate::{
+    engine::Engine,
+    resources::{ Resource, ResourceStorage, Texture, Material, Mesh, Sound },
+    ecs::TimeComponent,
+    config::DEFAULT_RESOURCE_PREFIX,
+};
+
+use pill_core::{ PillStyle, get_type_name };
+
+use anyhow::{ Result, Context, Error };
+use log::debug;
+
+pub fn resource_unloading_system(engine: &mut Engine) -> Result<()> {
+    if !engine.resource_manager.auto_unload {
+        return Ok(())
+    }
+
+    // Counting references of all resources scans all components, so it is done only once per interval
+    let time = engine.get_global_component::<TimeComponent>()?.time;
+    if time < engine.resource_manager.next_auto_unload_time {
+        return Ok(())
+    }
+    engine.resource_manager.next_auto_unload_time = time + engine.resource_manager.auto_unload_interval;
+
+    // Materials are unloaded first so textures used only by them can be unloaded in the same frame
+    unload_unused_resources::<Material>(engine)?;
+    unload_unused_resources::<Texture>(engine)?;
+    unload_unused_resources::<Mesh>(engine)?;
+    unload_unused_resources::<Sound>(engine)?;
+
+    Ok(())
+}
+
+fn unload_unused_resources<T>(engine: &mut Engine) -> Result<()> 
+    where T: Resource<Storage = ResourceStorage::<T>>
+{
+    // Find resources that are in use and resources that were used before but are not anymore
+    let resource_storage = engine.resource_manager.get_resource_storage::<T>()?;
+    let mut used_resource_handles = Vec::<T::Handle>::new();
+    let mut unused_resource_handles = Vec::<T::Handle>::new();
+    for (resource_handle, resource) in resource_storage.data.iter() {
+        let resource = resource.as_ref().expect("Critical: Resource is None");
+        if resource.get_name().starts_with(DEFAULT_RESOURCE_PREFIX) {
+            continue;
+        }
+
+        if resource.get_reference_count(engine, resource_handle)? > 0 {
+            used_resource_handles.push(resource_handle);
+        }
+        else if resource_storage.used_resources.contains(&resource_handle) {
+            unused_resource_handles.push(resource_handle);
+        }
+    }
+
+    // Remember used resources
+    let resource_storage = engine.resource_manager.get_resource_storage_mut::<T>()?;
+    resource_storage.used_resources.extend(used_resource_handles);
+
+    // Remove resources that are not used anymore
+    for resource_handle in unused_resource_handles {
+        debug!("Unloading unused {} {}", "Resource".gobj_style(), get_type_name::<T>().sobj_style());
+        engine.remove_resource::<T>(&resource_handle)?;
+    }
+
+    Ok(())
+}
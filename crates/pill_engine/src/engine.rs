@@ -15,10 +15,10 @@ use pill_core::{
     get_enum_variant_type_name, get_game_error_message, Vector2f, 
 };
 
-use std::{ any::type_name, any::Any, any::TypeId, collections::VecDeque, cell::RefCell, ops::RangeBounds };
+use std::{ any::type_name, any::Any, any::TypeId, collections::VecDeque, cell::RefCell, ops::RangeBounds, path::Path, sync::Arc, time::Instant };
 use anyhow::{Context, Result, Error};
 use boolinator::Boolinator;
-use log::{debug, info, error};
+use log::{debug, info, warn, error};
 use winit::{ dpi::PhysicalPosition, event::KeyEvent,};
 
 // -------------------------------------------------------------------------------
@@ -42,9 +42,17 @@ pub struct Engine {
     pub(crate) scene_manager: SceneManager,
     pub(crate) system_manager: SystemManager,
     pub(crate) resource_manager: ResourceManager,
+    #[cfg(all(debug_assertions, feature = "hot-reload"))]
+    pub(crate) resource_watcher: Option<ResourceWatcher>,
+    pub(crate) resource_loader: ResourceLoader,
     pub(crate) global_components: PillTypeMap,
     pub(crate) input_queue: VecDeque<InputEvent>,
-    pub(crate) render_queue: Vec<RenderQueueItem>,
+    pub(crate) render_views: Vec<RenderView>, // One per enabled camera, kept between frames to reuse render queue allocations
+    pub(crate) render_statistics: RenderStatistics,
+    pub(crate) frustum_culling_enabled: bool,
+    pub(crate) post_process_settings: PostProcessSettings, // Used by cameras without their own post-process settings
+    pub(crate) post_process_conflict_reported: bool, // Cameras rendering to the window have different post-process settings and it was already reported
+    pub(crate) max_frame_rate: Option<u32>, // Frame rate is not limited if not set
     pub(crate) window_size: winit::dpi::PhysicalSize<u32>,
     pub(crate) frame_delta_time: f32,
 }
@@ -53,28 +61,123 @@ pub struct Engine {
 
 /// Pill Engine internal functions
 impl Engine {
+    pub(crate) fn record_profiler_sample(&mut self, name: &str, category: &'static str, start: Instant) {
+        let end = Instant::now();
+        if let Ok(profiler_component) = self.get_global_component_mut::<ProfilerComponent>() {
+            profiler_component.record(name, category, start, end);
+        }
+    }
+
+    // Finishes loading of resource added with add_resource_async, called on main thread when data loader is done
+    fn finish_resource_loading<T>(&mut self, resource_handle: T::Handle, data: Result<ResourceData>) 
+        where T: Resource<Storage = ResourceStorage::<T>>
+    {
+        // Get resource slot (it may happen that this resource was removed while loading, if so then just continue)
+        let resource_slot = match self.resource_manager.get_resource_slot_mut::<T>(&resource_handle) {
+            Ok(v) => v,
+            Err(_) => return,
+        };
+
+        // Take resource from slot
+        let mut resource = resource_slot.take().expect("Critical: Resource is None");
+
+        // Initialize resource with loaded data
+        let result = data.and_then(|data| resource.initialize_with_data(self, resource_handle, data))
+            .context(format!("Loading {} {} {} failed", "Resource".gobj_style(), get_type_name::<T>().sobj_style(), resource.get_name().name_style()));
+        let load_state = match result {
+            Ok(_) => {
+                debug!("{} {} {} loaded", "Resource".gobj_style(), get_type_name::<T>().sobj_style(), resource.get_name().name_style());
+                LoadState::Loaded
+            },
+            Err(error) => {
+                error!("{:?}", error);
+                LoadState::Failed
+            },
+        };
+
+        // Put resource back to slot
+        let resource_slot = self.resource_manager.get_resource_slot_mut::<T>(&resource_handle).expect("Critical: Resource not registered");
+        let _ = resource_slot.insert(resource);
+
+        // Set load state
+        self.resource_manager.set_resource_load_state::<T>(&resource_handle, load_state).expect("Critical: Resource not registered");
+    }
+
+    // Applies removal policy to resource that is going to be removed
+    fn prepare_resource_removal<T>(&mut self, resource_handle: &T::Handle) -> Result<()> 
+        where T: Resource<Storage = ResourceStorage::<T>>
+    {
+        // Get number of resources and components using this resource
+        let resource = self.resource_manager.get_resource::<T>(resource_handle)?;
+        let reference_count = resource.get_reference_count(self, *resource_handle)?;
+        if reference_count == 0 {
+            return Ok(())
+        }
+
+        match self.resource_manager.removal_policy {
+            ResourceRemovalPolicy::Refuse => {
+                Err(Error::new(EngineError::ResourceInUse(get_type_name::<T>(), resource.get_name(), reference_count)))
+            },
+            ResourceRemovalPolicy::Cascade => {
+                // Take resource from slot
+                let resource_slot = self.resource_manager.get_resource_slot_mut::<T>(resource_handle)?;
+                let mut resource = resource_slot.take().expect("Critical: Resource is None");
+
+                // Remove resources that depend on it
+                let result = resource.remove_dependent_resources(self, *resource_handle);
+
+                // Put resource back to slot
+                let resource_slot = self.resource_manager.get_resource_slot_mut::<T>(resource_handle).expect("Critical: Resource not registered");
+                let _ = resource_slot.insert(resource);
+
+                result
+            },
+        }
+    }
+
     fn create_default_resources(&mut self) -> Result<()> {
 
         let max_texture_count = self.config.get_int("MAX_TEXTURES").unwrap_or(MAX_TEXTURES as i64) as usize;
         let max_mesh_count = self.config.get_int("MAX_MESHES").unwrap_or(MAX_MESHES as i64) as usize;
+        let max_shader_count = self.config.get_int("MAX_SHADERS").unwrap_or(MAX_SHADERS as i64) as usize;
         let max_material_count = self.config.get_int("MAX_MATERIALS").unwrap_or(MAX_MATERIALS as i64) as usize;
         let max_sound_count = self.config.get_int("MAX_SOUNDS").unwrap_or(MAX_SOUNDS as i64) as usize;
 
         self.register_resource_type::<Texture>(max_texture_count)?;
         self.register_resource_type::<Mesh>(max_mesh_count)?;
+        self.register_resource_type::<Shader>(max_shader_count)?;
         self.register_resource_type::<Material>(max_material_count)?;
         self.register_resource_type::<Sound>(max_sound_count)?;
 
         // - Create default resources
 
-        // Load master shader data to executable
-        let master_vertex_shader_bytes = include_bytes!("../res/shaders/built/master.vert.spv");
-        let master_fragment_shader_bytes = include_bytes!("../res/shaders/built/master.frag.spv");
-        self.renderer.set_master_pipeline(master_vertex_shader_bytes, master_fragment_shader_bytes)?;
+        // Load skybox shader data to executable
+        let skybox_vertex_shader_source = include_str!("../res/shaders/skybox.vert.wgsl");
+        let skybox_fragment_shader_source = include_str!("../res/shaders/skybox.frag.wgsl");
+        self.renderer.set_skybox_pipeline(skybox_vertex_shader_source, skybox_fragment_shader_source)?;
+
+        // Load clear shader data to executable
+        let clear_vertex_shader_source = include_str!("../res/shaders/clear.vert.wgsl");
+        let clear_fragment_shader_source = include_str!("../res/shaders/clear.frag.wgsl");
+        self.renderer.set_clear_pipelines(clear_vertex_shader_source, clear_fragment_shader_source)?;
+
+        // Load post-process shader data to executable
+        let post_process_shaders = PostProcessShaders {
+            vertex_shader_source: include_str!("../res/shaders/post_process.vert.wgsl"),
+            bloom_prefilter_shader_source: include_str!("../res/shaders/bloom_prefilter.frag.wgsl"),
+            bloom_downsample_shader_source: include_str!("../res/shaders/bloom_downsample.frag.wgsl"),
+            bloom_upsample_shader_source: include_str!("../res/shaders/bloom_upsample.frag.wgsl"),
+            composite_shader_source: include_str!("../res/shaders/composite.frag.wgsl"),
+            fxaa_shader_source: include_str!("../res/shaders/fxaa.frag.wgsl"),
+        };
+        self.renderer.set_post_process_pipelines(&post_process_shaders)?;
 
         // Load default resource data to executable
         let default_color_texture_bytes = Box::new(*include_bytes!("../res/textures/default_color.png"));
         let default_normal_texture_bytes = Box::new(*include_bytes!("../res/textures/default_normal.png"));
+        let default_cubemap_texture_bytes = Box::new(*include_bytes!("../res/textures/default_cubemap.png"));
+        let master_vertex_shader_bytes = Box::new(*include_bytes!("../res/shaders/master.vert.wgsl"));
+        let master_fragment_shader_bytes = Box::new(*include_bytes!("../res/shaders/master.frag.wgsl"));
 
         // Create default textures
         let mut default_color_texture = Texture::new(DEFAULT_COLOR_TEXTURE_NAME, TextureType::Color, ResourceLoadType::Bytes(default_color_texture_bytes));
@@ -84,40 +187,52 @@ impl Engine {
         let mut default_normal_texture = Texture::new(DEFAULT_NORMAL_TEXTURE_NAME, TextureType::Normal, ResourceLoadType::Bytes(default_normal_texture_bytes));
         default_normal_texture.initialize(self)?;
         self.resource_manager.add_resource(default_normal_texture)?;
+
+        // Default cubemap is uniform dark gray so that materials without environment still get some ambient light
+        let mut default_cubemap_texture = Texture::new(DEFAULT_CUBEMAP_TEXTURE_NAME, TextureType::Cubemap, ResourceLoadType::Bytes(default_cubemap_texture_bytes));
+        default_cubemap_texture.initialize(self)?;
+        self.resource_manager.add_resource(default_cubemap_texture)?;
         
+        // Create default shader (master shader)
+        let mut default_shader = Shader::new(DEFAULT_SHADER_NAME, ResourceLoadType::Bytes(master_vertex_shader_bytes), ResourceLoadType::Bytes(master_fragment_shader_bytes));
+        default_shader.initialize(self)?;
+        self.resource_manager.add_resource(default_shader)?;
+
         // Create default material
         let mut default_material = Material::new(DEFAULT_MATERIAL_NAME);
         default_material.initialize(self)?;
         self.resource_manager.add_resource(default_material)?;
+
+        // Create default meshes
+        let default_meshes = [
+            Mesh::from_mesh_data(DEFAULT_CUBE_MESH_NAME, MeshData::cube(1.0)),
+            Mesh::from_mesh_data(DEFAULT_SPHERE_MESH_NAME, MeshData::uv_sphere(0.5, 32, 16)),
+            Mesh::from_mesh_data(DEFAULT_PLANE_MESH_NAME, MeshData::plane(1.0)),
+            Mesh::from_mesh_data(DEFAULT_CYLINDER_MESH_NAME, MeshData::cylinder(0.5, 1.0, 32)),
+            Mesh::from_mesh_data(DEFAULT_CAPSULE_MESH_NAME, MeshData::capsule(0.5, 2.0, 32, 8)),
+            Mesh::from_mesh_data(DEFAULT_TORUS_MESH_NAME, MeshData::torus(0.5, 0.2, 32, 16)),
+        ];
+        for mut default_mesh in default_meshes {
+            default_mesh.initialize(self)?;
+            let (default_mesh_handle, default_mesh) = self.resource_manager.add_resource(default_mesh)?;
+            default_mesh.pass_handle(default_mesh_handle);
+        }
         
         Ok(())
     }
 }
 
 // ---- INTERNAL API -----------------------------------------------------------------
-struct EguiUI<'a> {
-    frame_delta_time: &'a f32, // Use a reference to frame_delta_time
-}
 
-impl<'a> EguiUI<'a> {
-    fn render(&self, ui: &egui::Context) {
-        egui::Window::new("PillEngine")
-            .default_open(true)
-            .resizable(true)
-            .anchor(egui::Align2::LEFT_TOP, [0.0, 0.0])
-            .show(ui, |ui| {
-                if ui.add(egui::Button::new("Click me")).clicked() {
-                    println!("PRESSED");
-                    println!("{}", self.frame_delta_time);
-                }
-            });
-    }
-}
 /// Pill Engine internal API
 #[cfg(feature = "internal")]
 impl Engine {
     pub fn new(game: Box<dyn PillGame>, renderer: Box<dyn PillRenderer>, config: config::Config) -> Self {
         let max_entity_count = config.get_int("MAX_ENTITIES").unwrap_or(MAX_ENTITIES as i64) as usize;
+        let resource_loading_thread_count = config.get_int("RESOURCE_LOADING_THREAD_COUNT").unwrap_or(RESOURCE_LOADING_THREAD_COUNT as i64) as usize;
+        let frustum_culling_enabled = config.get_bool("FRUSTUM_CULLING_ENABLED").unwrap_or(FRUSTUM_CULLING_ENABLED);
+        let post_process_settings = PostProcessSettings::from_config(&config);
+        let max_frame_rate = config.get_int("MAX_FRAME_RATE").unwrap_or(MAX_FRAME_RATE as i64).max(0) as u32;
 
         Self { 
             config,
@@ -126,9 +241,17 @@ impl Engine {
             scene_manager: SceneManager::new(max_entity_count),
             system_manager: SystemManager::new(),
             resource_manager: ResourceManager::new(),
+            #[cfg(all(debug_assertions, feature = "hot-reload"))]
+            resource_watcher: None,
+            resource_loader: ResourceLoader::new(resource_loading_thread_count),
             global_components: PillTypeMap::new(),
             input_queue: VecDeque::new(),
-            render_queue: Vec::<RenderQueueItem>::with_capacity(max_entity_count),
+            render_views: Vec::<RenderView>::new(),
+            render_statistics: RenderStatistics::default(),
+            frustum_culling_enabled,
+            post_process_settings,
+            post_process_conflict_reported: false,
+            max_frame_rate: (max_frame_rate > 0).then_some(max_frame_rate),
             window_size: winit::dpi::PhysicalSize::<u32>::default(),
             frame_delta_time: 0.0.into(),
         }
@@ -150,6 +273,13 @@ impl Engine {
         self.add_global_component(DeferredUpdateComponent::new())?;
         self.add_global_component(EguiManagerComponent::new())?;
 
+        let profiler_enabled = self.config.get_bool("PROFILER_ENABLED").unwrap_or(PROFILER_ENABLED);
+        let profiler_overlay = self.config.get_bool("PROFILER_OVERLAY").unwrap_or(PROFILER_OVERLAY);
+        let profiler_history_length = self.config.get_int("PROFILER_HISTORY_LENGTH").unwrap_or(PROFILER_HISTORY_LENGTH as i64) as usize;
+        let profiler_trace_frame_count = self.config.get_int("PROFILER_TRACE_FRAME_COUNT").unwrap_or(PROFILER_TRACE_FRAME_COUNT as i64) as usize;
+        self.add_global_component(ProfilerComponent::new(profiler_enabled, profiler_overlay, profiler_history_length, profiler_trace_frame_count))?;
+        self.add_ui_callback(PROFILER_UI_CALLBACK_NAME, profiler_ui)?;
+
         let max_ambient_sink_count = self.config.get_int("MAX_CONCURRENT_2D_SOUNDS").unwrap_or(MAX_CONCURRENT_2D_SOUNDS as i64) as usize;
         let max_spatial_sink_count = self.config.get_int("MAX_CONCURRENT_3D_SOUNDS").unwrap_or(MAX_CONCURRENT_3D_SOUNDS as i64) as usize;
         self.add_global_component(AudioManagerComponent::new(max_ambient_sink_count, max_spatial_sink_count))?;
@@ -157,11 +287,41 @@ impl Engine {
 
         // Add built-in systems
         self.system_manager.add_system("InputSystem", input_system, UpdatePhase::PreGame)?;
+        #[cfg(all(debug_assertions, feature = "hot-reload"))]
+        self.system_manager.add_system("HotReloadSystem", hot_reload_system, UpdatePhase::PreGame)?;
+        self.system_manager.add_system("ResourceLoadingSystem", resource_loading_system, UpdatePhase::PreGame)?;
         self.system_manager.add_system("TimeSystem", time_system, UpdatePhase::PostGame)?;
+        self.system_manager.add_system("ResourceUnloadingSystem", resource_unloading_system, UpdatePhase::PostGame)?;
         self.system_manager.add_system("RenderingSystem", rendering_system, UpdatePhase::PostGame)?;
         self.system_manager.add_system("AudioSystem", audio_system, UpdatePhase::PostGame)?;
         self.system_manager.add_system("DeferredUpdateSystem", deferred_update_system, UpdatePhase::PostGame)?;
 
+        // Set resource lifetime policies
+        let removal_policy = self.config.get_str("RESOURCE_REMOVAL_POLICY").unwrap_or(RESOURCE_REMOVAL_POLICY.to_string());
+        self.resource_manager.removal_policy = match removal_policy.as_str() {
+            "Refuse" => ResourceRemovalPolicy::Refuse,
+            _ => ResourceRemovalPolicy::Cascade,
+        };
+        self.resource_manager.auto_unload = self.config.get_bool("RESOURCE_AUTO_UNLOAD").unwrap_or(RESOURCE_AUTO_UNLOAD);
+        self.resource_manager.auto_unload_interval = self.config.get_float("RESOURCE_AUTO_UNLOAD_INTERVAL").map(|v| v as f32).unwrap_or(RESOURCE_AUTO_UNLOAD_INTERVAL).max(0.0);
+
+        // Mount resource archive of the game (present only in shipped builds)
+        let resource_archive = self.config.get_str("RESOURCE_ARCHIVE").unwrap_or(RESOURCE_ARCHIVE.to_string());
+        let resource_archive_path = std::env::current_dir()?.join(resource_archive);
+        if resource_archive_path.exists() {
+            mount_resource_archive(&resource_archive_path).context("Failed to mount resource archive")?;
+        }
+
+        // Watch resource folder of the game (debug builds with hot-reload feature only)
+        #[cfg(all(debug_assertions, feature = "hot-reload"))]
+        if self.config.get_bool("HOT_RELOAD_ENABLED").unwrap_or(HOT_RELOAD_ENABLED) {
+            let resource_path = std::env::current_dir()?.join("res");
+            match ResourceWatcher::new(&resource_path) {
+                Ok(resource_watcher) => self.resource_watcher = Some(resource_watcher),
+                Err(error) => warn!("Hot reload disabled: {:?}", error),
+            }
+        }
+
         // Create default resources
         self.create_default_resources().context("Failed to create default resources")?;
 
@@ -188,28 +348,42 @@ impl Engine {
     /// Runs all systems in order: PreGame -> Game -> PostGame 
     pub fn update(&mut self, delta_time: std::time::Duration) {
         let stop_on_game_errors = self.config.get_bool("PANIC_ON_GAME_ERRORS").unwrap_or(PANIC_ON_GAME_ERRORS);
+        let frame_start = Instant::now();
         
         // Run systems
         let update_phase_count = self.system_manager.update_phases.len();
         for i in (0..update_phase_count).rev() {
+            let phase_start = Instant::now();
             let systems_count = self.system_manager.update_phases[i].len();
             for j in (0..systems_count).rev() {
                 let system = &self.system_manager.update_phases[i][j];
                 if !system.enabled { continue; }
                 let system_name = system.name.to_string();
+                let system_start = Instant::now();
 
                 if system.update_phase.clone() == UpdatePhase::Game && stop_on_game_errors {
                     let mut result = (system.system_function)(self);
-                    result = result.context(EngineError::SystemUpdateFailed(system_name, get_enum_variant_type_name(self.system_manager.update_phases.get_index(i).unwrap().0)));
+                    result = result.context(EngineError::SystemUpdateFailed(system_name.clone(), get_enum_variant_type_name(self.system_manager.update_phases.get_index(i).unwrap().0)));
                     if let Some(message) = get_game_error_message(result) {
                         error!("{}", message);
                     }
                 }
                 else {
                     let result = (system.system_function)(self);
-                    result.context(EngineError::SystemUpdateFailed(system_name, get_enum_variant_type_name(self.system_manager.update_phases.get_index(i).unwrap().0))).unwrap();
+                    result.context(EngineError::SystemUpdateFailed(system_name.clone(), get_enum_variant_type_name(self.system_manager.update_phases.get_index(i).unwrap().0))).unwrap();
                 }
+
+                self.record_profiler_sample(&system_name, PROFILER_CATEGORY_SYSTEM, system_start);
             }
+
+            let phase_name = format!("{}", self.system_manager.update_phases.get_index(i).unwrap().0);
+            self.record_profiler_sample(&phase_name, PROFILER_CATEGORY_PHASE, phase_start);
+        }
+
+        // Finish profiler frame
+        let gpu_frame_time = self.renderer.get_gpu_frame_time();
+        if let Ok(profiler_component) = self.get_global_component_mut::<ProfilerComponent>() {
+            profiler_component.end_frame(frame_start, gpu_frame_time);
         }
  
         // Update FPS counter
@@ -271,6 +445,11 @@ impl Engine {
        self.renderer.pass_input_to_egui(event);
     }
 
+    /// Returns minimal time between frames resulting from frame rate limit (None if frame rate is not limited)
+    pub fn get_min_frame_time(&self) -> Option<std::time::Duration> {
+        self.max_frame_rate.map(|max_frame_rate| std::time::Duration::from_secs_f64(1.0 / max_frame_rate as f64))
+    }
+
     pub fn get_input_queue(&self) -> &VecDeque<InputEvent> {
         &self.input_queue
     }
@@ -304,6 +483,29 @@ impl Engine {
         self.system_manager.toggle_system(name, UpdatePhase::Game, enabled).context(format!("Toggling {} failed", "System".gobj_style()))
     }
     
+    // --- UI API ---
+
+    /// Adds game-defined egui UI callback, called every frame with access to the engine
+    pub fn add_ui_callback(&mut self, name: &str, callback_function: UiCallbackFunction) -> Result<()> {
+        debug!("Adding {} {}", "UiCallback".gobj_style(), name.name_style());
+
+        self.get_global_component_mut::<EguiManagerComponent>()?.add_ui_callback(name, callback_function).context(format!("Adding {} failed", "UiCallback".gobj_style()))
+    }
+
+    /// Removes egui UI callback
+    pub fn remove_ui_callback(&mut self, name: &str) -> Result<()> {
+        debug!("Removing {} {}", "UiCallback".gobj_style(), name.name_style());
+
+        self.get_global_component_mut::<EguiManagerComponent>()?.remove_ui_callback(name).context(format!("Removing {} failed", "UiCallback".gobj_style()))
+    }
+
+    /// Toggles egui UI callback
+    pub fn toggle_ui_callback(&mut self, name: &str, enabled: bool) -> Result<()> {
+        debug!("Toggling {} {} to {} state", "UiCallback".gobj_style(), name.name_style(), if enabled { "Enabled" } else { "Disabled" });
+
+        self.get_global_component_mut::<EguiManagerComponent>()?.toggle_ui_callback(name, enabled).context(format!("Toggling {} failed", "UiCallback".gobj_style()))
+    }
+
     // --- Entity API ---
 
     /// Returns EntityBuilder, allowing for handy entity creation
@@ -606,6 +808,89 @@ impl Engine {
         Ok(resource_handle)
     }
 
+    /// Adds resource to the engine and loads its data on worker thread
+    /// 
+    /// Returned handle can be used right away. Until loading is finished textures are replaced with default ones
+    /// and meshes are not rendered. Resources that do not load any data are added synchronously
+    pub fn add_resource_async<T>(&mut self, mut resource: T) -> Result<T::Handle> 
+        where T: Resource<Storage = ResourceStorage::<T>> + Send + 'static
+    {
+        debug!("Adding {} {} {} asynchronously", "Resource".gobj_style(), get_type_name::<T>().sobj_style(), resource.get_name().name_style());
+
+        // Check if resource has proper name
+        let resource_name = resource.get_name();
+        if resource_name.starts_with(DEFAULT_RESOURCE_PREFIX) {
+            return Err(Error::new(EngineError::WrongResourceName(resource_name.clone())))
+        }
+
+        // Get data loader
+        let data_loader = match resource.get_data_loader(&self.resource_manager) {
+            Some(v) => v,
+            None => return self.add_resource(resource),
+        };
+
+        // Let resource keep what it needs from the engine (initialize is not called for resources loaded asynchronously)
+        resource.prepare_data_loading(self);
+
+        // Add resource before it is loaded so its handle can be used right away
+        let add_result = self.resource_manager.add_resource(resource)
+            .context(format!("Adding {} {} failed", "Resource".gobj_style(), get_type_name::<T>().sobj_style()))?;
+        let resource_handle = add_result.0;
+        let resource = add_result.1;
+
+        // Pass handle to this resource so it can store it if needed
+        resource.pass_handle(resource_handle);
+        self.resource_manager.set_resource_load_state::<T>(&resource_handle, LoadState::Loading)?;
+
+        // Load data on worker thread and finish loading on main thread
+        self.resource_loader.load(Box::new(move || {
+            let data = data_loader();
+            Box::new(move |engine: &mut Engine| engine.finish_resource_loading::<T>(resource_handle, data))
+        }));
+
+        Ok(resource_handle)
+    }
+
+    /// Returns number of resources and components that use resource associated with resource handle
+    pub fn get_resource_reference_count<T>(&self, resource_handle: &T::Handle) -> Result<usize> 
+        where T: Resource<Storage = ResourceStorage::<T>>
+    {
+        let resource = self.resource_manager.get_resource::<T>(resource_handle)?;
+        resource.get_reference_count(self, *resource_handle)
+    }
+
+    /// Sets what happens when resource that is still in use is removed
+    pub fn set_resource_removal_policy(&mut self, removal_policy: ResourceRemovalPolicy) {
+        self.resource_manager.removal_policy = removal_policy;
+    }
+
+    /// Enables automatic unloading of resources that were used but are not used by any resource or component anymore
+    pub fn set_resource_auto_unload(&mut self, enabled: bool) {
+        self.resource_manager.auto_unload = enabled;
+    }
+
+    /// Sets how often (in seconds) resources are checked for being unused when automatic unloading is enabled
+    pub fn set_resource_auto_unload_interval(&mut self, interval: f32) {
+        self.resource_manager.auto_unload_interval = interval.max(0.0);
+    }
+
+    /// Registers loader creating data of type D from asset files with its extensions (replaces loaders already registered for them)
+    pub fn register_asset_loader<D: 'static>(&mut self, asset_loader: impl AssetLoader<D> + 'static) {
+        self.resource_manager.register_asset_loader::<D>(asset_loader);
+    }
+
+    /// Returns loader registered for extension of the asset, can be used in custom resources to load their data
+    pub fn get_asset_loader<D: 'static>(&self, path: &Path) -> Result<Arc<dyn AssetLoader<D>>> {
+        self.resource_manager.get_asset_loader::<D>(path)
+    }
+
+    /// Returns load state of resource associated with resource handle
+    pub fn get_resource_load_state<T>(&self, resource_handle: &T::Handle) -> Result<LoadState> 
+        where T: Resource<Storage = ResourceStorage::<T>>
+    {
+        Ok(self.resource_manager.get_resource_load_state::<T>(resource_handle)?)
+    }
+
     // Returns resource associated with resource handle
     pub fn get_resource<'a, T>(&'a self, resource_handle: &'a T::Handle) -> Result<&'a T> 
         where T: Resource<Storage = ResourceStorage::<T>>
@@ -653,6 +938,9 @@ impl Engine {
             return Err(Error::new(EngineError::RemoveDefaultResource(resource_name.clone()))).context(error_message.to_string())
         }
 
+        // Check if resource can be removed
+        self.prepare_resource_removal::<T>(resource_handle).context(error_message.to_string())?;
+
         // Remove and destroy resource
         let mut remove_result = self.resource_manager.remove_resource::<T>(resource_handle).context(error_message.to_string())?;
         remove_result.1.destroy(self, *resource_handle)?;
@@ -674,10 +962,229 @@ impl Engine {
             return Err(Error::new(EngineError::RemoveDefaultResource(name.to_string()))).context(error_message.to_string())
         }
 
+        // Check if resource can be removed
+        let resource_handle = self.resource_manager.get_resource_handle::<T>(name).context(error_message.to_string())?;
+        self.prepare_resource_removal::<T>(&resource_handle).context(error_message.to_string())?;
+
         // Remove resource
         let mut remove_result = self.resource_manager.remove_resource_by_name::<T>(name).context(error_message.to_string())?;
         remove_result.1.destroy(self, remove_result.0)?;
 
         Ok(())
     }
+
+    // --- Rendering ---
+
+    /// Returns number of drawn and culled mesh rendering components in the last frame
+    pub fn get_render_statistics(&self) -> RenderStatistics {
+        self.render_statistics
+    }
+
+    /// Returns size of the window in pixels (e.g. to normalize mouse position for camera screen rays)
+    pub fn get_window_size(&self) -> Vector2f {
+        Vector2f::new(self.window_size.width as f32, self.window_size.height as f32)
+    }
+
+    /// Enables skipping of entities that are outside of active camera view
+    pub fn set_frustum_culling(&mut self, enabled: bool) {
+        self.frustum_culling_enabled = enabled;
+    }
+
+    /// Sets post-process settings used by cameras without their own settings (e.g. to set global color grading LUT)
+    pub fn set_post_process_settings(&mut self, post_process_settings: PostProcessSettings) {
+        self.post_process_settings = post_process_settings;
+    }
+
+    pub fn get_post_process_settings(&self) -> &PostProcessSettings {
+        &self.post_process_settings
+    }
+
+    /// Sets how presented frames are synchronized with display refresh rate
+    /// 
+    /// If display does not support given mode, the closest supported one is used
+    pub fn set_vsync_mode(&mut self, vsync_mode: VsyncMode) {
+        self.renderer.set_vsync_mode(vsync_mode);
+    }
+
+    pub fn get_vsync_mode(&self) -> VsyncMode {
+        self.renderer.get_vsync_mode()
+    }
+
+    /// Sets number of samples per pixel used for multisample anti-aliasing (1 disables it)
+    /// 
+    /// Returns error if sample count is not supported by GPU (see get_supported_msaa_sample_counts)
+    pub fn set_msaa_sample_count(&mut self, sample_count: u32) -> Result<()> {
+        self.renderer.set_msaa_sample_count(sample_count)
+    }
+
+    pub fn get_msaa_sample_count(&self) -> u32 {
+        self.renderer.get_msaa_sample_count()
+    }
+
+    pub fn get_supported_msaa_sample_counts(&self) -> Vec<u32> {
+        self.renderer.get_supported_msaa_sample_counts()
+    }
+
+    /// Limits number of frames per second (None removes the limit)
+    pub fn set_max_frame_rate(&mut self, max_frame_rate: Option<u32>) {
+        self.max_frame_rate = max_frame_rate.filter(|v| *v > 0);
+    }
+
+    pub fn get_max_frame_rate(&self) -> Option<u32> {
+        self.max_frame_rate
+    }
+
+    // --- Profiling ---
+
+    /// Enables recording of durations of systems, renderer and GPU frames
+    pub fn set_profiler_enabled(&mut self, enabled: bool) -> Result<()> {
+        self.get_global_component_mut::<ProfilerComponent>()?.enabled = enabled;
+        Ok(())
+    }
+
+    pub fn is_profiler_enabled(&self) -> Result<bool> {
+        Ok(self.get_global_component::<ProfilerComponent>()?.enabled)
+    }
+
+    /// Writes recorded frames to the file in Chrome trace event format (open it in chrome://tracing or Perfetto)
+    pub fn export_profiler_trace(&self, path: &Path) -> Result<()> {
+        self.get_global_component::<ProfilerComponent>()?.export_chrome_trace(path)
+    }
+}
+
+#[cfg(all(test, feature = "game", feature = "internal"))]
+mod test {
+    use super::*;
+    use pill_core::PillSlotMap;
+
+    struct TestGame;
+
+    impl PillGame for TestGame {
+        fn start(&self, engine: &mut Engine) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    // Renderer without GPU that only keeps track of created resources (using destroyed resource is an error)
+    struct TestRenderer {
+        meshes: PillSlotMap<RendererMeshHandle, ()>,
+        textures: PillSlotMap<RendererTextureHandle, ()>,
+        shaders: PillSlotMap<RendererShaderHandle, ()>,
+        materials: PillSlotMap<RendererMaterialHandle, RendererPipelineHandle>,
+        pipelines: PillSlotMap<RendererPipelineHandle, ()>,
+        cameras: PillSlotMap<RendererCameraHandle, ()>,
+    }
+
+    impl TestRenderer {
+        fn create() -> Self {
+            Self {
+                meshes: PillSlotMap::with_key(),
+                textures: PillSlotMap::with_key(),
+                shaders: PillSlotMap::with_key(),
+                materials: PillSlotMap::with_key(),
+                pipelines: PillSlotMap::with_key(),
+                cameras: PillSlotMap::with_key(),
+            }
+        }
+    }
+
+    fn check<K: PillSlotMapKey, V>(storage: &PillSlotMap<K, V>, handle: K) -> Result<()> {
+        storage.contains_key(handle).ok_or(Error::new(RendererError::RendererResourceNotFound))
+    }
+
+    fn destroy<K: PillSlotMapKey, V>(storage: &mut PillSlotMap<K, V>, handle: K) -> Result<()> {
+        storage.remove(handle).map(|_| ()).ok_or(Error::new(RendererError::RendererResourceNotFound))
+    }
+
+    impl PillRenderer for TestRenderer {
+        fn new(window: Arc<winit::window::Window>, config: config::Config) -> Self { Self::create() }
+
+        fn resize(&mut self, new_window_size: winit::dpi::PhysicalSize<u32>) {}
+        fn set_skybox_pipeline(&mut self, vertex_shader_source: &str, fragment_shader_source: &str) -> Result<()> { Ok(()) }
+        fn set_clear_pipelines(&mut self, vertex_shader_source: &str, fragment_shader_source: &str) -> Result<()> { Ok(()) }
+        fn set_post_process_pipelines(&mut self, post_process_shaders: &PostProcessShaders) -> Result<()> { Ok(()) }
+        fn set_vsync_mode(&mut self, vsync_mode: VsyncMode) {}
+        fn set_msaa_sample_count(&mut self, sample_count: u32) -> Result<()> { Ok(()) }
+        fn get_vsync_mode(&self) -> VsyncMode { VsyncMode::On }
+        fn get_msaa_sample_count(&self) -> u32 { 1 }
+        fn get_supported_msaa_sample_counts(&self) -> Vec<u32> { vec![1] }
+
+        fn create_mesh(&mut self, name: &str, mesh_data: &MeshData) -> Result<RendererMeshHandle> { Ok(self.meshes.insert(())) }
+        fn create_texture(&mut self, name: &str, texture_data: &TextureData, settings: &TextureSettings) -> Result<RendererTextureHandle> { Ok(self.textures.insert(())) }
+        fn create_render_target_texture(&mut self, name: &str, width: u32, height: u32, settings: &TextureSettings) -> Result<RendererTextureHandle> { Ok(self.textures.insert(())) }
+        fn create_shader(&mut self, name: &str, shader_data: &ShaderData) -> Result<RendererShaderHandle> { Ok(self.shaders.insert(())) }
+        fn create_material(&mut self, name: &str, renderer_shader_handle: RendererShaderHandle, blend_mode: BlendMode, textures: &MaterialTextureMap, parameters: &MaterialParameterMap) -> Result<RendererMaterialHandle> {
+            check(&self.shaders, renderer_shader_handle)?;
+            let pipeline_handle = self.pipelines.insert(());
+            Ok(self.materials.insert(pipeline_handle))
+        }
+        fn create_camera(&mut self) -> Result<RendererCameraHandle> { Ok(self.cameras.insert(())) }
+
+        fn update_mesh(&mut self, renderer_mesh_handle: RendererMeshHandle, mesh_data: &MeshData) -> Result<()> { check(&self.meshes, renderer_mesh_handle) }
+        fn update_shader(&mut self, renderer_shader_handle: RendererShaderHandle, shader_data: &ShaderData) -> Result<()> { check(&self.shaders, renderer_shader_handle) }
+        fn update_material_textures(&mut self, renderer_material_handle: RendererMaterialHandle, textures: &MaterialTextureMap) -> Result<()> { check(&self.materials, renderer_material_handle) }
+        fn update_material_parameters(&mut self, renderer_material_handle: RendererMaterialHandle, parameters: &MaterialParameterMap) -> Result<()> { check(&self.materials, renderer_material_handle) }
+        fn update_material_blend_mode(&mut self, renderer_material_handle: RendererMaterialHandle, blend_mode: BlendMode) -> Result<()> { check(&self.materials, renderer_material_handle) }
+
+        fn get_material_pipeline_handle(&self, renderer_material_handle: RendererMaterialHandle) -> Result<RendererPipelineHandle> {
+            self.materials.get(renderer_material_handle).copied().ok_or(Error::new(RendererError::RendererResourceNotFound))
+        }
+
+        fn destroy_mesh(&mut self, renderer_mesh_handle: RendererMeshHandle) -> Result<()> { destroy(&mut self.meshes, renderer_mesh_handle) }
+        fn destroy_texture(&mut self, renderer_texture_handle: RendererTextureHandle) -> Result<()> { destroy(&mut self.textures, renderer_texture_handle) }
+        fn destroy_shader(&mut self, renderer_shader_handle: RendererShaderHandle) -> Result<()> { destroy(&mut self.shaders, renderer_shader_handle) }
+        fn destroy_material(&mut self, renderer_material_handle: RendererMaterialHandle) -> Result<()> { destroy(&mut self.materials, renderer_material_handle) }
+        fn destroy_camera(&mut self, renderer_camera_handle: RendererCameraHandle) -> Result<()> { destroy(&mut self.cameras, renderer_camera_handle) }
+
+        fn pass_input_to_egui(&mut self, event: &winit::event::WindowEvent) -> Result<()> { Ok(()) }
+        fn begin_egui_frame(&mut self) -> egui::Context { egui::Context::default() }
+        fn get_gpu_frame_time(&self) -> Option<f32> { None }
+
+        fn render(&mut self, 
+            render_views: &[RenderView],
+            post_process_settings: &PostProcessSettings,
+            color_grading_lut_handle: Option<RendererTextureHandle>,
+            camera_component_storage: &ComponentStorage<CameraComponent>,
+            transform_component_storage: &ComponentStorage<TransformComponent>,
+        ) -> Result<(), RendererError> {
+            Ok(())
+        }
+    }
+
+    fn create_engine() -> Engine {
+        let mut engine = Engine::new(Box::new(TestGame), Box::new(TestRenderer::create()), config::Config::default());
+        engine.add_global_component(DeferredUpdateComponent::new()).unwrap();
+        engine.create_default_resources().unwrap();
+        engine
+    }
+
+    #[test]
+    fn engine_remove_texture_in_use_with_cascade_policy() {
+        let mut engine = create_engine();
+        assert_eq!(engine.resource_manager.removal_policy, ResourceRemovalPolicy::Cascade);
+
+        // Create material with texture and entity that uses it
+        let texture_bytes = Box::new(*include_bytes!("../res/textures/default_color.png"));
+        let texture_handle = engine.add_resource(Texture::new("Texture", TextureType::Color, ResourceLoadType::Bytes(texture_bytes))).unwrap();
+        let material = Material::builder("Material").texture("BaseColor", texture_handle).unwrap().build();
+        let material_handle = engine.add_resource(material).unwrap();
+        let mesh_handle = engine.get_resource_handle::<Mesh>(DEFAULT_CUBE_MESH_NAME).unwrap();
+
+        let scene_handle = engine.create_scene("Scene").unwrap();
+        engine.register_component::<MeshRenderingComponent>(scene_handle).unwrap();
+        let entity_handle = engine.create_entity(scene_handle).unwrap();
+        let mesh_rendering_component = MeshRenderingComponent::builder().mesh(&mesh_handle).material(&material_handle).build();
+        engine.add_component_to_entity(scene_handle, entity_handle, mesh_rendering_component).unwrap();
+        let component = engine.scene_manager.get_entity_component::<MeshRenderingComponent>(entity_handle, scene_handle).unwrap();
+        component.update_render_queue_key(&engine.resource_manager).unwrap();
+
+        // Removing texture removes material that uses it and component falls back to default material
+        engine.remove_resource::<Texture>(&texture_handle).unwrap();
+        assert!(engine.get_resource::<Material>(&material_handle).is_err());
+
+        let expected_render_queue_key = compose_render_queue_key(&engine.resource_manager, &DEFAULT_MATERIAL_HANDLE, &mesh_handle).unwrap();
+        let component = engine.scene_manager.get_entity_component::<MeshRenderingComponent>(entity_handle, scene_handle).unwrap();
+        assert!(component.material_handle.is_none());
+        assert_eq!(component.get_render_queue_key(), Some(expected_render_queue_key));
+    }
 }
\ No newline at end of file
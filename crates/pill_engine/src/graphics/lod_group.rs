@@ -0,0 +1,58 @@
+use crate::{
+    resources::MeshHandle,
+    config::DEFAULT_LOD_HYSTERESIS,
+};
+
+use pill_core::select_lod_level;
+
+// --- LOD level ---
+
+#[derive(Clone, Copy, Debug)]
+pub struct LodLevel {
+    pub mesh_handle: MeshHandle,
+    pub screen_coverage: f32, // Minimal fraction of screen height covered by entity for this level to be used
+}
+
+// --- LOD group ---
+
+// Meshes with different level of detail, the one used is selected every frame by how big entity is on screen
+// If entity is smaller than threshold of the last level it is not rendered
+#[derive(Clone, Debug)]
+pub struct LodGroup {
+    levels: Vec<LodLevel>, // Sorted from the most detailed one
+    hysteresis: f32,
+}
+
+impl LodGroup {
+    pub fn new() -> Self {
+        Self {
+            levels: Vec::<LodLevel>::new(),
+            hysteresis: DEFAULT_LOD_HYSTERESIS,
+        }
+    }
+
+    pub fn level(mut self, mesh_handle: &MeshHandle, screen_coverage: f32) -> Self {
+        self.levels.push(LodLevel { mesh_handle: *mesh_handle, screen_coverage: screen_coverage.max(0.0) });
+        self.levels.sort_by(|a, b| b.screen_coverage.partial_cmp(&a.screen_coverage).unwrap());
+        self
+    }
+
+    // Relative margin around thresholds that has to be crossed before level is switched (prevents popping when entity is near threshold)
+    pub fn hysteresis(mut self, hysteresis: f32) -> Self {
+        self.hysteresis = hysteresis.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn get_levels(&self) -> &[LodLevel] {
+        &self.levels
+    }
+
+    pub(crate) fn remove_mesh(&mut self, mesh_handle: &MeshHandle) {
+        self.levels.retain(|v| v.mesh_handle != *mesh_handle);
+    }
+
+    // Returns index of the level to use (None if entity is too small to be rendered)
+    pub(crate) fn select_level(&self, current_level: Option<usize>, screen_coverage: f32) -> Option<usize> {
+        select_lod_level(self.levels.iter().map(|v| v.screen_coverage), self.hysteresis, current_level, screen_coverage)
+    }
+}
@@ -2,6 +2,10 @@
 
 mod renderer;
 mod render_queue;
+mod render_view;
+mod render_statistics;
+mod lod_group;
+mod post_process;
 
 // --- Use ---
 
@@ -14,6 +18,8 @@ pub use renderer::{
     RendererMeshHandle,
     RendererTextureHandle,
     RendererPipelineHandle,
+    RendererShaderHandle,
+    VsyncMode,
 };
 
 pub use render_queue::{
@@ -22,5 +28,24 @@ pub use render_queue::{
     RenderQueueKey,
     compose_render_queue_key,
     decompose_render_queue_key,
+    is_render_queue_key_transparent,
+    set_render_queue_key_depth,
     RENDER_QUEUE_KEY_ORDER,
 };
+
+pub use render_view::RenderView;
+
+pub use pill_core::RenderLayers;
+
+pub use render_statistics::RenderStatistics;
+
+pub use lod_group::{
+    LodGroup,
+    LodLevel,
+};
+
+pub use post_process::{
+    PostProcessSettings,
+    PostProcessShaders,
+    Tonemapping,
+};
@@ -0,0 +1,132 @@
+use crate::{
+    resources::TextureHandle,
+    config::*,
+};
+
+// --- Tonemapping ---
+
+// Maps HDR colors of the scene to displayable range
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tonemapping {
+    None, // Colors are clamped
+    Reinhard,
+    Aces,
+}
+
+impl Tonemapping {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "None" => Some(Tonemapping::None),
+            "Reinhard" => Some(Tonemapping::Reinhard),
+            "Aces" => Some(Tonemapping::Aces),
+            _ => None,
+        }
+    }
+}
+
+// --- Post-process settings ---
+
+// Settings of passes applied to HDR image of the scene before it is presented (in order: bloom, exposure and tonemapping, color grading, vignette, FXAA)
+// Global settings are read from config and can be replaced with Engine::set_post_process_settings (color grading LUT can only be set this way), camera can override them with its own settings
+#[derive(Clone, Debug, PartialEq)]
+pub struct PostProcessSettings {
+    pub exposure: f32, // Multiplier of scene colors applied before tonemapping
+    pub tonemapping: Tonemapping,
+    pub bloom_enabled: bool,
+    pub bloom_threshold: f32, // Brightness above which colors start to bloom
+    pub bloom_intensity: f32,
+    pub fxaa_enabled: bool,
+    pub vignette_enabled: bool,
+    pub vignette_intensity: f32, // Darkening of screen corners from 0 to 1
+    pub vignette_smoothness: f32, // Width of transition between not darkened center and corners from 0 to 1
+    pub color_grading_lut: Option<TextureHandle>, // Color texture with N slices of NxN size placed horizontally (e.g. 256x16), color grading is disabled if not set
+    pub color_grading_intensity: f32, // Blend between original and graded colors from 0 to 1
+}
+
+impl PostProcessSettings {
+    pub fn new() -> Self {
+        Self {
+            exposure: POST_PROCESS_EXPOSURE,
+            tonemapping: Tonemapping::from_name(POST_PROCESS_TONEMAPPING).expect("Critical: Invalid default tonemapping"),
+            bloom_enabled: POST_PROCESS_BLOOM_ENABLED,
+            bloom_threshold: POST_PROCESS_BLOOM_THRESHOLD,
+            bloom_intensity: POST_PROCESS_BLOOM_INTENSITY,
+            fxaa_enabled: POST_PROCESS_FXAA_ENABLED,
+            vignette_enabled: POST_PROCESS_VIGNETTE_ENABLED,
+            vignette_intensity: POST_PROCESS_VIGNETTE_INTENSITY,
+            vignette_smoothness: POST_PROCESS_VIGNETTE_SMOOTHNESS,
+            color_grading_lut: None,
+            color_grading_intensity: POST_PROCESS_COLOR_GRADING_INTENSITY,
+        }
+    }
+
+    // Creates settings from config, values that are not in config are default ones
+    pub(crate) fn from_config(config: &config::Config) -> Self {
+        let default_settings = Self::new();
+        let get_float = |key: &str, default: f32| config.get_float(key).map(|v| v as f32).unwrap_or(default);
+        let get_bool = |key: &str, default: bool| config.get_bool(key).unwrap_or(default);
+
+        Self {
+            exposure: get_float("POST_PROCESS_EXPOSURE", default_settings.exposure).max(0.0),
+            tonemapping: config.get_str("POST_PROCESS_TONEMAPPING").ok()
+                .and_then(|v| Tonemapping::from_name(&v))
+                .unwrap_or(default_settings.tonemapping),
+            bloom_enabled: get_bool("POST_PROCESS_BLOOM_ENABLED", default_settings.bloom_enabled),
+            bloom_threshold: get_float("POST_PROCESS_BLOOM_THRESHOLD", default_settings.bloom_threshold).max(0.0),
+            bloom_intensity: get_float("POST_PROCESS_BLOOM_INTENSITY", default_settings.bloom_intensity).max(0.0),
+            fxaa_enabled: get_bool("POST_PROCESS_FXAA_ENABLED", default_settings.fxaa_enabled),
+            vignette_enabled: get_bool("POST_PROCESS_VIGNETTE_ENABLED", default_settings.vignette_enabled),
+            vignette_intensity: get_float("POST_PROCESS_VIGNETTE_INTENSITY", default_settings.vignette_intensity).clamp(0.0, 1.0),
+            vignette_smoothness: get_float("POST_PROCESS_VIGNETTE_SMOOTHNESS", default_settings.vignette_smoothness).clamp(0.0, 1.0),
+            color_grading_lut: None,
+            color_grading_intensity: get_float("POST_PROCESS_COLOR_GRADING_INTENSITY", default_settings.color_grading_intensity).clamp(0.0, 1.0),
+        }
+    }
+
+    pub fn exposure(mut self, exposure: f32) -> Self {
+        self.exposure = exposure.max(0.0);
+        self
+    }
+
+    pub fn tonemapping(mut self, tonemapping: Tonemapping) -> Self {
+        self.tonemapping = tonemapping;
+        self
+    }
+
+    pub fn bloom(mut self, enabled: bool, threshold: f32, intensity: f32) -> Self {
+        self.bloom_enabled = enabled;
+        self.bloom_threshold = threshold.max(0.0);
+        self.bloom_intensity = intensity.max(0.0);
+        self
+    }
+
+    pub fn fxaa(mut self, enabled: bool) -> Self {
+        self.fxaa_enabled = enabled;
+        self
+    }
+
+    pub fn vignette(mut self, enabled: bool, intensity: f32, smoothness: f32) -> Self {
+        self.vignette_enabled = enabled;
+        self.vignette_intensity = intensity.clamp(0.0, 1.0);
+        self.vignette_smoothness = smoothness.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn color_grading(mut self, lut: Option<TextureHandle>, intensity: f32) -> Self {
+        self.color_grading_lut = lut;
+        self.color_grading_intensity = intensity.clamp(0.0, 1.0);
+        self
+    }
+}
+
+// --- Post-process shaders ---
+
+// WGSL sources of post-process passes, all fragment shaders use the same full screen vertex shader
+pub struct PostProcessShaders<'a> {
+    pub vertex_shader_source: &'a str,
+    pub bloom_prefilter_shader_source: &'a str,
+    pub bloom_downsample_shader_source: &'a str,
+    pub bloom_upsample_shader_source: &'a str,
+    pub composite_shader_source: &'a str,
+    pub fxaa_shader_source: &'a str,
+}
@@ -10,7 +10,7 @@ use crate::{
     config::*,
 };
 
-use pill_core::PillSlotMapKey;
+use pill_core::{ PillSlotMapKey, EngineError };
 
 use std::{
     cmp::Ordering,
@@ -99,23 +99,45 @@ where
     }
 }
 
-// Creates pill engine render queue composed from order, material index, material version, mesh index, mesh version
+// Creates pill engine render queue composed from pass, order, depth, pipeline index, material index, material version, mesh index, mesh version
+// Depth is left empty, it is set for transparent items each frame since it depends on camera
 pub fn compose_render_queue_key(resource_manager: &ResourceManager, material_handle: &MaterialHandle, mesh_handle: &MeshHandle) -> Result<RenderQueueKey> { 
     let material = resource_manager.get_resource::<Material>(material_handle)?;
     let mesh = resource_manager.get_resource::<Mesh>(mesh_handle)?;
 
+    // Mesh that is still loading cannot be rendered, material that is still loading is replaced with default one
+    let mesh_renderer_resource_handle = mesh.renderer_resource_handle.ok_or(Error::new(EngineError::ResourceNotLoaded("Mesh".to_string(), mesh.name.clone())))?;
+    let material_renderer_resource_handle = material.renderer_resource_handle.unwrap_or(DEFAULT_RENDERER_MATERIAL_HANDLE);
+    let pipeline_renderer_resource_handle = material.renderer_pipeline_handle.unwrap_or(DEFAULT_RENDERER_PIPELINE_HANDLE);
+
     let render_queue_key: RenderQueueKey = 
+        ((material.blend_mode.is_transparent() as RenderQueueKey) << RENDER_QUEUE_KEY_PASS.mask_shift) | // Transparent items are drawn after opaque ones
         ((RENDER_QUEUE_KEY_ORDER.max - material.rendering_order as RenderQueueKey) << RENDER_QUEUE_KEY_ORDER.mask_shift) | // Order has to be inverted for proper sorting
-        ((material.renderer_resource_handle.unwrap().data().index as RenderQueueKey) << RENDER_QUEUE_KEY_MATERIAL_INDEX.mask_shift) | 
-        ((material.renderer_resource_handle.unwrap().data().version.get() as RenderQueueKey) << RENDER_QUEUE_KEY_MATERIAL_VERSION.mask_shift) | 
-        ((mesh.renderer_resource_handle.unwrap().data().index as RenderQueueKey) << RENDER_QUEUE_KEY_MESH_INDEX.mask_shift ) | 
-        ((mesh.renderer_resource_handle.unwrap().data().version.get() as RenderQueueKey) << RENDER_QUEUE_KEY_MESH_VERSION.mask_shift);
+        ((pipeline_renderer_resource_handle.data().index as RenderQueueKey) << RENDER_QUEUE_KEY_PIPELINE_INDEX.mask_shift) | // Materials using the same pipeline are drawn together
+        ((material_renderer_resource_handle.data().index as RenderQueueKey) << RENDER_QUEUE_KEY_MATERIAL_INDEX.mask_shift) | 
+        ((material_renderer_resource_handle.data().version.get() as RenderQueueKey) << RENDER_QUEUE_KEY_MATERIAL_VERSION.mask_shift) | 
+        ((mesh_renderer_resource_handle.data().index as RenderQueueKey) << RENDER_QUEUE_KEY_MESH_INDEX.mask_shift ) | 
+        ((mesh_renderer_resource_handle.data().version.get() as RenderQueueKey) << RENDER_QUEUE_KEY_MESH_VERSION.mask_shift);
 
     Ok(render_queue_key)
 }
 
+// Returns true if render queue key belongs to item drawn in transparent pass
+pub fn is_render_queue_key_transparent(render_queue_key: RenderQueueKey) -> bool {
+    (render_queue_key & RENDER_QUEUE_KEY_PASS.mask) != 0
+}
+
+// Sets depth of the item in render queue key so that farther items are drawn first (distance is clamped to max distance)
+pub fn set_render_queue_key_depth(render_queue_key: RenderQueueKey, distance: f32, max_distance: f32) -> RenderQueueKey {
+    let depth = ((distance / max_distance).clamp(0.0, 1.0) * RENDER_QUEUE_KEY_DEPTH.max as f32) as RenderQueueKey;
+    (render_queue_key & !RENDER_QUEUE_KEY_DEPTH.mask) | ((RENDER_QUEUE_KEY_DEPTH.max - depth) << RENDER_QUEUE_KEY_DEPTH.mask_shift) // Depth has to be inverted for back to front sorting
+}
+
 pub struct RenderQueueKeyFields {
+    pub pass: u8,
     pub order: u8,
+    pub depth: u16,
+    pub pipeline_index: u8,
     pub material_index: u8,
     pub material_version: u8,
     pub mesh_index: u8,
@@ -126,14 +148,20 @@ pub struct RenderQueueKeyFields {
 pub fn decompose_render_queue_key(render_queue_key: RenderQueueKey) -> Result<RenderQueueKeyFields> { 
 
     // [TODO] What if render queue key is not valid
+    let pass: u8 = ((render_queue_key & RENDER_QUEUE_KEY_PASS.mask) >> RENDER_QUEUE_KEY_PASS.mask_shift) as u8;
     let order: u8 = ((render_queue_key & RENDER_QUEUE_KEY_ORDER.mask as RenderQueueKey) >> RENDER_QUEUE_KEY_ORDER.mask_shift as RenderQueueKey) as u8;
+    let depth: u16 = ((render_queue_key & RENDER_QUEUE_KEY_DEPTH.mask) >> RENDER_QUEUE_KEY_DEPTH.mask_shift) as u16;
+    let pipeline_index: u8 = ((render_queue_key & RENDER_QUEUE_KEY_PIPELINE_INDEX.mask) >> RENDER_QUEUE_KEY_PIPELINE_INDEX.mask_shift) as u8;
     let material_index: u8 = ((render_queue_key & RENDER_QUEUE_KEY_MATERIAL_INDEX.mask) >> RENDER_QUEUE_KEY_MATERIAL_INDEX.mask_shift) as u8;
     let material_version: u8 = ((render_queue_key & RENDER_QUEUE_KEY_MATERIAL_VERSION.mask) >> RENDER_QUEUE_KEY_MATERIAL_VERSION.mask_shift) as u8;
     let mesh_index: u8 = ((render_queue_key & RENDER_QUEUE_KEY_MESH_INDEX.mask) >> RENDER_QUEUE_KEY_MESH_INDEX.mask_shift) as u8;
     let mesh_version: u8 = ((render_queue_key & RENDER_QUEUE_KEY_MESH_VERSION.mask) >> RENDER_QUEUE_KEY_MESH_VERSION.mask_shift) as u8;
 
     let render_queue_key_fields = RenderQueueKeyFields {
+        pass,
         order,
+        depth,
+        pipeline_index,
         material_index,
         material_version,
         mesh_index,
@@ -165,7 +193,10 @@ fn get_render_queue_key_item_range(render_queue_item_index: u8) -> Range<RenderQ
 }
 
 lazy_static! { // This will be initialized in runtime instead of compile-time (this is the cost of not using const function, const functions do not allow for generic variables bound by traits different than Sized)
+    pub static ref RENDER_QUEUE_KEY_PASS: RenderQueueField<RenderQueueKey> = RenderQueueField::<RenderQueueKey>::new(get_render_queue_key_item_range(RENDER_QUEUE_KEY_PASS_IDX));
     pub static ref RENDER_QUEUE_KEY_ORDER: RenderQueueField<RenderQueueKey> = RenderQueueField::<RenderQueueKey>::new(get_render_queue_key_item_range(RENDER_QUEUE_KEY_ORDER_IDX));
+    pub static ref RENDER_QUEUE_KEY_DEPTH: RenderQueueField<RenderQueueKey> = RenderQueueField::<RenderQueueKey>::new(get_render_queue_key_item_range(RENDER_QUEUE_KEY_DEPTH_IDX));
+    pub static ref RENDER_QUEUE_KEY_PIPELINE_INDEX: RenderQueueField<RenderQueueKey> = RenderQueueField::<RenderQueueKey>::new(get_render_queue_key_item_range(RENDER_QUEUE_KEY_PIPELINE_INDEX_IDX));
     pub static ref RENDER_QUEUE_KEY_MATERIAL_INDEX: RenderQueueField<RenderQueueKey> = RenderQueueField::<RenderQueueKey>::new(get_render_queue_key_item_range(RENDER_QUEUE_KEY_MATERIAL_INDEX_IDX));
     pub static ref RENDER_QUEUE_KEY_MATERIAL_VERSION: RenderQueueField<RenderQueueKey> = RenderQueueField::<RenderQueueKey>::new(get_render_queue_key_item_range(RENDER_QUEUE_KEY_MATERIAL_VERSION_IDX));
     pub static ref RENDER_QUEUE_KEY_MESH_INDEX: RenderQueueField<RenderQueueKey> = RenderQueueField::<RenderQueueKey>::new(get_render_queue_key_item_range(RENDER_QUEUE_KEY_MESH_INDEX_IDX));
@@ -0,0 +1,7 @@
+// Statistics of the last rendered frame
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RenderStatistics {
+    pub drawn_count: usize, // Mesh rendering components added to render queue
+    pub culled_count: usize, // Mesh rendering components skipped because they are outside of camera frustum
+    pub lod_culled_count: usize, // Mesh rendering components skipped because they are too small on screen for any of their LOD levels
+}
@@ -0,0 +1,25 @@
+use crate::{
+    ecs::EntityHandle,
+    graphics::{ RenderQueueItem, RendererTextureHandle },
+};
+
+// --- Render view ---
+
+// Scene seen by one camera, renderer reads viewport, clear mode and clear color from camera component
+pub struct RenderView {
+    pub camera_entity_handle: EntityHandle,
+    pub skybox_texture_handle: Option<RendererTextureHandle>, // None if camera has no skybox or it is still loading
+    pub render_target_texture_handle: Option<RendererTextureHandle>, // None if camera renders to the window
+    pub render_queue: Vec<RenderQueueItem>, // Sorted items visible by the camera
+}
+
+impl RenderView {
+    pub fn new(camera_entity_handle: EntityHandle, skybox_texture_handle: Option<RendererTextureHandle>, render_target_texture_handle: Option<RendererTextureHandle>) -> Self {
+        Self {
+            camera_entity_handle,
+            skybox_texture_handle,
+            render_target_texture_handle,
+            render_queue: Vec::<RenderQueueItem>::new(),
+        }
+    }
+}
@@ -11,12 +11,17 @@ use crate::{
         MeshData, 
         MeshHandle, 
         TextureHandle, 
-        TextureType, 
+        TextureSettings, 
+        TextureData, 
+        ShaderData,
         MaterialTextureMap, 
-        MaterialParameterMap
+        MaterialParameterMap,
+        BlendMode,
     },
     graphics::{
-        RenderQueueItem,
+        RenderView,
+        PostProcessSettings,
+        PostProcessShaders,
     },
 };
 
@@ -42,6 +47,10 @@ pill_core::define_new_pill_slotmap_key! {
     pub struct RendererPipelineHandle;
 }
 
+pill_core::define_new_pill_slotmap_key! { 
+    pub struct RendererShaderHandle;
+}
+
 pill_core::define_new_pill_slotmap_key! { 
     pub struct RendererCameraHandle;
 }
@@ -66,35 +75,73 @@ pub enum RendererError {
     SurfaceOther,
 }
 
+// --- Vsync mode ---
+
+// Defines how presented frames are synchronized with display refresh rate
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VsyncMode {
+    On, // Frames wait for vertical blank, no tearing
+    Off, // Frames are presented immediately (without tearing if supported by display)
+    Adaptive, // Frames wait for vertical blank unless they are late, then they are presented immediately
+}
+
+impl VsyncMode {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "On" => Some(VsyncMode::On),
+            "Off" => Some(VsyncMode::Off),
+            "Adaptive" => Some(VsyncMode::Adaptive),
+            _ => None,
+        }
+    }
+}
+
 // --- Renderer trait definition ---
 
 pub trait PillRenderer { 
     fn new(window: Arc<winit::window::Window>, config: config::Config) -> Self where Self: Sized;
 
     fn resize(&mut self, new_window_size: winit::dpi::PhysicalSize<u32>);
-    fn set_master_pipeline(&mut self, vertex_shader_bytes: &[u8], fragment_shader_bytes: &[u8],) -> Result<()>;
+    fn set_skybox_pipeline(&mut self, vertex_shader_source: &str, fragment_shader_source: &str) -> Result<()>;
+    fn set_clear_pipelines(&mut self, vertex_shader_source: &str, fragment_shader_source: &str) -> Result<()>; // Used to clear viewports of cameras
+    fn set_post_process_pipelines(&mut self, post_process_shaders: &PostProcessShaders) -> Result<()>; // Has to be set before first frame is rendered
+    fn set_vsync_mode(&mut self, vsync_mode: VsyncMode);
+    fn set_msaa_sample_count(&mut self, sample_count: u32) -> Result<()>; // Pipelines and render targets are recreated
+    fn get_vsync_mode(&self) -> VsyncMode;
+    fn get_msaa_sample_count(&self) -> u32;
+    fn get_supported_msaa_sample_counts(&self) -> Vec<u32>;
     
     fn create_mesh(&mut self, name: &str, mesh_data: &MeshData) -> Result<RendererMeshHandle>;
-    fn create_texture(&mut self, name: &str, image_data: &image::DynamicImage, texture_type: TextureType) -> Result<RendererTextureHandle>;
-    fn create_material(&mut self, name: &str, textures: &MaterialTextureMap, parameters: &MaterialParameterMap) -> Result<RendererMaterialHandle>;
+    fn create_texture(&mut self, name: &str, texture_data: &TextureData, settings: &TextureSettings) -> Result<RendererTextureHandle>;
+    fn create_render_target_texture(&mut self, name: &str, width: u32, height: u32, settings: &TextureSettings) -> Result<RendererTextureHandle>; // Texture that cameras can render to, contents are undefined until it is rendered
+    fn create_shader(&mut self, name: &str, shader_data: &ShaderData) -> Result<RendererShaderHandle>;
+    fn create_material(&mut self, name: &str, renderer_shader_handle: RendererShaderHandle, blend_mode: BlendMode, textures: &MaterialTextureMap, parameters: &MaterialParameterMap) -> Result<RendererMaterialHandle>;
     fn create_camera(&mut self) -> Result<RendererCameraHandle>;
 
+    fn update_mesh(&mut self, renderer_mesh_handle: RendererMeshHandle, mesh_data: &MeshData) -> Result<()>;
+    fn update_shader(&mut self, renderer_shader_handle: RendererShaderHandle, shader_data: &ShaderData) -> Result<()>; // Pipelines of the shader are recreated, bindings of its materials have to be updated after
     fn update_material_textures(&mut self, renderer_material_handle: RendererMaterialHandle, textures: &MaterialTextureMap) -> Result<()>;
     fn update_material_parameters(&mut self, renderer_material_handle: RendererMaterialHandle, parameters: &MaterialParameterMap) -> Result<()>;
+    fn update_material_blend_mode(&mut self, renderer_material_handle: RendererMaterialHandle, blend_mode: BlendMode) -> Result<()>; // Material is switched to pipeline with matching blend state
+
+    fn get_material_pipeline_handle(&self, renderer_material_handle: RendererMaterialHandle) -> Result<RendererPipelineHandle>; // Pipeline is shared by materials using the same shader
 
     fn destroy_mesh(&mut self, renderer_mesh_handle: RendererMeshHandle) -> Result<()>;
     fn destroy_texture(&mut self, renderer_texture_handle: RendererTextureHandle) -> Result<()>;
+    fn destroy_shader(&mut self, renderer_shader_handle: RendererShaderHandle) -> Result<()>;
     fn destroy_material(&mut self, renderer_material_handle: RendererMaterialHandle) -> Result<()>;
     fn destroy_camera(&mut self, renderer_camera_handle: RendererCameraHandle) -> Result<()>;
 
     fn pass_input_to_egui(&mut self, event: &winit::event::WindowEvent) -> Result<()>;
+    fn begin_egui_frame(&mut self) -> egui::Context;
+    fn get_gpu_frame_time(&self) -> Option<f32>; // Returns GPU time of the last finished frame in milliseconds (None if timestamp queries are not supported)
 
     fn render(&mut self, 
-        active_camera_entity_handle: EntityHandle,
-        render_queue: &Vec::<RenderQueueItem>, 
+        render_views: &[RenderView], // Rendered in order, each one to viewport of its camera (views rendering to the same render target have to follow each other)
+        post_process_settings: &PostProcessSettings,
+        color_grading_lut_handle: Option<RendererTextureHandle>,
         camera_component_storage: &ComponentStorage<CameraComponent>,
         transform_component_storage: &ComponentStorage<TransformComponent>,
-        egui_ui: Box<dyn Fn(&egui::Context)>
     ) -> Result<(), RendererError>;
 
 }
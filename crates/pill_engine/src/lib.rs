@@ -67,30 +67,66 @@ pub mod game {
             InputComponent,
             CameraComponent,
             CameraAspectRatio,
+            CameraViewport,
+            CameraClearMode,
+            CameraProjection,
             EntityHandle,
             AudioSourceComponent,
             AudioListenerComponent,
             TimeComponent,
             AudioManagerComponent,
             EguiManagerComponent,
+            UiCallbackFunction,
+            ProfilerComponent,
+            ProfilerSample,
             Component,
             ComponentStorage,
             GlobalComponent,
             GlobalComponentStorage,
             SoundType,
         },
+        graphics::{
+            RenderStatistics,
+            LodGroup,
+            LodLevel,
+            PostProcessSettings,
+            Tonemapping,
+            VsyncMode,
+            RenderLayers,
+        },
         resources::{
             Resource,
             ResourceStorage,
             Texture, 
             TextureHandle,
             TextureType,
+            TextureSettings,
+            TextureFilter,
+            TextureWrapMode,
+            TextureColorSpace,
+            TextureData,
+            HdrImage,
+            CompressedImage,
+            CubemapImage,
             Material,
             MaterialHandle,
+            BlendMode,
+            Shader,
+            ShaderHandle,
+            ShaderLayout,
+            ShaderTextureSlot,
+            ShaderParameterSlot,
+            ShaderParameterType,
             Mesh,
             MeshHandle,
+            MeshData,
+            MeshDataBuilder,
             ResourceLoadType,
-            Sound
+            ResourceRemovalPolicy,
+            LoadState,
+            AssetLoader,
+            Sound,
+            SoundHandle,
         },
 
     };
@@ -103,11 +139,17 @@ pub mod game {
         Color, 
         Vector2i, 
         Vector3i,
+        Matrix4f,
+        BoundingBox,
+        Frustum,
+        TextureCompressionFormat,
         define_new_pill_slotmap_key,
     };
   
     extern crate anyhow;
     pub use anyhow::{ Context, Result, Error };
+
+    pub use egui;
 }
 
 #[cfg(feature = "internal")]
@@ -124,14 +166,21 @@ pub mod internal {
             RenderQueueKey,
             RenderQueueItem,
             RenderQueueKeyFields,
+            RenderView,
             decompose_render_queue_key,
 
             RendererCameraHandle,
             RendererMaterialHandle,
             RendererMeshHandle,
             RendererPipelineHandle,
+            RendererShaderHandle,
             RendererTextureHandle,
-            RENDER_QUEUE_KEY_ORDER
+            RENDER_QUEUE_KEY_ORDER,
+            PostProcessSettings,
+            PostProcessShaders,
+            Tonemapping,
+            VsyncMode,
+            RenderLayers,
         },
         ecs::{
             Scene,
@@ -143,6 +192,9 @@ pub mod internal {
             InputComponent,
             TimeComponent,
             CameraAspectRatio,
+            CameraViewport,
+            CameraClearMode,
+            CameraProjection,
             AudioSourceComponent,
             AudioListenerComponent,
             AudioManagerComponent,
@@ -153,17 +205,46 @@ pub mod internal {
             Texture, 
             TextureHandle,
             TextureType,
+            TextureSettings,
+            TextureFilter,
+            TextureWrapMode,
+            TextureColorSpace,
+            TextureData,
+            HdrImage,
+            CompressedImage,
+            CubemapImage,
 
             Material,
             MaterialHandle,
+            BlendMode,
+
+            Shader,
+            ShaderHandle,
+            ShaderSource,
+            ShaderData,
+            ShaderLayout,
+            ShaderTextureSlot,
+            ShaderParameterSlot,
+            ShaderParameterType,
 
             Mesh,
             MeshHandle,
             MeshData,
             MeshVertex,    
 
+            SoundData,
+
             ResourceLoadType,
             ResourceManager,
+            AssetLoaderRegistry,
+            ImageAssetLoader,
+            HdrAssetLoader,
+            Ktx2AssetLoader,
+            DdsAssetLoader,
+            ObjAssetLoader,
+            SpirvAssetLoader,
+            WgslAssetLoader,
+            AudioAssetLoader,
 
             MaterialTexture,
             MaterialTextureMap,
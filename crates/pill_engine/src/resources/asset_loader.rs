@@ -0,0 +1,190 @@
+use crate::resources::{ ResourceLoadType, ResourceManager, MeshData, SoundData, TextureData, HdrImage, CompressedImage, ShaderSource, read_asset, read_archived_asset };
+
+use pill_core::{ EngineError, PillTypeMapKey };
+
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::Arc,
+};
+use anyhow::{ Result, Error };
+
+// Loads asset data from file contents
+//
+// D is the type of data resource is created from, e.g. TextureData for Texture, MeshData for Mesh, SoundData for Sound
+pub trait AssetLoader<D>: Send + Sync {
+    fn get_extensions(&self) -> &[&str]; // Extensions (without dot) of files this loader can load
+    fn load(&self, path: Option<&Path>, bytes: &[u8]) -> Result<D>; // Path is None if asset is loaded from memory
+}
+
+// Function loading asset data, asset loader is chosen before so it can run on worker thread
+pub(crate) type AssetLoadFunction<D> = Box<dyn FnOnce() -> Result<D> + Send>;
+
+// --- Registry ---
+
+pub struct AssetLoaderRegistry<D: 'static> {
+    loaders: HashMap<String, Arc<dyn AssetLoader<D>>>,
+    default_loader: Option<Arc<dyn AssetLoader<D>>>, // First registered loader, used for assets loaded from memory
+}
+
+impl<D: 'static> AssetLoaderRegistry<D> {
+    pub fn new() -> Self {
+        Self {
+            loaders: HashMap::<String, Arc<dyn AssetLoader<D>>>::new(),
+            default_loader: None,
+        }
+    }
+
+    // Loader registered later replaces previous one for the same extension
+    pub fn add_loader(&mut self, loader: Arc<dyn AssetLoader<D>>) {
+        for extension in loader.get_extensions() {
+            self.loaders.insert(extension.to_lowercase(), loader.clone());
+        }
+        self.default_loader.get_or_insert(loader);
+    }
+
+    pub fn get_loader(&self, extension: &str) -> Option<Arc<dyn AssetLoader<D>>> {
+        self.loaders.get(&extension.to_lowercase()).cloned()
+    }
+
+    pub fn get_default_loader(&self) -> Option<Arc<dyn AssetLoader<D>>> {
+        self.default_loader.clone()
+    }
+
+    pub fn get_extensions(&self) -> Vec<String> {
+        let mut extensions = self.loaders.keys().cloned().collect::<Vec<String>>();
+        extensions.sort();
+        extensions
+    }
+}
+
+impl<D: 'static> PillTypeMapKey for AssetLoaderRegistry<D> {
+    type Storage = AssetLoaderRegistry<D>;
+}
+
+// Prepares loading of asset data with loader registered for its format
+pub(crate) fn prepare_asset_load<D: 'static>(resource_manager: &ResourceManager, load_type: &ResourceLoadType) -> Result<AssetLoadFunction<D>> {
+    let asset_load: AssetLoadFunction<D> = match load_type {
+        ResourceLoadType::Path(path) => {
+            resource_manager.validate_asset_path::<D>(path)?;
+            let asset_loader = resource_manager.get_asset_loader::<D>(path)?;
+            let path = path.clone();
+            Box::new(move || asset_loader.load(Some(&path), &read_asset(&path)?))
+        },
+        ResourceLoadType::Archive(path) => {
+            let asset_loader = resource_manager.get_asset_loader::<D>(path)?;
+            let path = path.clone();
+            Box::new(move || asset_loader.load(Some(&path), &read_archived_asset(&path)?))
+        },
+        ResourceLoadType::Bytes(bytes) => {
+            let asset_loader = resource_manager.get_default_asset_loader::<D>()?;
+            let bytes = bytes.clone();
+            Box::new(move || asset_loader.load(None, &bytes))
+        },
+        ResourceLoadType::Generated => {
+            return Err(Error::new(EngineError::InvalidAssetPath("<generated>".to_string())));
+        },
+    };
+
+    Ok(asset_load)
+}
+
+// --- Built-in loaders ---
+
+pub struct ImageAssetLoader;
+
+impl AssetLoader<TextureData> for ImageAssetLoader {
+    fn get_extensions(&self) -> &[&str] {
+        &["png", "jpg", "gif", "tif"]
+    }
+
+    fn load(&self, path: Option<&Path>, bytes: &[u8]) -> Result<TextureData> {
+        Ok(TextureData::Image(image::load_from_memory(bytes)?))
+    }
+}
+
+pub struct HdrAssetLoader;
+
+impl AssetLoader<TextureData> for HdrAssetLoader {
+    fn get_extensions(&self) -> &[&str] {
+        &["hdr"]
+    }
+
+    fn load(&self, path: Option<&Path>, bytes: &[u8]) -> Result<TextureData> {
+        Ok(TextureData::Hdr(HdrImage::from_hdr_bytes(bytes)?))
+    }
+}
+
+pub struct Ktx2AssetLoader;
+
+impl AssetLoader<TextureData> for Ktx2AssetLoader {
+    fn get_extensions(&self) -> &[&str] {
+        &["ktx2"]
+    }
+
+    fn load(&self, path: Option<&Path>, bytes: &[u8]) -> Result<TextureData> {
+        Ok(TextureData::Compressed(CompressedImage::from_ktx2_bytes(bytes)?))
+    }
+}
+
+pub struct DdsAssetLoader;
+
+impl AssetLoader<TextureData> for DdsAssetLoader {
+    fn get_extensions(&self) -> &[&str] {
+        &["dds"]
+    }
+
+    fn load(&self, path: Option<&Path>, bytes: &[u8]) -> Result<TextureData> {
+        Ok(TextureData::Compressed(CompressedImage::from_dds_bytes(bytes)?))
+    }
+}
+
+pub struct ObjAssetLoader;
+
+impl AssetLoader<MeshData> for ObjAssetLoader {
+    fn get_extensions(&self) -> &[&str] {
+        &["obj"]
+    }
+
+    fn load(&self, path: Option<&Path>, bytes: &[u8]) -> Result<MeshData> {
+        MeshData::from_obj_bytes(path, bytes)
+    }
+}
+
+pub struct SpirvAssetLoader;
+
+impl AssetLoader<ShaderSource> for SpirvAssetLoader {
+    fn get_extensions(&self) -> &[&str] {
+        &["spv"]
+    }
+
+    fn load(&self, path: Option<&Path>, bytes: &[u8]) -> Result<ShaderSource> {
+        Ok(ShaderSource::SpirV(bytes.to_vec()))
+    }
+}
+
+pub struct WgslAssetLoader;
+
+impl AssetLoader<ShaderSource> for WgslAssetLoader {
+    fn get_extensions(&self) -> &[&str] {
+        &["wgsl"]
+    }
+
+    fn load(&self, path: Option<&Path>, bytes: &[u8]) -> Result<ShaderSource> {
+        let code = String::from_utf8(bytes.to_vec())
+            .map_err(|_| Error::new(EngineError::InvalidShaderData("Source is not valid UTF-8".to_string())))?;
+        Ok(ShaderSource::Wgsl(code))
+    }
+}
+
+pub struct AudioAssetLoader;
+
+impl AssetLoader<SoundData> for AudioAssetLoader {
+    fn get_extensions(&self) -> &[&str] {
+        &["mp3", "wav"]
+    }
+
+    fn load(&self, path: Option<&Path>, bytes: &[u8]) -> Result<SoundData> {
+        SoundData::from_bytes(bytes.to_vec())
+    }
+}
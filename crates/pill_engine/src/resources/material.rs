@@ -1,7 +1,7 @@
 use crate::{
     engine::Engine,
-    graphics::{ RendererTextureHandle, RendererMaterialHandle, RENDER_QUEUE_KEY_ORDER }, 
-    resources::{ TextureHandle, TextureType, Texture, ResourceStorage, Resource },
+    graphics::{ RendererTextureHandle, RendererMaterialHandle, RendererPipelineHandle, RENDER_QUEUE_KEY_ORDER }, 
+    resources::{ TextureHandle, TextureType, Texture, ResourceStorage, Resource, Shader, ShaderHandle, ShaderLayout, ShaderParameterType },
     ecs::{ DeferredUpdateManagerPointer, DeferredUpdateResourceRequest, MeshRenderingComponent, DeferredUpdateComponent },
     config::*,
 };
@@ -23,6 +23,10 @@ const DEFERRED_REQUEST_VARIANT_PARAMETER: usize = 1;
 const DEFERRED_REQUEST_VARIANT_TEXTURE_START: usize = 2;
 const DEFERRED_REQUEST_VARIANT_TEXTURE_END: usize = 10;
 
+const DEFERRED_REQUEST_VARIANT_BLEND_MODE: usize = 11;
+
+pub(crate) const MAX_MATERIAL_TEXTURE_SLOTS: usize = DEFERRED_REQUEST_VARIANT_TEXTURE_END - DEFERRED_REQUEST_VARIANT_TEXTURE_START + 1;
+
 // --- Material parameters ---
 
 #[derive(Debug)]
@@ -141,6 +145,30 @@ impl MaterialTextureMap {
     }
 }
 
+// --- Blend mode ---
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BlendMode {
+    Opaque,
+    AlphaCutout(f32), // Fragments with alpha below threshold are discarded (threshold is written to AlphaCutoff parameter of the shader)
+    AlphaBlend,
+    Additive,
+}
+
+impl BlendMode {
+    // Transparent materials are drawn after opaque ones and sorted back to front
+    pub fn is_transparent(&self) -> bool {
+        matches!(self, BlendMode::AlphaBlend | BlendMode::Additive)
+    }
+
+    fn get_alpha_cutoff(&self) -> f32 {
+        match self {
+            BlendMode::AlphaCutout(v) => *v,
+            BlendMode::Opaque | BlendMode::AlphaBlend | BlendMode::Additive => 0.0,
+        }
+    }
+}
+
 // --- Builder ---
 
 pub struct MaterialBuilder {
@@ -154,6 +182,12 @@ impl MaterialBuilder {
         }
     }
 
+    // Slots of the shader are known only when material is added, values set before are validated then
+    pub fn shader(mut self, shader_handle: ShaderHandle) -> Self {
+        self.material.set_shader(shader_handle);
+        self
+    }
+
     pub fn texture(mut self, slot_name: &str, texture_handle: TextureHandle) -> Result<Self> {
         self.material.set_texture(slot_name, texture_handle)?;
         Ok(self)
@@ -179,6 +213,11 @@ impl MaterialBuilder {
         Ok(self)
     }
 
+    pub fn blend_mode(mut self, blend_mode: BlendMode) -> Result<Self> {
+        self.material.set_blend_mode(blend_mode)?;
+        Ok(self)
+    }
+
     pub fn build(self) -> Material {
         self.material
     }
@@ -200,8 +239,16 @@ pub struct Material {
     parameters: MaterialParameterMap,
     #[readonly]
     pub rendering_order: u8,
+    #[readonly]
+    pub shader_handle: ShaderHandle,
+    #[readonly]
+    pub blend_mode: BlendMode,
     pub renderer_resource_handle: Option<RendererMaterialHandle>,
-   
+    pub(crate) renderer_pipeline_handle: Option<RendererPipelineHandle>,
+
+    slots_reflected: bool, // Slots of custom shader are known only after material is added
+    pending_textures: Vec<(String, TextureHandle)>, // Textures set before slots are known
+    pending_parameters: Vec<(String, MaterialParameter)>, // Parameters set before slots are known
     handle: Option<MaterialHandle>,
     deferred_update_manager: Option<DeferredUpdateManagerPointer>,
 }
@@ -211,31 +258,159 @@ impl Material {
         MaterialBuilder::new(name)
     }
 
+    // Creates material using master shader
     pub fn new(name: &str) -> Self {     
         let mut textures = MaterialTextureMap::new();
-        textures.data.insert(MASTER_SHADER_COLOR_TEXTURE_SLOT.to_string(), MaterialTexture::new(TextureType::Color));
-        textures.mapping.push(MASTER_SHADER_COLOR_TEXTURE_SLOT.to_string());
-        textures.data.insert(MASTER_SHADER_NORMAL_TEXTURE_SLOT.to_string(), MaterialTexture::new(TextureType::Normal));
-        textures.mapping.push(MASTER_SHADER_NORMAL_TEXTURE_SLOT.to_string());
+        let texture_slots = [
+            (MASTER_SHADER_BASE_COLOR_TEXTURE_SLOT, TextureType::Color),
+            (MASTER_SHADER_METALLIC_ROUGHNESS_TEXTURE_SLOT, TextureType::Color),
+            (MASTER_SHADER_NORMAL_TEXTURE_SLOT, TextureType::Normal),
+            (MASTER_SHADER_OCCLUSION_TEXTURE_SLOT, TextureType::Color),
+            (MASTER_SHADER_EMISSIVE_TEXTURE_SLOT, TextureType::Color),
+            (MASTER_SHADER_ENVIRONMENT_TEXTURE_SLOT, TextureType::Cubemap),
+        ];
+        for (slot_name, texture_type) in texture_slots {
+            textures.data.insert(slot_name.to_string(), MaterialTexture::new(texture_type));
+            textures.mapping.push(slot_name.to_string());
+        }
 
         let mut parameters = MaterialParameterMap::new();
-        parameters.data.insert(MASTER_SHADER_TINT_PARAMETER_SLOT.to_string(), MaterialParameter::Color(None));
-        textures.mapping.push(MASTER_SHADER_TINT_PARAMETER_SLOT.to_string());
-        parameters.data.insert(MASTER_SHADER_SPECULARITY_PARAMETER_SLOT.to_string(), MaterialParameter::Scalar(None));
-        textures.mapping.push(MASTER_SHADER_SPECULARITY_PARAMETER_SLOT.to_string());
+        let parameter_slots = [
+            (MASTER_SHADER_BASE_COLOR_PARAMETER_SLOT, MaterialParameter::Color(None)),
+            (MASTER_SHADER_BASE_COLOR_ALPHA_PARAMETER_SLOT, MaterialParameter::Scalar(None)),
+            (MASTER_SHADER_EMISSIVE_PARAMETER_SLOT, MaterialParameter::Color(None)),
+            (MASTER_SHADER_METALLIC_PARAMETER_SLOT, MaterialParameter::Scalar(None)),
+            (MASTER_SHADER_ROUGHNESS_PARAMETER_SLOT, MaterialParameter::Scalar(None)),
+            (MASTER_SHADER_NORMAL_SCALE_PARAMETER_SLOT, MaterialParameter::Scalar(None)),
+            (MASTER_SHADER_OCCLUSION_STRENGTH_PARAMETER_SLOT, MaterialParameter::Scalar(None)),
+            (MASTER_SHADER_ALPHA_CUTOFF_PARAMETER_SLOT, MaterialParameter::Scalar(None)),
+        ];
+        for (slot_name, parameter) in parameter_slots {
+            parameters.data.insert(slot_name.to_string(), parameter);
+            parameters.mapping.push(slot_name.to_string());
+        }
         
         Self {
             name: name.to_string(),  
             textures,
             parameters,
             rendering_order: RENDER_QUEUE_KEY_ORDER.max as u8,
+            shader_handle: DEFAULT_SHADER_HANDLE,
+            blend_mode: BlendMode::Opaque,
             renderer_resource_handle: None, 
+            renderer_pipeline_handle: None,
+            slots_reflected: true,
+            pending_textures: Vec::<(String, TextureHandle)>::new(),
+            pending_parameters: Vec::<(String, MaterialParameter)>::new(),
             handle: None,
             deferred_update_manager: None,
         }
     }
 
+    // Creates material using custom shader, its slots are reflected from shader when material is added
+    pub fn new_with_shader(name: &str, shader_handle: ShaderHandle) -> Self {
+        let mut material = Self::new(name);
+        material.set_shader(shader_handle);
+        material
+    }
+
+    fn set_shader(&mut self, shader_handle: ShaderHandle) {
+        // Keep values that are already set, they are applied to slots of the shader when material is added
+        for (slot_name, texture_slot) in self.textures.data.drain() {
+            if let Some(texture_handle) = texture_slot.texture_handle {
+                self.pending_textures.push((slot_name, texture_handle));
+            }
+        }
+        for (slot_name, parameter) in self.parameters.data.drain() {
+            if parameter.is_some() {
+                self.pending_parameters.push((slot_name, parameter));
+            }
+        }
+        self.textures.mapping.clear();
+        self.parameters.mapping.clear();
+
+        self.shader_handle = shader_handle;
+        self.slots_reflected = false;
+    }
+
+    // Creates slots from shader layout and fills them with values set so far
+    fn apply_shader_layout(&mut self, shader_layout: &ShaderLayout) -> Result<()> {
+        let shader_handle = self.shader_handle;
+        self.set_shader(shader_handle);
+
+        // Create texture slots
+        let mut textures = MaterialTextureMap::new();
+        for texture_slot in shader_layout.texture_slots.iter() {
+            textures.data.insert(texture_slot.name.clone(), MaterialTexture::new(texture_slot.texture_type));
+            textures.mapping.push(texture_slot.name.clone());
+        }
+        for (slot_name, texture_handle) in self.pending_textures.drain(..) {
+            let texture_slot = textures.data.get_mut(&slot_name)
+                .ok_or(Error::new(EngineError::MaterialTextureSlotNotFound(slot_name.clone())))?;
+            texture_slot.texture_handle = Some(texture_handle);
+        }
+
+        // Create parameter slots with default values
+        let mut parameters = MaterialParameterMap::new();
+        for parameter_slot in shader_layout.parameter_slots.iter() {
+            let parameter = get_default_parameter(&parameter_slot.name, parameter_slot.parameter_type);
+            parameters.data.insert(parameter_slot.name.clone(), parameter);
+            parameters.mapping.push(parameter_slot.name.clone());
+        }
+        for (slot_name, value) in self.pending_parameters.drain(..) {
+            let error = EngineError::MaterialParameterSlotNotFound(slot_name.clone(), get_enum_variant_type_name(&value).to_string());
+            let parameter = parameters.data.get_mut(&slot_name).ok_or(Error::new(error.clone()))?;
+            if !enum_variant_eq::<MaterialParameter>(parameter, &value) {
+                return Err(Error::new(error));
+            }
+            *parameter = value;
+        }
+
+        self.textures = textures;
+        self.parameters = parameters;
+        self.slots_reflected = true;
+
+        Ok(())
+    }
+
+    // Called when shader of initialized material is reloaded, values of slots that were removed or changed their type are discarded
+    pub(crate) fn update_shader_layout(&mut self, shader_layout: &ShaderLayout) {
+        // Create texture slots and keep textures from slots of the same type
+        let mut textures = MaterialTextureMap::new();
+        for texture_slot in shader_layout.texture_slots.iter() {
+            let texture = match self.textures.data.remove(&texture_slot.name) {
+                Some(v) if enum_variant_eq(&v.texture_type, &texture_slot.texture_type) => v,
+                _ => MaterialTexture::new(texture_slot.texture_type),
+            };
+            textures.data.insert(texture_slot.name.clone(), texture);
+            textures.mapping.push(texture_slot.name.clone());
+        }
+
+        // Create parameter slots and keep values from slots of the same type
+        let mut parameters = MaterialParameterMap::new();
+        for parameter_slot in shader_layout.parameter_slots.iter() {
+            let default_parameter = get_default_parameter(&parameter_slot.name, parameter_slot.parameter_type);
+            let parameter = match self.parameters.data.remove(&parameter_slot.name) {
+                Some(v) if enum_variant_eq::<MaterialParameter>(&v, &default_parameter) => v,
+                _ => default_parameter,
+            };
+            parameters.data.insert(parameter_slot.name.clone(), parameter);
+            parameters.mapping.push(parameter_slot.name.clone());
+        }
+
+        self.textures = textures;
+        self.parameters = parameters;
+        self.apply_alpha_cutoff();
+    }
+
     pub fn set_texture(&mut self, slot_name: &str, texture_handle: TextureHandle) -> Result<()> {
+        // Keep texture until slots are known
+        if !self.slots_reflected {
+            self.pending_textures.retain(|v| v.0 != slot_name);
+            self.pending_textures.push((slot_name.to_string(), texture_handle));
+            return Ok(());
+        }
+
         // Get texture slot
         let texture_slot = self.textures.data.get_mut(slot_name)
             .ok_or( Error::new(EngineError::MaterialTextureSlotNotFound(slot_name.to_string())))?;
@@ -255,6 +430,11 @@ impl Material {
     }
 
     pub fn remove_texture(&mut self, slot_name: &str) -> Result<()> {
+        if !self.slots_reflected {
+            self.pending_textures.retain(|v| v.0 != slot_name);
+            return Ok(());
+        }
+
         // Get texture slot
         let texture_slot = self.textures.data.get_mut(slot_name)
             .ok_or( Error::new(EngineError::MaterialTextureSlotNotFound(slot_name.to_string())))?;
@@ -291,6 +471,33 @@ impl Material {
         Ok(())
     }
 
+    pub fn set_blend_mode(&mut self, blend_mode: BlendMode) -> Result<()> {
+        // Check alpha cutout threshold
+        if let BlendMode::AlphaCutout(threshold) = blend_mode {
+            if !(0.0..=1.0).contains(&threshold) {
+                return Err(Error::new(EngineError::WrongAlphaCutoff(threshold.to_string(), "0.0-1.0".to_string())));
+            }
+        }
+
+        // Set new blend mode
+        self.blend_mode = blend_mode;
+
+        // Post deferred update request (only if renderer resource handle is set (it means that material is initialized))
+        if self.renderer_resource_handle.is_some() {
+            self.apply_alpha_cutoff();
+            self.post_deferred_update_request(DEFERRED_REQUEST_VARIANT_BLEND_MODE);
+        }
+
+        Ok(())
+    }
+
+    // Writes alpha cutout threshold to parameter slot if shader declares it (zero threshold discards nothing)
+    fn apply_alpha_cutoff(&mut self) {
+        if let Some(MaterialParameter::Scalar(value)) = self.parameters.data.get_mut(MASTER_SHADER_ALPHA_CUTOFF_PARAMETER_SLOT) {
+            *value = Some(self.blend_mode.get_alpha_cutoff());
+        }
+    }
+
     pub fn get_scalar(&self, parameter_name: &str) -> Result<f32> {
         self.parameters.get_scalar(parameter_name)
     }
@@ -318,6 +525,13 @@ impl Material {
     }
 
     fn set_parameter(&mut self, parameter_name: &str, value: MaterialParameter) -> Result<()> {
+        // Keep parameter until slots are known
+        if !self.slots_reflected {
+            self.pending_parameters.retain(|v| v.0 != parameter_name);
+            self.pending_parameters.push((parameter_name.to_string(), value));
+            return Ok(());
+        }
+
         // Set parameter
         self.parameters.set_parameter(parameter_name, value)?;
 
@@ -340,6 +554,33 @@ impl Material {
     }
 }
 
+// Requests render queue key update of mesh rendering components that use material with given handle (keys are composed after material is back in its storage)
+fn request_render_queue_key_updates(engine: &mut Engine, material_handle: MaterialHandle) -> Result<()> {
+    for (scene_handle, scene) in engine.scene_manager.scenes.iter_mut() {
+        for (entity_handle, mesh_rendering_component) in scene.get_one_component_iterator_mut::<MeshRenderingComponent>()? {
+            // If mesh rendering component has handle to this material
+            if mesh_rendering_component.material_handle.map(|v| v.data() == material_handle.data()).unwrap_or(false) {
+                mesh_rendering_component.request_render_queue_key_update();
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Returns value of parameter slot before it is set (factors of master material that multiply textures start at one)
+fn get_default_parameter(slot_name: &str, parameter_type: ShaderParameterType) -> MaterialParameter {
+    match (slot_name, parameter_type) {
+        (MASTER_SHADER_EMISSIVE_PARAMETER_SLOT, ShaderParameterType::Color) => MaterialParameter::Color(Some(Color::new(0.0, 0.0, 0.0))),
+        (MASTER_SHADER_ROUGHNESS_PARAMETER_SLOT, ShaderParameterType::Scalar) => MaterialParameter::Scalar(Some(MASTER_SHADER_DEFAULT_ROUGHNESS)),
+        (MASTER_SHADER_BASE_COLOR_ALPHA_PARAMETER_SLOT | MASTER_SHADER_NORMAL_SCALE_PARAMETER_SLOT | MASTER_SHADER_OCCLUSION_STRENGTH_PARAMETER_SLOT, ShaderParameterType::Scalar) => 
+            MaterialParameter::Scalar(Some(1.0)),
+        (_, ShaderParameterType::Scalar) => MaterialParameter::Scalar(Some(0.0)),
+        (_, ShaderParameterType::Bool) => MaterialParameter::Bool(Some(false)),
+        (_, ShaderParameterType::Color) => MaterialParameter::Color(Some(Color::new(1.0, 1.0, 1.0))),
+    }
+}
+
 impl PillTypeMapKey for Material {
     type Storage = ResourceStorage<Material>; 
 }
@@ -358,6 +599,15 @@ impl Resource for Material {
         let deferred_update_component = engine.get_global_component_mut::<DeferredUpdateComponent>().expect("Critical: No DeferredUpdateComponent");
         self.deferred_update_manager = Some(deferred_update_component.borrow_deferred_update_manager());
 
+        // Get shader and create slots from its layout
+        let shader_handle = self.shader_handle;
+        let shader = engine.get_resource::<Shader>(&shader_handle)
+            .context(error_message.clone()).context(format!("Invalid {} for {}", "Handle".sobj_style(), "Shader".sobj_style()))?;
+        let shader_layout = shader.layout.clone().expect("Critical: Shader layout not reflected");
+        let renderer_shader_handle = shader.renderer_resource_handle.expect("Critical: No renderer resource handle");
+        self.apply_shader_layout(&shader_layout).context(error_message.clone())?;
+        self.apply_alpha_cutoff();
+
         // Check if assigned textures are of correct type
         for texture_slot in self.textures.data.iter_mut() {
             if let Some(texture_handle) = texture_slot.1.texture_handle {
@@ -379,28 +629,10 @@ impl Resource for Material {
             }
         }
 
-        // Set default parameters if not already set
-        let parameter_values = vec![
-            (MASTER_SHADER_TINT_PARAMETER_SLOT, MaterialParameter::Color(Some(Color::new(1.0, 1.0, 1.0)))),
-            (MASTER_SHADER_SPECULARITY_PARAMETER_SLOT, MaterialParameter::Scalar(Some(0.0)))
-        ];
-        for parameter_value in parameter_values {
-            let parameter = self.parameters.data.get_mut(parameter_value.0);
-            match parameter {
-                Some(v) => {
-                    if pill_core::enum_variant_eq::<MaterialParameter>(&parameter_value.1, v) {
-                        if !v.is_some() {
-                            *v = parameter_value.1;
-                        }
-                    }
-                },
-                None => panic!("Critical: Wrong parameters setup"),
-            }
-        }
-
         // Create new renderer material resource
-        let renderer_resource_handle = engine.renderer.create_material(&self.name, &self.textures, &self.parameters).context(error_message)?;
+        let renderer_resource_handle = engine.renderer.create_material(&self.name, renderer_shader_handle, self.blend_mode, &self.textures, &self.parameters).context(error_message.clone())?;
         self.renderer_resource_handle = Some(renderer_resource_handle);
+        self.renderer_pipeline_handle = Some(engine.renderer.get_material_pipeline_handle(renderer_resource_handle).context(error_message)?);
 
         Ok(())
     }
@@ -413,17 +645,19 @@ impl Resource for Material {
         match request {
             DEFERRED_REQUEST_VARIANT_RENDERING_ORDER => 
             {
-                // Find mesh rendering components that use this material and update them
-                for (scene_handle, scene) in engine.scene_manager.scenes.iter_mut() {
-                    for (entity_handle, mesh_rendering_component) in scene.get_one_component_iterator_mut::<MeshRenderingComponent>()? {
-                        if let Some(material_handle) = mesh_rendering_component.material_handle {
-                            // If mesh rendering component has handle to this material 
-                            if material_handle.data() == self.handle.unwrap().data() {
-                                mesh_rendering_component.update_render_queue_key(&engine.resource_manager).unwrap();
-                            }
-                        }
-                    }
-                }
+                // Update mesh rendering components that use this material
+                request_render_queue_key_updates(engine, self.handle.unwrap())?;
+            },
+            DEFERRED_REQUEST_VARIANT_BLEND_MODE =>
+            {
+                // Update renderer counterpart (material switches to pipeline with new blend state)
+                let renderer_resource_handle = self.renderer_resource_handle.unwrap();
+                engine.renderer.update_material_blend_mode(renderer_resource_handle, self.blend_mode)?;
+                engine.renderer.update_material_parameters(renderer_resource_handle, &self.parameters)?;
+                self.renderer_pipeline_handle = Some(engine.renderer.get_material_pipeline_handle(renderer_resource_handle)?);
+
+                // Update mesh rendering components that use this material (pass and pipeline are part of render queue key)
+                request_render_queue_key_updates(engine, self.handle.unwrap())?;
             },
             DEFERRED_REQUEST_VARIANT_PARAMETER => 
             {
@@ -449,8 +683,8 @@ impl Resource for Material {
                         )));
                     }
 
-                    // Set renderer resource handle
-                    let _ = texture_slot.renderer_texture_handle.insert(texture.renderer_resource_handle.unwrap().clone());
+                    // Set renderer resource handle (texture that is still loading has none, default texture is used instead)
+                    texture_slot.renderer_texture_handle = texture.renderer_resource_handle;
                 }
 
                 // Update renderer counterpart
@@ -465,28 +699,37 @@ impl Resource for Material {
         Ok(())
     }
 
+    fn get_reference_count<H: PillSlotMapKey>(&self, engine: &Engine, self_handle: H) -> Result<usize> {
+        // Count components in all scenes that use this resource
+        let mut reference_count = 0;
+        for (scene_handle, scene) in engine.scene_manager.scenes.iter() {
+            for (entity_handle, component) in scene.get_one_component_iterator::<MeshRenderingComponent>()? {
+                if let Some(handle) = component.material_handle {
+                    if handle.data() == self_handle.data() {
+                        reference_count += 1;
+                    }
+                }
+            }
+        }
+
+        Ok(reference_count)
+    }
+
     fn destroy<H: PillSlotMapKey>(&mut self, engine: &mut Engine, self_handle: H) -> Result<()> {
         // Destroy renderer resource
         if let Some(v) = self.renderer_resource_handle {
             engine.renderer.destroy_material(v).unwrap();
         }
 
-        // Find mesh rendering components that use this material and update them
-
-        
-
+        // Find mesh rendering components that use this material and update them (they fall back to default material)
         for (scene_handle, scene) in engine.scene_manager.scenes.iter_mut() {
-            let x = &engine.resource_manager;
-
-            // for (entity_handle, mesh_rendering_component) in engine.iterate_one_component::<MeshRenderingComponent>()? {
-            //     if let Some(material_handle) = mesh_rendering_component.material_handle {
-            //         // If mesh rendering component has handle to this material 
-            //         if material_handle.data() == self_handle.data() {
-            //             mesh_rendering_component.set_material_handle(Option::<MaterialHandle>::None);
-            //             mesh_rendering_component.update_render_queue_key(&engine.resource_manager).unwrap();
-            //         }
-            //     }
-            // }
+            for (entity_handle, mesh_rendering_component) in scene.get_one_component_iterator_mut::<MeshRenderingComponent>()? {
+                // If mesh rendering component has handle to this material
+                if mesh_rendering_component.material_handle.map_or(false, |v| v.data() == self_handle.data()) {
+                    mesh_rendering_component.set_material_handle(None);
+                    mesh_rendering_component.update_render_queue_key(&engine.resource_manager).unwrap();
+                }
+            }
         }
 
         Ok(())
@@ -1,19 +1,21 @@
 use crate::{
     engine::Engine,
     graphics::{ RendererMeshHandle }, 
-    resources::{ ResourceStorage, Resource },
-    ecs::{ DeferredUpdateManagerPointer, MeshRenderingComponent },
+    resources::{ ResourceStorage, Resource, ResourceLoadType, ResourceManager, ResourceData, ResourceDataLoader, prepare_asset_load, read_asset },
+    ecs::{ DeferredUpdateManagerPointer, DeferredUpdateComponent, DeferredUpdateResourceRequest, MeshRenderingComponent },
     config::*,
 };
 
-use pill_core::{ EngineError, PillSlotMapKey, PillTypeMap, PillTypeMapKey, Vector3f, PillStyle, get_type_name };
+use pill_core::{ EngineError, PillSlotMapKey, PillTypeMap, PillTypeMapKey, Vector2f, Vector3f, BoundingBox, PillStyle, get_type_name };
 
-use std::path::{ Path, PathBuf };
+use std::{ io::{ BufReader, Cursor }, path::{ Path, PathBuf } };
 use boolinator::Boolinator;
-use cgmath::InnerSpace;
+use cgmath::{ InnerSpace, Zero };
 use tobj::LoadOptions;
 use anyhow::{Result, Context, Error};
 
+const DEFERRED_REQUEST_VARIANT_DESTROY_STALE_RENDERER_RESOURCE: usize = 0;
+const DEFERRED_REQUEST_VARIANT_UPDATE_RENDERER_RESOURCE: usize = 1;
 
 pill_core::define_new_pill_slotmap_key! { 
     pub struct MeshHandle;
@@ -24,20 +26,64 @@ pub struct Mesh {
     #[readonly]
     pub name: String,
     #[readonly]
-    pub path: PathBuf,
+    pub load_type: ResourceLoadType,
     pub(crate) renderer_resource_handle: Option<RendererMeshHandle>,
     mesh_data: Option<MeshData>,
+    stale_renderer_resource_handle: Option<RendererMeshHandle>, // Renderer mesh replaced by hot reload, destroyed after render queue keys are updated
+    mesh_data_changed: bool, // Mesh data was set and is waiting to be uploaded to renderer mesh
+    handle: Option<MeshHandle>,
+    deferred_update_manager: Option<DeferredUpdateManagerPointer>,
 }
 
 impl Mesh {
-    pub fn new(name: &str, path: PathBuf) -> Self {  
+    pub fn new(name: &str, resource_load_type: ResourceLoadType) -> Self {  
         Self { 
             name: name.to_string(),
-            path,
+            load_type: resource_load_type,
             renderer_resource_handle: None,
             mesh_data: None,
+            stale_renderer_resource_handle: None,
+            mesh_data_changed: false,
+            handle: None,
+            deferred_update_manager: None,
         }
     }
+
+    // Creates mesh from data built in code (e.g. with MeshData::builder or one of primitive shapes)
+    pub fn from_mesh_data(name: &str, mesh_data: MeshData) -> Self {
+        Self {
+            mesh_data: Some(mesh_data),
+            ..Self::new(name, ResourceLoadType::Generated)
+        }
+    }
+
+    pub fn get_mesh_data(&self) -> Option<&MeshData> {
+        self.mesh_data.as_ref()
+    }
+
+    // Replaces mesh data, renderer mesh is updated at the end of the frame (its handle stays the same so render queue keys remain valid)
+    pub fn set_mesh_data(&mut self, mut mesh_data: MeshData) {
+        // Vertices may have been modified after mesh data was built
+        mesh_data.update_bounding_box();
+        self.mesh_data = Some(mesh_data);
+
+        // Post deferred update request (only if mesh is initialized and only once until renderer mesh is updated)
+        if self.renderer_resource_handle.is_some() && !self.mesh_data_changed {
+            self.mesh_data_changed = true;
+            self.post_deferred_update_request(DEFERRED_REQUEST_VARIANT_UPDATE_RENDERER_RESOURCE);
+        }
+    }
+
+    fn keep_deferred_update_manager(&mut self, engine: &mut Engine) {
+        let deferred_update_component = engine.get_global_component_mut::<DeferredUpdateComponent>().expect("Critical: No DeferredUpdateComponent");
+        self.deferred_update_manager = Some(deferred_update_component.borrow_deferred_update_manager());
+    }
+
+    fn post_deferred_update_request(&mut self, request_variant: usize) {
+        let handle = self.handle.expect("Critical: Cannot post deferred update request. No Handle set in Resource");
+        let request = DeferredUpdateResourceRequest::<Mesh>::new(handle, request_variant);
+        self.deferred_update_manager.as_mut().expect("Critical: No DeferredUpdateManager").post_update_request(request);
+    }
 }
 
 impl PillTypeMapKey for Mesh {
@@ -53,13 +99,16 @@ impl Resource for Mesh {
 
     fn initialize(&mut self, engine: &mut Engine) -> Result<()> { 
         let error_message = format!("Initializing {} {} failed", "Resource".gobj_style(), get_type_name::<Self>().sobj_style());
-        
-        // Check if path to asset is correct
-        pill_core::validate_asset_path(&self.path, &["obj"]).context(error_message.clone())?;
 
-        // Create mesh data
-        let mesh_data = MeshData::new(&self.path).context(error_message.clone())?;
-        self.mesh_data = Some(mesh_data);
+        // This resource is using DeferredUpdateSystem so keep DeferredUpdateManager
+        self.keep_deferred_update_manager(engine);
+        
+        // Create mesh data with asset loader registered for its format (generated meshes already have it)
+        if self.mesh_data.is_none() {
+            let mesh_data = prepare_asset_load::<MeshData>(&engine.resource_manager, &self.load_type)
+                .and_then(|asset_load| asset_load()).context(error_message.clone())?;
+            self.mesh_data = Some(mesh_data);
+        }
   
         // Create new renderer mesh resource
         let renderer_resource_handle = engine.renderer.create_mesh(&self.name, &self.mesh_data.as_ref().unwrap()).context(error_message.clone())?;
@@ -68,22 +117,129 @@ impl Resource for Mesh {
         Ok(())
     }
 
+    fn pass_handle<H: PillSlotMapKey>(&mut self, self_handle: H) { 
+        self.handle = Some(MeshHandle::from(self_handle.data()));
+    }
+
+    fn deferred_update(&mut self, engine: &mut Engine, request: usize) -> Result<()> { 
+        match request {
+            DEFERRED_REQUEST_VARIANT_DESTROY_STALE_RENDERER_RESOURCE => 
+            {
+                // Render queue keys no longer point to old renderer mesh so it can be destroyed
+                if let Some(v) = self.stale_renderer_resource_handle.take() {
+                    engine.renderer.destroy_mesh(v)?;
+                }
+            },
+            DEFERRED_REQUEST_VARIANT_UPDATE_RENDERER_RESOURCE => 
+            {
+                // Write new data to existing renderer mesh
+                self.mesh_data_changed = false;
+                let mesh_data = self.mesh_data.as_ref().expect("Critical: No MeshData");
+                engine.renderer.update_mesh(self.renderer_resource_handle.expect("Critical: No RendererMeshHandle"), mesh_data)?;
+            },
+            _ => 
+            {
+                panic!("Critical: Processing deferred update request with value {} in {} failed. Handling is not implemented", request, get_type_name::<Self>().sobj_style());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn get_data_loader(&self, resource_manager: &ResourceManager) -> Option<ResourceDataLoader> {
+        // Generated meshes have nothing to load
+        if self.mesh_data.is_some() {
+            return None;
+        }
+
+        // Asset loader is chosen here since resource manager is not available on worker thread
+        let asset_load = prepare_asset_load::<MeshData>(resource_manager, &self.load_type);
+        Some(Box::new(move || Ok(Box::new(asset_load?()?) as ResourceData)))
+    }
+
+    fn prepare_data_loading(&mut self, engine: &mut Engine) {
+        // This resource is using DeferredUpdateSystem so keep DeferredUpdateManager (it is needed by reload even if loading fails)
+        self.keep_deferred_update_manager(engine);
+    }
+
+    fn initialize_with_data<H: PillSlotMapKey>(&mut self, engine: &mut Engine, self_handle: H, data: ResourceData) -> Result<()> {
+        let error_message = format!("Initializing {} {} {} failed", "Resource".gobj_style(), get_type_name::<Self>().sobj_style(), self.name.name_style());
+
+        // Create new renderer mesh resource from loaded mesh data (unless mesh data was set while loading)
+        let mesh_data = self.mesh_data.take().unwrap_or_else(|| *data.downcast::<MeshData>().expect("Critical: Wrong resource data type"));
+        let renderer_resource_handle = engine.renderer.create_mesh(&self.name, &mesh_data).context(error_message)?;
+        self.renderer_resource_handle = Some(renderer_resource_handle);
+        self.mesh_data = Some(mesh_data);
+
+        // Mesh rendering components that use this mesh were not rendered until now so update their render queue keys
+        request_render_queue_key_updates(engine, self_handle)?;
+
+        Ok(())
+    }
+
+    fn get_source_paths(&self) -> Vec<PathBuf> {
+        match &self.load_type {
+            ResourceLoadType::Path(path) => vec![path.clone()],
+            ResourceLoadType::Bytes(_) | ResourceLoadType::Archive(_) | ResourceLoadType::Generated => Vec::new(),
+        }
+    }
+
+    fn reload<H: PillSlotMapKey>(&mut self, engine: &mut Engine, self_handle: H) -> Result<()> {
+        let error_message = format!("Reloading {} {} {} failed", "Resource".gobj_style(), get_type_name::<Self>().sobj_style(), self.name.name_style());
+
+        // Create new mesh data and renderer mesh resource (old ones are kept if loading fails)
+        let mesh_data = prepare_asset_load::<MeshData>(&engine.resource_manager, &self.load_type)
+            .and_then(|asset_load| asset_load()).context(error_message.clone())?;
+        let renderer_resource_handle = engine.renderer.create_mesh(&self.name, &mesh_data).context(error_message)?;
+        self.mesh_data = Some(mesh_data);
+
+        // Old renderer mesh is still used by render queue keys so destroy it only after they are updated
+        if let Some(v) = self.stale_renderer_resource_handle.take() {
+            engine.renderer.destroy_mesh(v)?;
+        }
+        self.stale_renderer_resource_handle = self.renderer_resource_handle.replace(renderer_resource_handle);
+
+        // Update render queue keys of mesh rendering components that use this mesh
+        request_render_queue_key_updates(engine, self_handle)?;
+
+        // Requests are processed in order so this one is handled after all render queue keys are updated
+        self.post_deferred_update_request(DEFERRED_REQUEST_VARIANT_DESTROY_STALE_RENDERER_RESOURCE);
+
+        Ok(())
+    }
+
+    fn get_reference_count<H: PillSlotMapKey>(&self, engine: &Engine, self_handle: H) -> Result<usize> {
+        // Count components in all scenes that use this resource
+        let mut reference_count = 0;
+        for (scene_handle, scene) in engine.scene_manager.scenes.iter() {
+            for (entity_handle, component) in scene.get_one_component_iterator::<MeshRenderingComponent>()? {
+                if component.uses_mesh(&MeshHandle::from(self_handle.data())) {
+                    reference_count += 1;
+                }
+            }
+        }
+
+        Ok(reference_count)
+    }
+
     fn destroy<H: PillSlotMapKey>(&mut self, engine: &mut Engine, self_handle: H) -> Result<()> {
 
-        // Destroy renderer resource
+        // Destroy renderer resources
         if let Some(v) = self.renderer_resource_handle {
             engine.renderer.destroy_mesh(v).unwrap();
         }
+        if let Some(v) = self.stale_renderer_resource_handle.take() {
+            engine.renderer.destroy_mesh(v).unwrap();
+        }
 
         // Find mesh rendering components that use this mesh and update them
         for (scene_handle, scene) in engine.scene_manager.scenes.iter_mut() {
             for (entity_handle, mesh_rendering_component) in scene.get_one_component_iterator_mut::<MeshRenderingComponent>()? {
-                if let Some(mesh_handle) = mesh_rendering_component.mesh_handle {
-                    // If mesh rendering component has handle to this mesh 
-                    if mesh_handle.data() == self_handle.data() {
-                        mesh_rendering_component.set_mesh_handle(Option::<MeshHandle>::None);
-                        mesh_rendering_component.update_render_queue_key(&engine.resource_manager).unwrap();
-                    }
+                // If mesh rendering component has handle to this mesh (directly or in LOD group)
+                let mesh_handle = MeshHandle::from(self_handle.data());
+                if mesh_rendering_component.uses_mesh(&mesh_handle) {
+                    mesh_rendering_component.remove_mesh_handle(&mesh_handle);
+                    mesh_rendering_component.update_render_queue_key(&engine.resource_manager).unwrap();
                 }
             }
         }
@@ -92,133 +248,278 @@ impl Resource for Mesh {
     }
 }
 
+// Requests render queue key update of mesh rendering components that use mesh with given handle
+fn request_render_queue_key_updates<H: PillSlotMapKey>(engine: &mut Engine, mesh_handle: H) -> Result<()> {
+    for (scene_handle, scene) in engine.scene_manager.scenes.iter_mut() {
+        for (entity_handle, mesh_rendering_component) in scene.get_one_component_iterator_mut::<MeshRenderingComponent>()? {
+            // If mesh rendering component has handle to this mesh (directly or in LOD group)
+            if mesh_rendering_component.uses_mesh(&MeshHandle::from(mesh_handle.data())) {
+                mesh_rendering_component.request_render_queue_key_update();
+            }
+        }
+    }
+
+    Ok(())
+}
+
 #[repr(C)]
 // bytemuck::Pod indicates that Vertex is "Plain Old Data", and thus can be interpretted as a &[u8]
 // bytemuck::Zeroable indicates that Vertex can be used with std::mem::zeroed()
 #[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct MeshVertex {
-    position: [f32; 3],
-    texture_coordinates: [f32; 2],
-    normal: [f32; 3],
-    tangent: [f32; 3],
-    bitangent: [f32; 3],
+    pub position: [f32; 3],
+    pub texture_coordinates: [f32; 2],
+    pub normal: [f32; 3],
+    pub tangent: [f32; 3],
+    pub bitangent: [f32; 3],
+}
+
+impl MeshVertex {
+    pub fn new(position: Vector3f, texture_coordinates: Vector2f, normal: Vector3f) -> Self {
+        Self {
+            position: position.into(),
+            texture_coordinates: texture_coordinates.into(),
+            normal: normal.into(),
+            tangent: [0.0; 3],
+            bitangent: [0.0; 3],
+        }
+    }
+}
+
+// --- Builder ---
+
+pub struct MeshDataBuilder {
+    positions: Vec<Vector3f>,
+    texture_coordinates: Vec<Vector2f>,
+    normals: Vec<Vector3f>,
+    indices: Vec<u32>,
+}
+
+impl MeshDataBuilder {
+    pub fn default() -> Self {
+        Self {
+            positions: Vec::<Vector3f>::new(),
+            texture_coordinates: Vec::<Vector2f>::new(),
+            normals: Vec::<Vector3f>::new(),
+            indices: Vec::<u32>::new(),
+        }
+    }
+
+    pub fn positions(mut self, positions: Vec<Vector3f>) -> Self {
+        self.positions = positions;
+        self
+    }
+
+    // Optional, set to zero if not provided
+    pub fn texture_coordinates(mut self, texture_coordinates: Vec<Vector2f>) -> Self {
+        self.texture_coordinates = texture_coordinates;
+        self
+    }
+
+    // Optional, calculated from triangles if not provided
+    pub fn normals(mut self, normals: Vec<Vector3f>) -> Self {
+        self.normals = normals;
+        self
+    }
+
+    // Three indices per triangle, counter clockwise when looking at the front of the triangle
+    // Optional, if not provided every three positions make a triangle
+    pub fn indices(mut self, indices: Vec<u32>) -> Self {
+        self.indices = indices;
+        self
+    }
+
+    pub fn build(self) -> Result<MeshData> {
+        let vertex_count = self.positions.len();
+        let indices = match self.indices.is_empty() {
+            true => (0..vertex_count as u32).collect::<Vec<u32>>(),
+            false => self.indices,
+        };
+
+        // Check data validity
+        if vertex_count == 0 {
+            return Err(Error::new(EngineError::InvalidMeshData("No vertex positions".to_string())));
+        }
+        if !self.texture_coordinates.is_empty() && self.texture_coordinates.len() != vertex_count {
+            return Err(Error::new(EngineError::InvalidMeshData(format!("Expected {} texture coordinates but got {}", vertex_count, self.texture_coordinates.len()))));
+        }
+        if !self.normals.is_empty() && self.normals.len() != vertex_count {
+            return Err(Error::new(EngineError::InvalidMeshData(format!("Expected {} normals but got {}", vertex_count, self.normals.len()))));
+        }
+        if indices.len() % 3 != 0 {
+            return Err(Error::new(EngineError::InvalidMeshData(format!("Index count {} is not a multiple of 3", indices.len()))));
+        }
+        if let Some(index) = indices.iter().find(|v| **v as usize >= vertex_count) {
+            return Err(Error::new(EngineError::InvalidMeshData(format!("Index {} is out of range of {} vertices", index, vertex_count))));
+        }
+
+        // Fill missing data
+        let texture_coordinates = match self.texture_coordinates.is_empty() {
+            true => vec![Vector2f::new(0.0, 0.0); vertex_count],
+            false => self.texture_coordinates,
+        };
+        let normals = match self.normals.is_empty() {
+            true => calculate_normals(&self.positions, &indices),
+            false => self.normals,
+        };
+
+        // Create vertices
+        let mut vertices = self.positions.iter().zip(texture_coordinates.iter()).zip(normals.iter())
+            .map(|((position, texture_coordinates), normal)| MeshVertex::new(*position, *texture_coordinates, *normal))
+            .collect::<Vec<MeshVertex>>();
+        calculate_tangents(&mut vertices, &indices);
+
+        let mut mesh_data = MeshData {
+            vertices,
+            indices,
+            bounding_box: BoundingBox::new(Vector3f::zero(), Vector3f::zero()),
+        };
+        mesh_data.update_bounding_box();
+
+        Ok(mesh_data)
+    }
 }
 
+// --- Mesh data ---
+
 pub struct MeshData {
     pub vertices: Vec<MeshVertex>,
     pub indices: Vec<u32>,
+    pub bounding_box: BoundingBox, // Box in local space containing all vertices, used for frustum culling
 }
 
 impl MeshData {
+    pub fn builder() -> MeshDataBuilder {
+        MeshDataBuilder::default()
+    }
+
+    // Has to be called after vertex positions are modified directly
+    pub fn update_bounding_box(&mut self) {
+        let positions = self.vertices.iter().map(|v| Vector3f::from(v.position));
+        self.bounding_box = BoundingBox::from_points(positions).unwrap_or(BoundingBox::new(Vector3f::zero(), Vector3f::zero()));
+    }
+
     pub fn new(path: &PathBuf) -> Result<Self> {  
-        // Load model from path using tinyobjloader crate
+        // Read bytes from disk or mounted archive
+        let bytes = read_asset(path)?;
+
+        Self::from_obj_bytes(Some(path), &bytes)
+    }
+
+    // Creates mesh data from contents of .obj file, path is only used in error messages
+    pub fn from_obj_bytes(path: Option<&Path>, bytes: &[u8]) -> Result<Self> {  
+        // Load model using tinyobjloader crate
         let load_options = LoadOptions {
             triangulate: true,
             single_index: true,
             ..Default::default()
         };
 
-        // Load data
-        let (models, _materials) = tobj::load_obj(path.as_path(), &load_options)?;
+        // Load data (materials are not used so they are not loaded)
+        let (models, _materials) = tobj::load_obj_buf(&mut BufReader::new(Cursor::new(bytes)), &load_options, |_| Err(tobj::LoadError::OpenFileFailed))?;
 
         // Check data validity
+        let model_name = path.map(|v| v.display().to_string()).unwrap_or("<bytes>".to_string());
         if models.len() > 1 {
-            return Err(Error::new(EngineError::InvalidModelFileMultipleMeshes(path.clone().into_os_string().into_string().unwrap())));
+            return Err(Error::new(EngineError::InvalidModelFileMultipleMeshes(model_name)));
         }
 
         if models.len() < 1 {
-            return Err(Error::new(EngineError::InvalidModelFile(path.clone().into_os_string().into_string().unwrap())));
+            return Err(Error::new(EngineError::InvalidModelFile(model_name)));
         }
 
         // Load vertex data from model
         let mesh = &models[0].mesh;
+        let positions = mesh.positions.chunks(3).map(|v| Vector3f::new(v[0], v[1], v[2])).collect::<Vec<Vector3f>>();
+        let texture_coordinates = mesh.texcoords.chunks(2).map(|v| Vector2f::new(v[0], v[1])).collect::<Vec<Vector2f>>();
+        let normals = mesh.normals.chunks(3).map(|v| Vector3f::new(v[0], v[1], v[2])).collect::<Vec<Vector3f>>();
+
+        // Create mesh data (tangents and bitangents are calculated)
+        MeshData::builder()
+            .positions(positions)
+            .texture_coordinates(texture_coordinates)
+            .normals(normals)
+            .indices(mesh.indices.clone())
+            .build()
+            .context(EngineError::InvalidModelFile(model_name))
+    }    
+}
 
-        // Read vertices
-        let mut vertices = Vec::new();
-        for i in 0..mesh.positions.len() / 3 {
-            vertices.push(MeshVertex {
-                position: [
-                    mesh.positions[i * 3],
-                    mesh.positions[i * 3 + 1],
-                    mesh.positions[i * 3 + 2],
-                ],
-                texture_coordinates: [
-                    *mesh.texcoords.get(i * 2).unwrap_or(&0.0),
-                    *mesh.texcoords.get(i * 2 + 1).unwrap_or(&0.0),
-                ],
-                normal: [
-                    mesh.normals[i * 3],
-                    mesh.normals[i * 3 + 1],
-                    mesh.normals[i * 3 + 2],
-                ],
-                tangent: [0.0; 3].into(),
-                bitangent: [0.0; 3].into(),
-            });
-        }
-
-        // Read indices
-        let indices = &mesh.indices;
-        let mut triangles_included = (0..vertices.len()).collect::<Vec<_>>();
-
-        // Calculate tangents and bitangets
-        for c in indices.chunks(3) {
-            let v0 = vertices[c[0] as usize];
-            let v1 = vertices[c[1] as usize];
-            let v2 = vertices[c[2] as usize];
-
-            let pos0: cgmath::Vector3<_> = v0.position.into();
-            let pos1: cgmath::Vector3<_> = v1.position.into();
-            let pos2: cgmath::Vector3<_> = v2.position.into();
-
-            let uv0: cgmath::Vector2<_> = v0.texture_coordinates.into();
-            let uv1: cgmath::Vector2<_> = v1.texture_coordinates.into();
-            let uv2: cgmath::Vector2<_> = v2.texture_coordinates.into();
-
-            // Calculate the edges of the triangle
-            let delta_pos1 = pos1 - pos0;
-            let delta_pos2 = pos2 - pos0;
-
-            // Calculate the direction needed to calculate the tangent and bitangent
-            let delta_uv1 = uv1 - uv0;
-            let delta_uv2 = uv2 - uv0;
-
-            // Calculate tangent and bitangent       
-            let r = 1.0 / (delta_uv1.x * delta_uv2.y - delta_uv1.y * delta_uv2.x);
-            let tangent = (delta_pos1 * delta_uv2.y - delta_pos2 * delta_uv1.y) * r;
-            let bitangent = (delta_pos2 * delta_uv1.x - delta_pos1 * delta_uv2.x) * r;
-
-            // Assign same tangent/bitangent to each vertex in the triangle
-            vertices[c[0] as usize].tangent = (tangent + cgmath::Vector3::from(vertices[c[0] as usize].tangent)).into();
-            vertices[c[1] as usize].tangent = (tangent + cgmath::Vector3::from(vertices[c[1] as usize].tangent)).into();
-            vertices[c[2] as usize].tangent = (tangent + cgmath::Vector3::from(vertices[c[2] as usize].tangent)).into();
-            vertices[c[0] as usize].bitangent = (bitangent + cgmath::Vector3::from(vertices[c[0] as usize].bitangent)).into();
-            vertices[c[1] as usize].bitangent = (bitangent + cgmath::Vector3::from(vertices[c[1] as usize].bitangent)).into();
-            vertices[c[2] as usize].bitangent = (bitangent + cgmath::Vector3::from(vertices[c[2] as usize].bitangent)).into();
-
-            // Prepare data for averaging tangents and bitangents
-            triangles_included[c[0] as usize] += 1;
-            triangles_included[c[1] as usize] += 1;
-            triangles_included[c[2] as usize] += 1;
-        }
-
-        // Average the tangents and bitangents
-        for (i, n) in triangles_included.into_iter().enumerate() {
-            let denom = 1.0 / n as f32;
-            let mut v = &mut vertices[i];
-            v.tangent = (Vector3f::from(v.tangent) * denom).normalize().into();
-            v.bitangent = (Vector3f::from(v.bitangent) * denom).normalize().into();
-        }
-
-        let mesh_data = MeshData {
-            vertices: vertices,
-            indices: mesh.indices.clone(),
-        };
+// Calculates smooth normals by averaging normals of triangles that share the vertex (weighted by triangle area)
+fn calculate_normals(positions: &[Vector3f], indices: &[u32]) -> Vec<Vector3f> {
+    let mut normals = vec![Vector3f::zero(); positions.len()];
+    for c in indices.chunks(3) {
+        let normal = (positions[c[1] as usize] - positions[c[0] as usize]).cross(positions[c[2] as usize] - positions[c[0] as usize]);
+        for i in c {
+            normals[*i as usize] += normal;
+        }
+    }
 
-        Ok(mesh_data)
-    }    
+    // Vertices that are not part of any proper triangle get normal pointing up
+    normals.into_iter()
+        .map(|v| match v.magnitude2() > f32::EPSILON { true => v.normalize(), false => Vector3f::unit_y() })
+        .collect()
 }
 
+// Calculates tangents and bitangents from texture coordinates, they are averaged for vertices shared by triangles
+fn calculate_tangents(vertices: &mut [MeshVertex], indices: &[u32]) {
+    let mut tangents = vec![Vector3f::zero(); vertices.len()];
+    let mut bitangents = vec![Vector3f::zero(); vertices.len()];
+
+    for c in indices.chunks(3) {
+        let v0 = vertices[c[0] as usize];
+        let v1 = vertices[c[1] as usize];
+        let v2 = vertices[c[2] as usize];
+
+        let pos0: Vector3f = v0.position.into();
+        let pos1: Vector3f = v1.position.into();
+        let pos2: Vector3f = v2.position.into();
+
+        let uv0: Vector2f = v0.texture_coordinates.into();
+        let uv1: Vector2f = v1.texture_coordinates.into();
+        let uv2: Vector2f = v2.texture_coordinates.into();
+
+        // Calculate the edges of the triangle
+        let delta_pos1 = pos1 - pos0;
+        let delta_pos2 = pos2 - pos0;
+
+        // Calculate the direction needed to calculate the tangent and bitangent
+        let delta_uv1 = uv1 - uv0;
+        let delta_uv2 = uv2 - uv0;
 
+        // Skip triangles with degenerated texture coordinates
+        let determinant = delta_uv1.x * delta_uv2.y - delta_uv1.y * delta_uv2.x;
+        if determinant.abs() <= f32::EPSILON {
+            continue;
+        }
+
+        // Calculate tangent and bitangent
+        let r = 1.0 / determinant;
+        let tangent = (delta_pos1 * delta_uv2.y - delta_pos2 * delta_uv1.y) * r;
+        let bitangent = (delta_pos2 * delta_uv1.x - delta_pos1 * delta_uv2.x) * r;
+
+        // Add tangent/bitangent to each vertex in the triangle
+        for i in c {
+            tangents[*i as usize] += tangent;
+            bitangents[*i as usize] += bitangent;
+        }
+    }
 
+    // Make tangents perpendicular to normals and keep handedness of bitangents
+    for (i, vertex) in vertices.iter_mut().enumerate() {
+        let normal = Vector3f::from(vertex.normal);
+        let mut tangent = tangents[i] - normal * normal.dot(tangents[i]);
+        if tangent.magnitude2() <= f32::EPSILON {
+            // Texture coordinates do not define tangent so use any direction perpendicular to normal
+            let axis = match normal.x.abs() < 0.9 { true => Vector3f::unit_x(), false => Vector3f::unit_y() };
+            tangent = normal.cross(axis).cross(normal);
+        }
+        let tangent = tangent.normalize();
 
+        let bitangent = normal.cross(tangent);
+        let handedness = match bitangent.dot(bitangents[i]) < 0.0 { true => -1.0, false => 1.0 };
 
+        vertex.tangent = tangent.into();
+        vertex.bitangent = (bitangent * handedness).into();
+    }
+}
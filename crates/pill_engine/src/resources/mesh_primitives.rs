@@ -0,0 +1,238 @@
+use crate::resources::MeshData;
+
+use pill_core::{ Vector2f, Vector3f };
+
+use std::f32::consts::PI;
+use cgmath::{ InnerSpace, Zero };
+
+// Primitive shapes are centered at the origin, Y axis points up
+// Texture coordinates go from the top left corner of the texture when looking at the shape from outside
+
+impl MeshData {
+    pub fn cube(size: f32) -> Self {
+        let mut primitive = PrimitiveData::new();
+
+        // Add faces (normal and up direction of each face)
+        let faces = [
+            (Vector3f::unit_z(), Vector3f::unit_y()),
+            (-Vector3f::unit_z(), Vector3f::unit_y()),
+            (Vector3f::unit_x(), Vector3f::unit_y()),
+            (-Vector3f::unit_x(), Vector3f::unit_y()),
+            (Vector3f::unit_y(), -Vector3f::unit_z()),
+            (-Vector3f::unit_y(), Vector3f::unit_z()),
+        ];
+        for (normal, up) in faces.iter() {
+            primitive.add_quad(*normal * size / 2.0, *normal, *up, size);
+        }
+
+        primitive.build()
+    }
+
+    // Plane lies in XZ plane and faces up
+    pub fn plane(size: f32) -> Self {
+        let mut primitive = PrimitiveData::new();
+        primitive.add_quad(Vector3f::zero(), Vector3f::unit_y(), -Vector3f::unit_z(), size);
+
+        primitive.build()
+    }
+
+    pub fn uv_sphere(radius: f32, segments: u32, rings: u32) -> Self {
+        let rings = rings.max(2);
+
+        // Create profile from top to bottom pole
+        let profile = (0..=rings).map(|i| {
+            let angle = PI * i as f32 / rings as f32;
+            ProfilePoint::new(radius * angle.sin(), radius * angle.cos(), angle.sin(), angle.cos(), i as f32 / rings as f32)
+        }).collect::<Vec<ProfilePoint>>();
+
+        let mut primitive = PrimitiveData::new();
+        primitive.add_surface_of_revolution(&profile, segments);
+
+        primitive.build()
+    }
+
+    pub fn cylinder(radius: f32, height: f32, segments: u32) -> Self {
+        let half_height = height / 2.0;
+        let profile = [
+            ProfilePoint::new(radius, half_height, 1.0, 0.0, 0.0),
+            ProfilePoint::new(radius, -half_height, 1.0, 0.0, 1.0),
+        ];
+
+        let mut primitive = PrimitiveData::new();
+        primitive.add_surface_of_revolution(&profile, segments);
+        primitive.add_disc(Vector3f::unit_y() * half_height, Vector3f::unit_y(), -Vector3f::unit_z(), radius, segments);
+        primitive.add_disc(-Vector3f::unit_y() * half_height, -Vector3f::unit_y(), Vector3f::unit_z(), radius, segments);
+
+        primitive.build()
+    }
+
+    // Height is the total height including hemispheres, rings are counted per hemisphere
+    pub fn capsule(radius: f32, height: f32, segments: u32, rings: u32) -> Self {
+        let rings = rings.max(1);
+        let half_cylinder_height = (height / 2.0 - radius).max(0.0);
+
+        // Texture coordinates are distributed along the profile length
+        let hemisphere_length = PI * radius / 2.0;
+        let profile_length = 2.0 * hemisphere_length + 2.0 * half_cylinder_height;
+
+        // Create profile from top to bottom pole, hemispheres are connected by cylinder
+        let mut profile = Vec::<ProfilePoint>::new();
+        for i in 0..=rings {
+            let angle = PI / 2.0 * i as f32 / rings as f32;
+            let v = hemisphere_length * i as f32 / rings as f32 / profile_length;
+            profile.push(ProfilePoint::new(radius * angle.sin(), half_cylinder_height + radius * angle.cos(), angle.sin(), angle.cos(), v));
+        }
+        for i in 0..=rings {
+            let angle = PI / 2.0 + PI / 2.0 * i as f32 / rings as f32;
+            let v = (hemisphere_length * (1.0 + i as f32 / rings as f32) + 2.0 * half_cylinder_height) / profile_length;
+            profile.push(ProfilePoint::new(radius * angle.sin(), -half_cylinder_height + radius * angle.cos(), angle.sin(), angle.cos(), v));
+        }
+
+        let mut primitive = PrimitiveData::new();
+        primitive.add_surface_of_revolution(&profile, segments);
+
+        primitive.build()
+    }
+
+    // Radius is the distance from the center to the middle of the tube, torus lies in XZ plane
+    pub fn torus(radius: f32, tube_radius: f32, segments: u32, tube_segments: u32) -> Self {
+        let tube_segments = tube_segments.max(3);
+
+        // Create profile going around the tube, starting from its outer side
+        let profile = (0..=tube_segments).map(|i| {
+            let angle = -2.0 * PI * i as f32 / tube_segments as f32;
+            ProfilePoint::new(radius + tube_radius * angle.cos(), tube_radius * angle.sin(), angle.cos(), angle.sin(), i as f32 / tube_segments as f32)
+        }).collect::<Vec<ProfilePoint>>();
+
+        let mut primitive = PrimitiveData::new();
+        primitive.add_surface_of_revolution(&profile, segments);
+
+        primitive.build()
+    }
+}
+
+// Point of the profile rotated around Y axis to create surface of revolution
+struct ProfilePoint {
+    radius: f32, // Distance from Y axis
+    height: f32,
+    normal: Vector2f, // Normal in radial (x) and vertical (y) direction
+    v: f32, // Vertical texture coordinate
+}
+
+impl ProfilePoint {
+    fn new(radius: f32, height: f32, radial_normal: f32, vertical_normal: f32, v: f32) -> Self {
+        Self {
+            radius,
+            height,
+            normal: Vector2f::new(radial_normal, vertical_normal),
+            v,
+        }
+    }
+}
+
+struct PrimitiveData {
+    positions: Vec<Vector3f>,
+    texture_coordinates: Vec<Vector2f>,
+    normals: Vec<Vector3f>,
+    indices: Vec<u32>,
+}
+
+impl PrimitiveData {
+    fn new() -> Self {
+        Self {
+            positions: Vec::<Vector3f>::new(),
+            texture_coordinates: Vec::<Vector2f>::new(),
+            normals: Vec::<Vector3f>::new(),
+            indices: Vec::<u32>::new(),
+        }
+    }
+
+    fn add_vertex(&mut self, position: Vector3f, texture_coordinates: Vector2f, normal: Vector3f) -> u32 {
+        self.positions.push(position);
+        self.texture_coordinates.push(texture_coordinates);
+        self.normals.push(normal);
+        self.positions.len() as u32 - 1
+    }
+
+    // Adds triangle with vertices ordered counter clockwise when looking at its front (side its vertex normals point to)
+    fn add_triangle(&mut self, a: u32, b: u32, c: u32) {
+        let (pos_a, pos_b, pos_c) = (self.positions[a as usize], self.positions[b as usize], self.positions[c as usize]);
+        let normal = self.normals[a as usize] + self.normals[b as usize] + self.normals[c as usize];
+        match (pos_b - pos_a).cross(pos_c - pos_a).dot(normal) < 0.0 {
+            true => self.indices.extend_from_slice(&[a, c, b]),
+            false => self.indices.extend_from_slice(&[a, b, c]),
+        }
+    }
+
+    fn add_quad(&mut self, center: Vector3f, normal: Vector3f, up: Vector3f, size: f32) {
+        let right = up.cross(normal);
+        let half_size = size / 2.0;
+
+        let top_left = self.add_vertex(center + (up - right) * half_size, Vector2f::new(0.0, 0.0), normal);
+        let top_right = self.add_vertex(center + (up + right) * half_size, Vector2f::new(1.0, 0.0), normal);
+        let bottom_left = self.add_vertex(center + (-up - right) * half_size, Vector2f::new(0.0, 1.0), normal);
+        let bottom_right = self.add_vertex(center + (-up + right) * half_size, Vector2f::new(1.0, 1.0), normal);
+
+        self.add_triangle(top_left, bottom_left, bottom_right);
+        self.add_triangle(top_left, bottom_right, top_right);
+    }
+
+    fn add_disc(&mut self, center: Vector3f, normal: Vector3f, up: Vector3f, radius: f32, segments: u32) {
+        let segments = segments.max(3);
+        let right = up.cross(normal);
+
+        let center_index = self.add_vertex(center, Vector2f::new(0.5, 0.5), normal);
+        let first_index = center_index + 1;
+        for i in 0..segments {
+            let angle = 2.0 * PI * i as f32 / segments as f32;
+            let position = center + (right * angle.cos() + up * angle.sin()) * radius;
+            let texture_coordinates = Vector2f::new(0.5 + 0.5 * angle.cos(), 0.5 - 0.5 * angle.sin());
+            self.add_vertex(position, texture_coordinates, normal);
+        }
+
+        for i in 0..segments {
+            self.add_triangle(center_index, first_index + i, first_index + (i + 1) % segments);
+        }
+    }
+
+    // Rotates profile around Y axis, first column of vertices is duplicated at the end so texture wraps around
+    fn add_surface_of_revolution(&mut self, profile: &[ProfilePoint], segments: u32) {
+        let segments = segments.max(3);
+        let first_index = self.positions.len() as u32;
+
+        for point in profile.iter() {
+            for i in 0..=segments {
+                // Angle goes clockwise when looking from above so texture is not mirrored when looking from outside
+                let angle = 2.0 * PI * i as f32 / segments as f32;
+                let direction = Vector3f::new(angle.cos(), 0.0, -angle.sin());
+
+                let position = direction * point.radius + Vector3f::unit_y() * point.height;
+                let normal = (direction * point.normal.x + Vector3f::unit_y() * point.normal.y).normalize();
+                self.add_vertex(position, Vector2f::new(i as f32 / segments as f32, point.v), normal);
+            }
+        }
+
+        let row_length = segments + 1;
+        for j in 0..profile.len() as u32 - 1 {
+            for i in 0..segments {
+                let top_left = first_index + j * row_length + i;
+                let top_right = top_left + 1;
+                let bottom_left = top_left + row_length;
+                let bottom_right = bottom_left + 1;
+
+                self.add_triangle(top_left, bottom_left, top_right);
+                self.add_triangle(top_right, bottom_left, bottom_right);
+            }
+        }
+    }
+
+    fn build(self) -> MeshData {
+        MeshData::builder()
+            .positions(self.positions)
+            .texture_coordinates(self.texture_coordinates)
+            .normals(self.normals)
+            .indices(self.indices)
+            .build()
+            .expect("Critical: Invalid primitive mesh data")
+    }
+}
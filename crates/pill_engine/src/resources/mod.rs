@@ -2,11 +2,20 @@
 
 mod resource_manager;
 mod mesh;
+mod mesh_primitives;
 mod texture;
+mod texture_data;
 mod resource_storage;
 mod material;
+mod shader;
+mod shader_compiler;
 mod resource;
 mod sound;
+#[cfg(all(debug_assertions, feature = "hot-reload"))]
+mod resource_watcher;
+mod resource_loader;
+mod resource_archive;
+mod asset_loader;
 
 // --- Use ---
 
@@ -15,18 +24,60 @@ pub use resource_manager::ResourceManager;
 pub use resource::{
     Resource,
     ResourceLoadType,
+    ResourceRemovalPolicy,
 };
 
 pub use resource_storage::ResourceStorage;
 
+#[cfg(all(debug_assertions, feature = "hot-reload"))]
+pub use resource_watcher::ResourceWatcher;
+
+pub use resource_archive::{
+    mount_resource_archive,
+    get_resource_archive,
+};
+
+pub(crate) use resource_archive::{
+    is_asset_archived,
+    read_archived_asset,
+    read_asset,
+};
+
+pub use asset_loader::{
+    AssetLoader,
+    AssetLoaderRegistry,
+    ImageAssetLoader,
+    HdrAssetLoader,
+    Ktx2AssetLoader,
+    DdsAssetLoader,
+    ObjAssetLoader,
+    SpirvAssetLoader,
+    WgslAssetLoader,
+    AudioAssetLoader,
+};
+
+pub(crate) use asset_loader::{
+    AssetLoadFunction,
+    prepare_asset_load,
+};
+
+pub use resource_loader::{
+    ResourceLoader,
+    ResourceData,
+    ResourceDataLoader,
+    LoadState,
+};
+
 pub use sound::{
     Sound,
+    SoundData,
     SoundHandle,
 };
 
 pub use mesh::{ 
     Mesh, 
     MeshData, 
+    MeshDataBuilder,
     MeshVertex, 
     MeshHandle 
 };
@@ -34,11 +85,23 @@ pub use mesh::{
 pub use texture::{ 
     Texture, 
     TextureType, 
-    TextureHandle 
+    TextureHandle,
+    TextureSettings,
+    TextureFilter,
+    TextureWrapMode,
+    TextureColorSpace,
+};
+
+pub use texture_data::{
+    TextureData,
+    HdrImage,
+    CompressedImage,
+    CubemapImage,
 };
 
 pub use material::{ 
     Material, 
+    BlendMode,
     MaterialTextureMap, 
     MaterialParameter, 
     MaterialTexture, 
@@ -46,3 +109,16 @@ pub use material::{
     MaterialHandle,
     get_renderer_texture_handle_from_material_texture,
 };
+
+pub(crate) use material::MAX_MATERIAL_TEXTURE_SLOTS;
+
+pub use shader::{
+    Shader,
+    ShaderHandle,
+    ShaderSource,
+    ShaderData,
+    ShaderLayout,
+    ShaderTextureSlot,
+    ShaderParameterSlot,
+    ShaderParameterType,
+};
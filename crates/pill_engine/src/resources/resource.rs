@@ -2,6 +2,8 @@ use crate::engine::Engine;
 
 use pill_core::{ PillTypeMap, PillTypeMapKey, PillSlotMapKey };
 
+use crate::resources::{ ResourceManager, ResourceData, ResourceDataLoader };
+
 use std::path::PathBuf;
 use anyhow::{Context, Result, Error};
 
@@ -21,9 +23,25 @@ pub trait Resource : PillTypeMapKey {
     fn pass_handle<H: PillSlotMapKey>(&mut self, self_handle: H) {} // Called right after resource is added to the engine, after adding it to storage
     fn deferred_update(&mut self, engine: &mut Engine, request: usize) -> Result<()> { Ok(()) } // Called by DeferredUpdateSystem when request related to the resource is being processed
     fn destroy<H: PillSlotMapKey>(&mut self, engine: &mut Engine, self_handle: H) -> Result<()> { Ok(()) } // Called when resource is being removed from the engine
+    fn get_data_loader(&self, resource_manager: &ResourceManager) -> Option<ResourceDataLoader> { None } // Returns function loading data of the resource, used by add_resource_async to run it on worker thread
+    fn prepare_data_loading(&mut self, engine: &mut Engine) {} // Called by add_resource_async before resource is added to storage, it is called even if loading fails later
+    fn initialize_with_data<H: PillSlotMapKey>(&mut self, engine: &mut Engine, self_handle: H, data: ResourceData) -> Result<()> { Ok(()) } // Called on main thread with data returned by data loader, used instead of initialize by add_resource_async
+    fn get_reference_count<H: PillSlotMapKey>(&self, engine: &Engine, self_handle: H) -> Result<usize> { Ok(0) } // Returns number of resources and components that use this resource
+    fn remove_dependent_resources<H: PillSlotMapKey>(&mut self, engine: &mut Engine, self_handle: H) -> Result<()> { Ok(()) } // Called before resource is removed with cascade removal policy, components using it are updated in destroy
+    fn get_source_paths(&self) -> Vec<PathBuf> { Vec::new() } // Returns paths of the files this resource is loaded from, used for hot reloading
+    fn reload<H: PillSlotMapKey>(&mut self, engine: &mut Engine, self_handle: H) -> Result<()> { Ok(()) } // Called by HotReloadSystem when any of the files this resource is loaded from changed on disk
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResourceRemovalPolicy {
+    Cascade, // Resources that use removed resource are removed too, components that use it fall back to defaults (default)
+    Refuse, // Removing resource that is still in use fails
 }
 
+#[derive(Clone)]
 pub enum ResourceLoadType {
-    Path(PathBuf),
+    Path(PathBuf), // Loaded from disk, or from mounted archive if file is not present on disk
     Bytes(Box::<[u8]>),
+    Archive(PathBuf), // Loaded only from mounted archive, path is the same as used in development (e.g. "./res/textures/Stone.png")
+    Generated, // Data is created in code (e.g. procedural meshes), nothing is loaded
 }
@@ -0,0 +1,53 @@
+use pill_core::{ EngineError, PillArchive, PillStyle, get_archive_entry_name };
+
+use std::{
+    path::{ Path, PathBuf },
+    sync::{ Arc, RwLock },
+};
+use anyhow::{ Result, Context, Error };
+use lazy_static::lazy_static;
+use log::info;
+
+lazy_static! {
+    // Archive is global so resources can read from it on worker threads
+    static ref RESOURCE_ARCHIVE: RwLock<Option<Arc<PillArchive>>> = RwLock::new(None);
+}
+
+// Opens archive and uses it as a source of resources
+pub fn mount_resource_archive(path: &Path) -> Result<()> {
+    let archive = PillArchive::open(path)?;
+    info!("Mounted {} {}", "Archive".gobj_style(), path.display().to_string().name_style());
+    *RESOURCE_ARCHIVE.write().expect("Critical: Lock is poisoned") = Some(Arc::new(archive));
+
+    Ok(())
+}
+
+pub fn get_resource_archive() -> Option<Arc<PillArchive>> {
+    RESOURCE_ARCHIVE.read().expect("Critical: Lock is poisoned").clone()
+}
+
+// Reads asset with given development path from mounted archive
+pub(crate) fn read_archived_asset(path: &Path) -> Result<Vec<u8>> {
+    let archive = get_resource_archive().ok_or(Error::new(EngineError::ArchiveEntryNotFound(path.display().to_string())))?;
+    archive.read(&get_archive_entry_name(path))
+}
+
+// Reads asset from disk, or from mounted archive if it is not present on disk
+pub(crate) fn read_asset(path: &Path) -> Result<Vec<u8>> {
+    match is_asset_archived(path) {
+        true => read_archived_asset(path),
+        false => std::fs::read(path).map_err(|_| Error::new(EngineError::InvalidAssetPath(path.display().to_string()))),
+    }
+}
+
+// Returns true if asset is not present on disk but can be read from mounted archive
+pub(crate) fn is_asset_archived(path: &Path) -> bool {
+    if path.exists() {
+        return false;
+    }
+
+    match get_resource_archive() {
+        Some(archive) => archive.contains(&get_archive_entry_name(path)),
+        None => false,
+    }
+}
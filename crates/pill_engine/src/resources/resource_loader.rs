@@ -0,0 +1,89 @@
+use crate::engine::Engine;
+
+use std::{
+    any::Any,
+    sync::{ Arc, Mutex, mpsc::{ channel, Sender, Receiver } },
+    thread::JoinHandle,
+};
+use anyhow::Result;
+
+// Data decoded on worker thread, passed to Resource::initialize_with_data on main thread
+pub type ResourceData = Box<dyn Any + Send>;
+
+// Loads and decodes data of the resource, has no access to the engine so it can run on worker thread
+pub type ResourceDataLoader = Box<dyn FnOnce() -> Result<ResourceData> + Send>;
+
+pub(crate) type ResourceLoadCompletion = Box<dyn FnOnce(&mut Engine) + Send>;
+pub(crate) type ResourceLoadJob = Box<dyn FnOnce() -> ResourceLoadCompletion + Send>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LoadState {
+    Loading,
+    Loaded,
+    Failed,
+}
+
+// --- Resource loader ---
+
+// Runs resource load jobs on worker threads, finished loads are collected and completed on main thread
+pub struct ResourceLoader {
+    job_sender: Option<Sender<ResourceLoadJob>>,
+    completion_receiver: Receiver<ResourceLoadCompletion>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl ResourceLoader {
+    pub fn new(thread_count: usize) -> Self {
+        let (job_sender, job_receiver) = channel::<ResourceLoadJob>();
+        let (completion_sender, completion_receiver) = channel::<ResourceLoadCompletion>();
+        let job_receiver = Arc::new(Mutex::new(job_receiver));
+
+        // Create worker threads
+        let mut workers = Vec::<JoinHandle<()>>::with_capacity(thread_count.max(1));
+        for i in 0..thread_count.max(1) {
+            let job_receiver = job_receiver.clone();
+            let completion_sender = completion_sender.clone();
+            let worker = std::thread::Builder::new()
+                .name(format!("ResourceLoader{}", i))
+                .spawn(move || loop {
+                    // Wait for next job (receiving fails when loader is dropped)
+                    let job = match job_receiver.lock().expect("Critical: Mutex is blocked").recv() {
+                        Ok(job) => job,
+                        Err(_) => break,
+                    };
+
+                    // Run job and pass its completion to main thread
+                    if completion_sender.send(job()).is_err() {
+                        break;
+                    }
+                })
+                .expect("Critical: Cannot create resource loader thread");
+            workers.push(worker);
+        }
+
+        Self {
+            job_sender: Some(job_sender),
+            completion_receiver,
+            workers,
+        }
+    }
+
+    pub(crate) fn load(&self, job: ResourceLoadJob) {
+        self.job_sender.as_ref().expect("Critical: No job sender").send(job).expect("Critical: Resource loader threads stopped");
+    }
+
+    // Returns completions of loads that finished since last call
+    pub(crate) fn get_finished_loads(&self) -> Vec<ResourceLoadCompletion> {
+        self.completion_receiver.try_iter().collect()
+    }
+}
+
+impl Drop for ResourceLoader {
+    fn drop(&mut self) {
+        // Close job channel so workers stop after finishing current jobs
+        drop(self.job_sender.take());
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     graphics::{ RendererMaterialHandle, RendererTextureHandle }, 
-    resources::{ ResourceStorage, Resource, MaterialHandle, Material, TextureHandle, Texture, TextureType },
+    resources::{ ResourceStorage, Resource, ResourceRemovalPolicy, LoadState, MaterialHandle, Material, TextureHandle, Texture, TextureType, MeshData, SoundData, AssetLoader, AssetLoaderRegistry, TextureData, ShaderSource, ImageAssetLoader, HdrAssetLoader, Ktx2AssetLoader, DdsAssetLoader, ObjAssetLoader, SpirvAssetLoader, WgslAssetLoader, AudioAssetLoader, is_asset_archived },
     config::*,
 };
 
@@ -11,20 +11,43 @@ use std::{
     convert::TryInto,
     env,
     num::NonZeroU32,
-    path::PathBuf
+    path::{ Path, PathBuf },
+    sync::Arc,
 };
 use boolinator::Boolinator;
 use anyhow::{Result, Context, Error};
 
 pub struct ResourceManager {
     resources: PillTypeMap,
+    asset_loaders: PillTypeMap,
+    pub(crate) removal_policy: ResourceRemovalPolicy,
+    pub(crate) auto_unload: bool,
+    pub(crate) auto_unload_interval: f32,
+    pub(crate) next_auto_unload_time: f32, // Time when resources are scanned for unused ones again
 }
 
 impl ResourceManager {
     pub fn new() -> Self {
-	    Self { 
+	    let mut resource_manager = Self { 
             resources: PillTypeMap::new(),
-        }
+            asset_loaders: PillTypeMap::new(),
+            removal_policy: ResourceRemovalPolicy::Cascade,
+            auto_unload: false,
+            auto_unload_interval: RESOURCE_AUTO_UNLOAD_INTERVAL,
+            next_auto_unload_time: 0.0,
+        };
+
+        // Register built-in asset loaders
+        resource_manager.register_asset_loader::<TextureData>(ImageAssetLoader);
+        resource_manager.register_asset_loader::<TextureData>(HdrAssetLoader);
+        resource_manager.register_asset_loader::<TextureData>(Ktx2AssetLoader);
+        resource_manager.register_asset_loader::<TextureData>(DdsAssetLoader);
+        resource_manager.register_asset_loader::<MeshData>(ObjAssetLoader);
+        resource_manager.register_asset_loader::<ShaderSource>(WgslAssetLoader); // Default loader, shaders loaded from bytes are WGSL like the ones of the engine
+        resource_manager.register_asset_loader::<ShaderSource>(SpirvAssetLoader);
+        resource_manager.register_asset_loader::<SoundData>(AudioAssetLoader);
+
+        resource_manager
     }
 
     // --- Slots ---
@@ -116,8 +139,10 @@ impl ResourceManager {
         let resource = resource_storage.data.remove(*resource_handle)
             .ok_or(EngineError::InvalidResourceHandle(get_type_name::<T>()))?.expect("Critical: Resource is None");
 
-        // Remove mapping
+        // Remove mapping and load state
         resource_storage.mapping.remove_by_value(resource_handle);
+        resource_storage.load_states.remove(resource_handle);
+        resource_storage.used_resources.remove(resource_handle);
 
         Ok((resource_handle.clone(), resource))
     } 
@@ -135,12 +160,88 @@ impl ResourceManager {
         // Remove resource
         let resource = resource_storage.data.remove(resource_handle).unwrap().expect("Critical: Resource is None");
 
-        // Remove mapping
+        // Remove mapping and load state
         resource_storage.mapping.remove_by_key(&name.to_string());
+        resource_storage.load_states.remove(&resource_handle);
+        resource_storage.used_resources.remove(&resource_handle);
 
         Ok((resource_handle, resource))
     } 
 
+    // --- Load state ---
+
+    pub fn get_resource_load_state<T>(&self, resource_handle: &T::Handle) -> Result<LoadState> 
+        where T: Resource<Storage = ResourceStorage::<T>>
+    {
+        // Check if resource exists
+        self.get_resource_slot::<T>(resource_handle)?;
+
+        // Get load state (resources without stored state are loaded)
+        let resource_storage = self.get_resource_storage::<T>()?;
+        let load_state = resource_storage.load_states.get(resource_handle).cloned().unwrap_or(LoadState::Loaded);
+
+        Ok(load_state)
+    }
+
+    pub(crate) fn set_resource_load_state<T>(&mut self, resource_handle: &T::Handle, load_state: LoadState) -> Result<()> 
+        where T: Resource<Storage = ResourceStorage::<T>>
+    {
+        // Get resource storage
+        let resource_storage = self.get_resource_storage_mut::<T>()?;
+
+        // Set load state
+        match load_state {
+            LoadState::Loaded => { resource_storage.load_states.remove(resource_handle); },
+            _ => { resource_storage.load_states.insert(*resource_handle, load_state); },
+        }
+
+        Ok(())
+    }
+
+    // --- Asset loaders ---
+
+    pub fn register_asset_loader<D: 'static>(&mut self, asset_loader: impl AssetLoader<D> + 'static) {
+        // Create registry for this data type if it does not exist yet
+        if self.asset_loaders.get::<AssetLoaderRegistry<D>>().is_none() {
+            self.asset_loaders.insert::<AssetLoaderRegistry<D>>(AssetLoaderRegistry::<D>::new());
+        }
+
+        // Add loader
+        let asset_loader_registry = self.asset_loaders.get_mut::<AssetLoaderRegistry<D>>().unwrap();
+        asset_loader_registry.add_loader(Arc::new(asset_loader));
+    }
+
+    pub fn get_asset_loader<D: 'static>(&self, path: &Path) -> Result<Arc<dyn AssetLoader<D>>> {
+        let extension = path.extension().and_then(|v| v.to_str()).unwrap_or("").to_string();
+        let registered_extensions = self.get_asset_loader_extensions::<D>();
+
+        // Get loader registered for extension of the asset
+        self.asset_loaders.get::<AssetLoaderRegistry<D>>()
+            .and_then(|v| v.get_loader(&extension))
+            .ok_or(Error::new(EngineError::AssetLoaderNotRegistered(get_type_name::<D>(), extension, registered_extensions)))
+    }
+
+    pub(crate) fn get_default_asset_loader<D: 'static>(&self) -> Result<Arc<dyn AssetLoader<D>>> {
+        self.asset_loaders.get::<AssetLoaderRegistry<D>>()
+            .and_then(|v| v.get_default_loader())
+            .ok_or(Error::new(EngineError::AssetLoaderNotRegistered(get_type_name::<D>(), String::new(), Vec::<String>::new())))
+    }
+
+    pub fn get_asset_loader_extensions<D: 'static>(&self) -> Vec<String> {
+        match self.asset_loaders.get::<AssetLoaderRegistry<D>>() {
+            Some(v) => v.get_extensions(),
+            None => Vec::<String>::new(),
+        }
+    }
+
+    // Check if path to asset is correct (exists on disk or in mounted archive and has format with registered loader)
+    pub fn validate_asset_path<D: 'static>(&self, path: &Path) -> Result<()> {
+        (path.exists() || is_asset_archived(path)).ok_or(Error::new(EngineError::InvalidAssetPath(path.display().to_string())))?;
+        self.get_asset_loader::<D>(path)?;
+
+        Ok(())
+    }
+
     // --- Get ---
 
     pub fn get_resource_handle<T>(&self, name: &str) -> Result<T::Handle> 
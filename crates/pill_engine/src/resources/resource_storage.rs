@@ -1,14 +1,16 @@
-use crate::{resources::Resource, config::RESOURCE_VERSION_LIMIT};
+use crate::{resources::{ Resource, LoadState }, config::RESOURCE_VERSION_LIMIT};
 
 use pill_core::{ PillSlotMap, PillSlotMapKey, PillTwinMap };
 
-use std::collections::HashMap;
+use std::collections::{ HashMap, HashSet };
 
 
 pub struct ResourceStorage<T: Resource> {
     pub data: PillSlotMap<T::Handle, Option<T>>, 
     pub mapping: PillTwinMap<String, T::Handle>,
     pub max_resource_count: usize,
+    pub(crate) load_states: HashMap<T::Handle, LoadState>, // Only resources that are not loaded yet or failed to load are stored
+    pub(crate) used_resources: HashSet<T::Handle>, // Resources that were in use at least once, only those can be unloaded automatically
 }
 
 impl<T: Resource> ResourceStorage<T> {
@@ -17,6 +19,8 @@ impl<T: Resource> ResourceStorage<T> {
             data: PillSlotMap::<T::Handle, Option<T>>::with_capacity_and_key_and_version_limit(max_resource_count, RESOURCE_VERSION_LIMIT as u32).unwrap(),
             mapping: PillTwinMap::<String, T::Handle>::new(),
             max_resource_count,
+            load_states: HashMap::<T::Handle, LoadState>::new(),
+            used_resources: HashSet::<T::Handle>::new(),
         }
     }
 }
\ No newline at end of file
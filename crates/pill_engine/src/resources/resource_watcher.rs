@@ -0,0 +1,64 @@
+use pill_core::PillStyle;
+
+use std::{
+    collections::HashSet,
+    path::{ Path, PathBuf },
+    sync::mpsc::{ channel, Receiver },
+};
+use anyhow::{ Result, Context, Error };
+use log::{ debug };
+use notify::{ Watcher, RecursiveMode, EventKind };
+
+// --- Resource watcher ---
+
+// Watches resource folder of the game and collects paths of files that changed on disk
+pub struct ResourceWatcher {
+    watcher: notify::RecommendedWatcher, // Has to be kept alive, watching stops when it is dropped
+    receiver: Receiver<notify::Result<notify::Event>>,
+}
+
+impl ResourceWatcher {
+    pub fn new(path: &Path) -> Result<Self> {
+        let (sender, receiver) = channel();
+
+        let mut watcher = notify::recommended_watcher(move |event| {
+            let _ = sender.send(event);
+        }).context(format!("Cannot create {}", "ResourceWatcher".sobj_style()))?;
+
+        watcher.watch(path, RecursiveMode::Recursive)
+            .context(format!("Cannot watch folder {}", path.display()))?;
+
+        Ok(Self {
+            watcher,
+            receiver,
+        })
+    }
+
+    // Returns canonicalized paths of files that were created or modified since last call
+    pub fn get_changed_paths(&self) -> HashSet<PathBuf> {
+        let mut changed_paths = HashSet::<PathBuf>::new();
+
+        for event in self.receiver.try_iter() {
+            let event = match event {
+                Ok(event) => event,
+                Err(error) => {
+                    debug!("{} error: {}", "ResourceWatcher".sobj_style(), error);
+                    continue;
+                }
+            };
+
+            // Editors often save files by creating new file and renaming it so treat creation as modification too
+            if !matches!(event.kind, EventKind::Modify(_) | EventKind::Create(_)) {
+                continue;
+            }
+
+            for path in event.paths {
+                if let Ok(path) = path.canonicalize() {
+                    changed_paths.insert(path);
+                }
+            }
+        }
+
+        changed_paths
+    }
+}
@@ -0,0 +1,391 @@
+use crate::{
+    engine::Engine,
+    graphics::{ RendererShaderHandle },
+    resources::{ ResourceStorage, Resource, ResourceLoadType, ResourceManager, Material, MaterialHandle, TextureType, prepare_asset_load, shader_compiler, MAX_MATERIAL_TEXTURE_SLOTS },
+    config::*,
+};
+
+use pill_core::{ EngineError, PillSlotMapKey, PillTypeMapKey, PillStyle, get_type_name };
+
+use std::{ collections::BTreeMap, path::PathBuf };
+use anyhow::{ Result, Context, Error };
+
+// Bind groups used by material shaders
+const TEXTURE_BIND_GROUP: u32 = 0;
+const PARAMETER_BIND_GROUP: u32 = 1;
+const CAMERA_BIND_GROUP: u32 = 2; // Set by renderer
+
+pill_core::define_new_pill_slotmap_key! {
+    pub struct ShaderHandle;
+}
+
+// --- Shader source ---
+
+// Code of single shader stage
+pub enum ShaderSource {
+    SpirV(Vec<u8>),
+    Wgsl(String), // Compiled at runtime, so it can be hot reloaded
+}
+
+impl ShaderSource {
+    // Source name is path of the file or name of the shader, it is used in error messages
+    fn parse(&self, source_name: &str) -> Result<naga::Module> {
+        let parse_result = match self {
+            ShaderSource::SpirV(bytes) => shader_compiler::parse_spirv(bytes, source_name),
+            ShaderSource::Wgsl(code) => shader_compiler::parse_wgsl(code, source_name),
+        };
+
+        match parse_result {
+            Ok((module, _)) => Ok(module),
+            Err(error) => Err(Error::new(EngineError::InvalidShaderData(format!("{:#}", error)))),
+        }
+    }
+}
+
+// --- Shader layout ---
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShaderParameterType {
+    Scalar, // float
+    Bool, // bool, int or uint
+    Color, // vec3
+}
+
+#[derive(Clone, Debug)]
+pub struct ShaderTextureSlot {
+    pub name: String,
+    pub texture_type: TextureType,
+    pub texture_binding: u32,
+    pub sampler_binding: u32,
+}
+
+#[derive(Clone, Debug)]
+pub struct ShaderParameterSlot {
+    pub name: String,
+    pub parameter_type: ShaderParameterType,
+    pub offset: u32, // Offset of the value in parameter buffer
+}
+
+// Material slots reflected from resources declared by shader
+//
+// Bind group 0 holds textures, each followed by its sampler (named <texture>_sampler or bound to next binding)
+// Bind group 1 holds uniform block with parameters at binding 0
+// Bind group 2 holds camera and is set by renderer
+//
+// Slot names are variable names in PascalCase without "_texture" suffix (e.g. "base_color_texture" -> "BaseColor", "normal_scale" -> "NormalScale")
+// Texture slots with "normal" in their name are of Normal type, other 2D textures are of Color type
+#[derive(Clone, Debug)]
+pub struct ShaderLayout {
+    pub texture_slots: Vec<ShaderTextureSlot>, // Sorted by texture binding
+    pub parameter_slots: Vec<ShaderParameterSlot>,
+    pub parameter_buffer_size: u32, // Zero if shader has no parameters
+}
+
+impl ShaderLayout {
+    fn from_modules(modules: &[naga::Module]) -> Result<Self> {
+        let mut textures = BTreeMap::<u32, (String, naga::ImageDimension)>::new();
+        let mut samplers = BTreeMap::<u32, String>::new();
+        let mut parameters: Option<(Vec<ShaderParameterSlot>, u32)> = None;
+
+        // Collect resources from all shader stages
+        for module in modules {
+            for (_, variable) in module.global_variables.iter() {
+                let binding = match &variable.binding {
+                    Some(v) => v,
+                    None => continue,
+                };
+                let variable_name = variable.name.clone().unwrap_or_default();
+
+                match (binding.group, &module.types[variable.ty].inner) {
+                    (TEXTURE_BIND_GROUP, naga::TypeInner::Image { dim, arrayed: false, class: naga::ImageClass::Sampled { kind: naga::ScalarKind::Float, multi: false } }) => {
+                        textures.insert(binding.binding, (variable_name, *dim));
+                    },
+                    (TEXTURE_BIND_GROUP, naga::TypeInner::Sampler { comparison: false }) => {
+                        samplers.insert(binding.binding, variable_name);
+                    },
+                    (PARAMETER_BIND_GROUP, _) if binding.binding == 0 && variable.space == naga::AddressSpace::Uniform => {
+                        if parameters.is_none() {
+                            parameters = Some(get_parameter_slots(module, variable.ty)?);
+                        }
+                    },
+                    (CAMERA_BIND_GROUP, _) => {},
+                    _ => {
+                        let message = format!("Resource {} in group {} at binding {} is not supported", variable_name, binding.group, binding.binding);
+                        return Err(Error::new(EngineError::InvalidShaderData(message)));
+                    },
+                }
+            }
+        }
+
+        // Create texture slots
+        let mut texture_slots = Vec::<ShaderTextureSlot>::new();
+        for (texture_binding, (variable_name, dimension)) in textures.iter() {
+            let base_name = variable_name.strip_suffix("_texture").unwrap_or(variable_name);
+
+            // Find sampler of the texture
+            let sampler_name = format!("{}_sampler", base_name);
+            let sampler_binding = samplers.iter().find(|(_, name)| **name == sampler_name).map(|(binding, _)| *binding)
+                .or_else(|| samplers.contains_key(&(texture_binding + 1)).then(|| texture_binding + 1))
+                .ok_or(Error::new(EngineError::InvalidShaderData(format!("Texture {} has no sampler", variable_name))))?;
+
+            let texture_type = match dimension {
+                naga::ImageDimension::D2 if base_name.to_lowercase().contains("normal") => TextureType::Normal,
+                naga::ImageDimension::D2 => TextureType::Color,
+                naga::ImageDimension::Cube => TextureType::Cubemap,
+                _ => return Err(Error::new(EngineError::InvalidShaderData(format!("Texture {} has unsupported dimension {:?}", variable_name, dimension)))),
+            };
+
+            texture_slots.push(ShaderTextureSlot {
+                name: get_slot_name(base_name),
+                texture_type,
+                texture_binding: *texture_binding,
+                sampler_binding,
+            });
+        }
+
+        if texture_slots.len() > MAX_MATERIAL_TEXTURE_SLOTS {
+            let message = format!("Shader uses {} textures, maximum is {}", texture_slots.len(), MAX_MATERIAL_TEXTURE_SLOTS);
+            return Err(Error::new(EngineError::InvalidShaderData(message)));
+        }
+
+        let (parameter_slots, parameter_buffer_size) = parameters.unwrap_or((Vec::<ShaderParameterSlot>::new(), 0));
+
+        Ok(Self {
+            texture_slots,
+            parameter_slots,
+            parameter_buffer_size,
+        })
+    }
+
+    pub fn get_texture_slot(&self, slot_name: &str) -> Option<&ShaderTextureSlot> {
+        self.texture_slots.iter().find(|v| v.name == slot_name)
+    }
+
+    pub fn get_parameter_slot(&self, slot_name: &str) -> Option<&ShaderParameterSlot> {
+        self.parameter_slots.iter().find(|v| v.name == slot_name)
+    }
+}
+
+// Returns parameter slots and size of parameter buffer from uniform block type
+fn get_parameter_slots(module: &naga::Module, block_type: naga::Handle<naga::Type>) -> Result<(Vec<ShaderParameterSlot>, u32)> {
+    let (mut members, mut span) = match &module.types[block_type].inner {
+        naga::TypeInner::Struct { members, span } => (members, *span),
+        _ => return Err(Error::new(EngineError::InvalidShaderData("Parameter uniform is not a block".to_string()))),
+    };
+
+    // Some compilers wrap uniform block in another struct
+    while members.len() == 1 {
+        match &module.types[members[0].ty].inner {
+            naga::TypeInner::Struct { members: inner_members, span: inner_span } => {
+                members = inner_members;
+                span = *inner_span;
+            },
+            _ => break,
+        }
+    }
+
+    let mut parameter_slots = Vec::<ShaderParameterSlot>::new();
+    for member in members.iter() {
+        let member_name = member.name.clone().unwrap_or_default();
+        let parameter_type = match &module.types[member.ty].inner {
+            naga::TypeInner::Scalar(naga::Scalar { kind: naga::ScalarKind::Float, width: 4 }) => ShaderParameterType::Scalar,
+            naga::TypeInner::Scalar(naga::Scalar { kind: naga::ScalarKind::Bool | naga::ScalarKind::Sint | naga::ScalarKind::Uint, .. }) => ShaderParameterType::Bool,
+            naga::TypeInner::Vector { size: naga::VectorSize::Tri, scalar: naga::Scalar { kind: naga::ScalarKind::Float, width: 4 } } => ShaderParameterType::Color,
+            _ => return Err(Error::new(EngineError::InvalidShaderData(format!("Parameter {} has unsupported type", member_name)))),
+        };
+
+        parameter_slots.push(ShaderParameterSlot {
+            name: get_slot_name(&member_name),
+            parameter_type,
+            offset: member.offset,
+        });
+    }
+
+    // Uniform buffer size has to be multiple of 16 bytes
+    let parameter_buffer_size = (span + 15) / 16 * 16;
+
+    Ok((parameter_slots, parameter_buffer_size))
+}
+
+// Converts variable name to slot name (e.g. "environment_map" -> "EnvironmentMap")
+fn get_slot_name(variable_name: &str) -> String {
+    variable_name.split('_')
+        .filter(|v| !v.is_empty())
+        .map(|v| {
+            let mut characters = v.chars();
+            match characters.next() {
+                Some(first) => first.to_uppercase().chain(characters).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+// --- Shader data ---
+
+pub struct ShaderData {
+    pub vertex_source: ShaderSource,
+    pub fragment_source: ShaderSource,
+    pub layout: ShaderLayout,
+}
+
+impl ShaderData {
+    // Sources are validated, errors are reported with given source names (and lines for WGSL)
+    pub fn new(vertex_source: ShaderSource, vertex_source_name: &str, fragment_source: ShaderSource, fragment_source_name: &str) -> Result<Self> {
+        // Reflect material slots from both stages
+        let modules = [vertex_source.parse(vertex_source_name)?, fragment_source.parse(fragment_source_name)?];
+        let layout = ShaderLayout::from_modules(&modules)?;
+
+        Ok(Self {
+            vertex_source,
+            fragment_source,
+            layout,
+        })
+    }
+}
+
+// --- Shader ---
+
+#[readonly::make]
+pub struct Shader {
+    #[readonly]
+    pub name: String,
+    #[readonly]
+    pub vertex_load_type: ResourceLoadType,
+    #[readonly]
+    pub fragment_load_type: ResourceLoadType,
+    pub(crate) layout: Option<ShaderLayout>,
+    pub(crate) renderer_resource_handle: Option<RendererShaderHandle>,
+}
+
+impl Shader {
+    pub fn new(name: &str, vertex_load_type: ResourceLoadType, fragment_load_type: ResourceLoadType) -> Self {
+        Self {
+            name: name.to_string(),
+            vertex_load_type,
+            fragment_load_type,
+            layout: None,
+            renderer_resource_handle: None,
+        }
+    }
+
+    // Layout is reflected when shader is added
+    pub fn get_layout(&self) -> Option<&ShaderLayout> {
+        self.layout.as_ref()
+    }
+}
+
+fn load_shader_data(resource_manager: &ResourceManager, shader: &Shader) -> Result<ShaderData> {
+    // Load data with asset loader registered for its format
+    let vertex_source = prepare_asset_load::<ShaderSource>(resource_manager, &shader.vertex_load_type)?()?;
+    let fragment_source = prepare_asset_load::<ShaderSource>(resource_manager, &shader.fragment_load_type)?()?;
+
+    // Compile and validate both stages
+    let vertex_source_name = get_source_name(&shader.vertex_load_type, &shader.name, "vertex");
+    let fragment_source_name = get_source_name(&shader.fragment_load_type, &shader.name, "fragment");
+    ShaderData::new(vertex_source, &vertex_source_name, fragment_source, &fragment_source_name)
+}
+
+// Returns path of the shader stage file or name of the shader and its stage if it is not loaded from file
+fn get_source_name(load_type: &ResourceLoadType, shader_name: &str, stage_name: &str) -> String {
+    match load_type {
+        ResourceLoadType::Path(path) | ResourceLoadType::Archive(path) => path.display().to_string(),
+        ResourceLoadType::Bytes(_) | ResourceLoadType::Generated => format!("{} ({} stage)", shader_name, stage_name),
+    }
+}
+
+// Returns handles of materials that use this shader
+fn get_dependent_material_handles<H: PillSlotMapKey>(engine: &Engine, shader_handle: H) -> Vec<MaterialHandle> {
+    let resource_storage = engine.resource_manager.get_resource_storage::<Material>().expect("Critical: Resource not registered");
+    resource_storage.data.iter()
+        .filter(|(_, material)| material.as_ref().expect("Critical: Resource is None").shader_handle.data() == shader_handle.data())
+        .map(|(material_handle, _)| material_handle)
+        .collect()
+}
+
+impl PillTypeMapKey for Shader {
+    type Storage = ResourceStorage<Shader>;
+}
+
+impl Resource for Shader {
+    type Handle = ShaderHandle;
+
+    fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn initialize(&mut self, engine: &mut Engine) -> Result<()> {
+        let error_message = format!("Initializing {} {} {} failed", "Resource".gobj_style(), get_type_name::<Self>().sobj_style(), self.name.name_style());
+
+        // Load shader data and reflect its layout
+        let shader_data = load_shader_data(&engine.resource_manager, self).context(error_message.clone())?;
+
+        // Create renderer shader resource
+        let renderer_resource_handle = engine.renderer.create_shader(&self.name, &shader_data).context(error_message)?;
+        self.renderer_resource_handle = Some(renderer_resource_handle);
+        self.layout = Some(shader_data.layout);
+
+        Ok(())
+    }
+
+    fn get_source_paths(&self) -> Vec<PathBuf> {
+        [&self.vertex_load_type, &self.fragment_load_type].iter()
+            .filter_map(|load_type| match load_type {
+                ResourceLoadType::Path(path) => Some(path.clone()),
+                ResourceLoadType::Bytes(_) | ResourceLoadType::Archive(_) | ResourceLoadType::Generated => None,
+            })
+            .collect()
+    }
+
+    fn reload<H: PillSlotMapKey>(&mut self, engine: &mut Engine, self_handle: H) -> Result<()> {
+        let error_message = format!("Reloading {} {} {} failed", "Resource".gobj_style(), get_type_name::<Self>().sobj_style(), self.name.name_style());
+
+        // Compile shader again (old one is kept if compilation fails)
+        let shader_data = load_shader_data(&engine.resource_manager, self).context(error_message.clone())?;
+
+        // Replace renderer shader, its pipelines are recreated in place so materials keep their pipeline handles
+        let renderer_resource_handle = self.renderer_resource_handle.expect("Critical: No renderer resource handle");
+        engine.renderer.update_shader(renderer_resource_handle, &shader_data).context(error_message.clone())?;
+
+        // Update slots of materials that use this shader and recreate their renderer bindings
+        let resource_storage = engine.resource_manager.get_resource_storage_mut::<Material>().expect("Critical: Resource not registered");
+        for material_slot in resource_storage.data.iter_mut() {
+            let material = material_slot.1.as_mut().expect("Critical: Resource is None");
+            if material.shader_handle.data() != self_handle.data() {
+                continue;
+            }
+
+            material.update_shader_layout(&shader_data.layout);
+            if let Some(v) = material.renderer_resource_handle {
+                engine.renderer.update_material_textures(v, &material.textures).context(error_message.clone())?;
+                engine.renderer.update_material_parameters(v, &material.parameters).context(error_message.clone())?;
+            }
+        }
+
+        self.layout = Some(shader_data.layout);
+
+        Ok(())
+    }
+
+    fn get_reference_count<H: PillSlotMapKey>(&self, engine: &Engine, self_handle: H) -> Result<usize> {
+        Ok(get_dependent_material_handles(engine, self_handle).len())
+    }
+
+    fn remove_dependent_resources<H: PillSlotMapKey>(&mut self, engine: &mut Engine, self_handle: H) -> Result<()> {
+        // Remove materials that use this shader
+        for material_handle in get_dependent_material_handles(engine, self_handle) {
+            engine.remove_resource::<Material>(&material_handle)?;
+        }
+
+        Ok(())
+    }
+
+    fn destroy<H: PillSlotMapKey>(&mut self, engine: &mut Engine, self_handle: H) -> Result<()> {
+        // Destroy renderer resource (pipelines created for this shader are destroyed with it)
+        if let Some(v) = self.renderer_resource_handle {
+            engine.renderer.destroy_shader(v).unwrap();
+        }
+
+        Ok(())
+    }
+}
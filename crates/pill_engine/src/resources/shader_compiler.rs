@@ -0,0 +1,28 @@
+// Shader compilation done in process with naga
+// This module is also used by build script to compile shaders of the engine, so it can only depend on naga and anyhow
+
+use anyhow::{ Result, Error };
+
+// Parses and validates WGSL shader, returned error contains file, line and column of the problem
+pub fn parse_wgsl(source: &str, path: &str) -> Result<(naga::Module, naga::valid::ModuleInfo)> {
+    let module = naga::front::wgsl::parse_str(source)
+        .map_err(|error| Error::msg(error.emit_to_string_with_path(source, path)))?;
+    let module_info = validate(&module)
+        .map_err(|error| Error::msg(error.emit_to_string_with_path(source, path)))?;
+
+    Ok((module, module_info))
+}
+
+// Parses and validates SPIR-V shader (there is no source text so error only contains path)
+pub fn parse_spirv(bytes: &[u8], path: &str) -> Result<(naga::Module, naga::valid::ModuleInfo)> {
+    let module = naga::front::spv::parse_u8_slice(bytes, &naga::front::spv::Options::default())
+        .map_err(|error| Error::msg(format!("{}: {}", path, error)))?;
+    let module_info = validate(&module)
+        .map_err(|error| Error::new(error.into_inner()).context(format!("{}: Invalid shader", path)))?;
+
+    Ok((module, module_info))
+}
+
+fn validate(module: &naga::Module) -> Result<naga::valid::ModuleInfo, naga::WithSpan<naga::valid::ValidationError>> {
+    naga::valid::Validator::new(naga::valid::ValidationFlags::all(), naga::valid::Capabilities::default()).validate(module)
+}
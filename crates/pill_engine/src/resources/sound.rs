@@ -1,7 +1,7 @@
 use crate::{
     engine::Engine,
     graphics::{ RendererTextureHandle }, 
-    resources::{ ResourceStorage, Resource, ResourceLoadType, Material },
+    resources::{ ResourceStorage, Resource, ResourceLoadType, ResourceManager, ResourceData, ResourceDataLoader, Material, prepare_asset_load, read_asset },
     ecs::{ DeferredUpdateManagerPointer, AudioSourceComponent, SoundType, AudioManagerComponent },
     config::*,
 };
@@ -27,15 +27,15 @@ pub struct Sound {
     #[readonly]
     pub name: String,
     #[readonly]
-    pub path: PathBuf,
+    pub load_type: ResourceLoadType,
     pub(crate) sound_data: Option<SoundData>
 }
 
 impl Sound {
-    pub fn new(name: &str, path: PathBuf) -> Self {
+    pub fn new(name: &str, resource_load_type: ResourceLoadType) -> Self {
         Self {
             name: name.to_string(),
-            path, 
+            load_type: resource_load_type, 
             sound_data: None
         }
     }  
@@ -51,11 +51,9 @@ impl Resource for Sound {
     fn initialize(&mut self, engine: &mut Engine) -> Result<()> {
         let error_message = format!("Initializing {} {} failed", "Resource".gobj_style(), get_type_name::<Self>().sobj_style());    
         
-        // Check if path to asset is correct
-        pill_core::validate_asset_path(&self.path, &["mp3", "wav"]).context(error_message.clone())?;
-
-        // Create sound data
-        let sound_data = SoundData::new(&self.path).context(error_message.clone())?;
+        // Create sound data with asset loader registered for its format
+        let sound_data = prepare_asset_load::<SoundData>(&engine.resource_manager, &self.load_type)
+            .and_then(|asset_load| asset_load()).context(error_message.clone())?;
         self.sound_data = Some(sound_data);
 
         Ok(())
@@ -65,6 +63,54 @@ impl Resource for Sound {
         self.name.clone()
     }
 
+    fn get_data_loader(&self, resource_manager: &ResourceManager) -> Option<ResourceDataLoader> {
+        // Asset loader is chosen here since resource manager is not available on worker thread
+        let asset_load = prepare_asset_load::<SoundData>(resource_manager, &self.load_type);
+        Some(Box::new(move || Ok(Box::new(asset_load?()?) as ResourceData)))
+    }
+
+    fn initialize_with_data<H: PillSlotMapKey>(&mut self, engine: &mut Engine, self_handle: H, data: ResourceData) -> Result<()> {
+        // Set loaded sound data
+        let sound_data = data.downcast::<SoundData>().expect("Critical: Wrong resource data type");
+        self.sound_data = Some(*sound_data);
+
+        Ok(())
+    }
+
+    fn get_source_paths(&self) -> Vec<PathBuf> {
+        match &self.load_type {
+            ResourceLoadType::Path(path) => vec![path.clone()],
+            ResourceLoadType::Bytes(_) | ResourceLoadType::Archive(_) | ResourceLoadType::Generated => Vec::new(),
+        }
+    }
+
+    fn reload<H: PillSlotMapKey>(&mut self, engine: &mut Engine, self_handle: H) -> Result<()> {
+        let error_message = format!("Reloading {} {} {} failed", "Resource".gobj_style(), get_type_name::<Self>().sobj_style(), self.name.name_style());
+
+        // Replace sound data, audio sources read it each time sound is played
+        let sound_data = prepare_asset_load::<SoundData>(&engine.resource_manager, &self.load_type)
+            .and_then(|asset_load| asset_load()).context(error_message)?;
+        self.sound_data = Some(sound_data);
+
+        Ok(())
+    }
+
+    fn get_reference_count<H: PillSlotMapKey>(&self, engine: &Engine, self_handle: H) -> Result<usize> {
+        // Count components in all scenes that use this resource
+        let mut reference_count = 0;
+        for (scene_handle, scene) in engine.scene_manager.scenes.iter() {
+            for (entity_handle, component) in scene.get_one_component_iterator::<AudioSourceComponent>()? {
+                if let Some(handle) = component.sound_handle {
+                    if handle.data() == self_handle.data() {
+                        reference_count += 1;
+                    }
+                }
+            }
+        }
+
+        Ok(reference_count)
+    }
+
     fn destroy<H: PillSlotMapKey>(&mut self, engine: &mut Engine, self_handle: H) -> Result<()> {
         // Find audio source components that use this sound and update them
         for (scene_handle, scene) in engine.scene_manager.scenes.iter_mut() {
@@ -89,33 +135,23 @@ pub struct SoundData {
 
 impl SoundData {
     pub fn new(path: &PathBuf) -> Result<Self> {
-        // Open sound file
-        let mut sound_file = match File::open(path) {
-            Err(err) => return Err(Error::new(EngineError::InvalidAssetPath(path.clone().into_os_string().into_string().unwrap()))),
-            file => file?
-        };
-
-        // Read bytes to vector
-        let mut sound_data = Vec::new();
-        sound_file.read_to_end(&mut sound_data).unwrap();
-
-        // Create SoundData
-        let sound_data = SoundData {
-            source_buffer: sound_data
-        };
-
-        Ok(sound_data)
+        // Read bytes from disk or mounted archive
+        let sound_data = read_asset(path)?;
+
+        Self::from_bytes(sound_data)
     }
 
-    pub fn get_source_sound(&self) -> Decoder<Cursor<Vec<u8>>> {
-        let mut sound_source = Vec::<u8>::new();
+    // Creates sound data from encoded sound (it is decoded each time sound is played, here it is only checked that it can be)
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self> {
+        Decoder::new(Cursor::new(bytes.clone())).map_err(|error| Error::new(EngineError::InvalidSoundData(error.to_string())))?;
 
-        // Read bytes from the buffer
-        for buffer in self.source_buffer.iter() {
-            sound_source.push(buffer.clone());
-        }
+        Ok(SoundData {
+            source_buffer: bytes
+        })
+    }
 
-        // Return decoded bytes as the sound, which can be played
-        Decoder::new(Cursor::new(sound_source)).unwrap()
+    // Returns decoder of the sound, which can be played
+    pub fn get_source_sound(&self) -> Result<Decoder<Cursor<Vec<u8>>>> {
+        Decoder::new(Cursor::new(self.source_buffer.clone())).map_err(|error| Error::new(EngineError::InvalidSoundData(error.to_string())))
     }
 }
\ No newline at end of file
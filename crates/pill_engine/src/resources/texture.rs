@@ -1,8 +1,8 @@
 use crate::{
     engine::Engine,
     graphics::{ RendererTextureHandle }, 
-    resources::{ ResourceStorage, Resource, ResourceLoadType, Material },
-    ecs::{ DeferredUpdateManagerPointer },
+    resources::{ ResourceStorage, Resource, ResourceLoadType, ResourceManager, ResourceData, ResourceDataLoader, Material, MaterialHandle, TextureData, CubemapImage, AssetLoadFunction, prepare_asset_load },
+    ecs::{ DeferredUpdateManagerPointer, CameraComponent },
     config::*,
 };
 
@@ -20,6 +20,93 @@ pill_core::define_new_pill_slotmap_key! {
 pub enum TextureType {
     Color,
     Normal,
+    Cubemap,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextureFilter {
+    Nearest,
+    Linear,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextureWrapMode {
+    Repeat,
+    MirrorRepeat,
+    ClampToEdge,
+}
+
+// Color textures are usually stored in sRGB, data textures (normal maps, masks, etc.) should be sampled as linear
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextureColorSpace {
+    Srgb,
+    Linear,
+}
+
+// --- Texture settings ---
+
+#[derive(Clone, Copy, Debug)]
+pub struct TextureSettings {
+    pub filter: TextureFilter, // Used for magnification and minification
+    pub mipmap_filter: TextureFilter, // Used for blending between mip levels
+    pub wrap_mode: TextureWrapMode,
+    pub anisotropy: u16, // Maximal anisotropy from 1 to 16, only used if all filters are linear
+    pub generate_mipmaps: bool,
+    pub color_space: TextureColorSpace,
+}
+
+impl TextureSettings {
+    pub fn new(texture_type: TextureType) -> Self {
+        let color_space = match texture_type {
+            TextureType::Color | TextureType::Cubemap => TextureColorSpace::Srgb,
+            TextureType::Normal => TextureColorSpace::Linear,
+        };
+
+        // Cubemaps are sampled by direction so their edges should not blend with opposite side of the face
+        let wrap_mode = match texture_type {
+            TextureType::Cubemap => TextureWrapMode::ClampToEdge,
+            TextureType::Color | TextureType::Normal => TextureWrapMode::Repeat,
+        };
+
+        Self {
+            filter: TextureFilter::Linear,
+            mipmap_filter: TextureFilter::Linear,
+            wrap_mode,
+            anisotropy: 1,
+            generate_mipmaps: true, // Mip levels of cubemaps are used as blurred environment for rough materials
+            color_space,
+        }
+    }
+
+    pub fn filter(mut self, filter: TextureFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    pub fn mipmap_filter(mut self, mipmap_filter: TextureFilter) -> Self {
+        self.mipmap_filter = mipmap_filter;
+        self
+    }
+
+    pub fn wrap_mode(mut self, wrap_mode: TextureWrapMode) -> Self {
+        self.wrap_mode = wrap_mode;
+        self
+    }
+
+    pub fn anisotropy(mut self, anisotropy: u16) -> Self {
+        self.anisotropy = anisotropy.clamp(1, 16);
+        self
+    }
+
+    pub fn generate_mipmaps(mut self, generate_mipmaps: bool) -> Self {
+        self.generate_mipmaps = generate_mipmaps;
+        self
+    }
+
+    pub fn color_space(mut self, color_space: TextureColorSpace) -> Self {
+        self.color_space = color_space;
+        self
+    }
 }
 
 #[readonly::make]
@@ -30,6 +117,12 @@ pub struct Texture {
     pub load_type: ResourceLoadType,
     #[readonly]
     pub texture_type: TextureType,
+    #[readonly]
+    pub settings: TextureSettings,
+    #[readonly]
+    pub face_load_types: Vec<ResourceLoadType>, // Only set for cubemaps created from six images
+    #[readonly]
+    pub render_target_size: Option<(u32, u32)>, // Only set for textures that cameras render to
     pub(crate) renderer_resource_handle: Option<RendererTextureHandle>,
 }
 
@@ -39,9 +132,115 @@ impl Texture {
             name: name.to_string(),
             load_type: resource_load_type,
             texture_type,
+            settings: TextureSettings::new(texture_type),
+            face_load_types: Vec::<ResourceLoadType>::new(),
+            render_target_size: None,
             renderer_resource_handle: None,
         }
     }
+
+    // Creates cubemap from six images in order +X, -X, +Y, -Y, +Z, -Z (cubemap created with new is loaded from single equirectangular image)
+    pub fn new_cubemap(name: &str, face_load_types: [ResourceLoadType; 6]) -> Self {
+        let mut texture = Self::new(name, TextureType::Cubemap, face_load_types[0].clone());
+        texture.face_load_types = face_load_types.to_vec();
+        texture
+    }
+
+    // Creates color texture that cameras can render to (e.g. mirrors, security monitors, minimaps), it is stored in HDR so it should be sampled as linear
+    pub fn new_render_target(name: &str, width: u32, height: u32) -> Self {
+        let mut texture = Self::new(name, TextureType::Color, ResourceLoadType::Generated);
+        texture.settings = TextureSettings::new(TextureType::Color)
+            .wrap_mode(TextureWrapMode::ClampToEdge)
+            .generate_mipmaps(false)
+            .color_space(TextureColorSpace::Linear);
+        texture.render_target_size = Some((width.max(1), height.max(1)));
+        texture
+    }
+
+    // Settings are used when renderer texture is created so they have to be set before texture is added
+    pub fn with_settings(mut self, settings: TextureSettings) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    // Creates renderer texture from texture data and points materials using this texture to it
+    fn set_texture_data<H: PillSlotMapKey>(&mut self, engine: &mut Engine, self_handle: H, texture_data: &TextureData) -> Result<()> {
+        // Create new renderer texture resource
+        let renderer_resource_handle = engine.renderer.create_texture(&self.name, texture_data, &self.settings)?;
+        let old_renderer_resource_handle = self.renderer_resource_handle.replace(renderer_resource_handle);
+
+        // Find materials that use this texture and point them to new renderer texture
+        let resource_storage = engine.resource_manager.get_resource_storage_mut::<Material>().expect("Critical: Resource not registered");
+        for material_slot in resource_storage.data.iter_mut() {
+            let material = material_slot.1.as_mut().expect("Critical: Resource is None");
+
+            // Update texture slots
+            let mut material_updated = false;
+            for texture_slot in material.get_textures().data.iter_mut() {
+                if let Some(texture_handle) = texture_slot.1.texture_handle {
+                    // If material texture has handle to this texture
+                    if texture_handle.data() == self_handle.data() {
+                        texture_slot.1.renderer_texture_handle = Some(renderer_resource_handle);
+                        material_updated = true;
+                    }
+                }
+            }
+
+            if material_updated {
+                engine.renderer.update_material_textures(material.renderer_resource_handle.unwrap(), &material.textures)?;
+            }
+        }
+
+        // Destroy old renderer resource (it is no longer used by any material)
+        if let Some(v) = old_renderer_resource_handle {
+            engine.renderer.destroy_texture(v)?;
+        }
+
+        Ok(())
+    }
+}
+
+// Returns handles of materials that have this texture set in any of their slots
+fn get_dependent_material_handles<H: PillSlotMapKey>(engine: &Engine, texture_handle: H) -> Vec<MaterialHandle> {
+    let resource_storage = engine.resource_manager.get_resource_storage::<Material>().expect("Critical: Resource not registered");
+    resource_storage.data.iter()
+        .filter(|(_, material)| {
+            let material = material.as_ref().expect("Critical: Resource is None");
+            material.textures.data.values().any(|texture_slot| match texture_slot.texture_handle {
+                Some(v) => v.data() == texture_handle.data(),
+                None => false,
+            })
+        })
+        .map(|(material_handle, _)| material_handle)
+        .collect()
+}
+
+// Prepares loading of texture data, cubemap data is converted on load so it can run on worker thread
+fn prepare_texture_data_load(resource_manager: &ResourceManager, texture: &Texture) -> Result<AssetLoadFunction<TextureData>> {
+    // Load data with asset loader registered for its format
+    if !texture.face_load_types.is_empty() {
+        let face_asset_loads = texture.face_load_types.iter()
+            .map(|v| prepare_asset_load::<TextureData>(resource_manager, v))
+            .collect::<Result<Vec<AssetLoadFunction<TextureData>>>>()?;
+        return Ok(Box::new(move || {
+            let faces = face_asset_loads.into_iter().map(|v| v()).collect::<Result<Vec<TextureData>>>()?;
+            Ok(TextureData::Cubemap(CubemapImage::from_faces(&faces)?))
+        }));
+    }
+
+    let asset_load = prepare_asset_load::<TextureData>(resource_manager, &texture.load_type)?;
+    match texture.texture_type {
+        TextureType::Cubemap => Ok(Box::new(move || match asset_load()? {
+            TextureData::Cubemap(v) => Ok(TextureData::Cubemap(v)),
+            v => Ok(TextureData::Cubemap(CubemapImage::from_equirectangular(&v)?)),
+        })),
+        TextureType::Color | TextureType::Normal => Ok(asset_load),
+    }
+}
+
+fn load_texture_data(resource_manager: &ResourceManager, texture: &Texture) -> Result<TextureData> {
+    let texture_data_load = prepare_texture_data_load(resource_manager, texture)?;
+    texture_data_load()
 }
 
 impl PillTypeMapKey for Texture {
@@ -58,28 +257,93 @@ impl Resource for Texture {
     fn initialize(&mut self, engine: &mut Engine) -> Result<()> {
         let error_message = format!("Initializing {} {} failed", "Resource".gobj_style(), get_type_name::<Self>().sobj_style());    
 
-        // Create new renderer texture resource
-        let image_data = match &self.load_type {
-            ResourceLoadType::Path(path) => {
-                // Check if path to asset is correct
-                pill_core::validate_asset_path(path, &["png", "jpg", "gif", "tif"])?;
-
-                // Load data
-                image::open(path)?
-            },
-            ResourceLoadType::Bytes(bytes) => {
-                // Load data
-                image::load_from_memory(bytes)?
-            },
-        };
+        // Create renderer texture that cameras render to (it has no data to load)
+        if let Some((width, height)) = self.render_target_size {
+            let renderer_resource_handle = engine.renderer.create_render_target_texture(&self.name, width, height, &self.settings).context(error_message)?;
+            self.renderer_resource_handle = Some(renderer_resource_handle);
+            return Ok(());
+        }
+
+        // Load texture data
+        let texture_data = load_texture_data(&engine.resource_manager, self).context(error_message.clone())?;
 
         // Create renderer texture resource
-        let renderer_resource_handle = engine.renderer.create_texture(&self.name, &image_data, self.texture_type).context(error_message.clone())?;
+        let renderer_resource_handle = engine.renderer.create_texture(&self.name, &texture_data, &self.settings).context(error_message.clone())?;
         self.renderer_resource_handle = Some(renderer_resource_handle);
 
         Ok(())
     }
 
+    fn get_source_paths(&self) -> Vec<PathBuf> {
+        // Cubemaps created from six images are reloaded when any of their faces changes
+        let load_types = match self.face_load_types.is_empty() {
+            true => std::slice::from_ref(&self.load_type),
+            false => self.face_load_types.as_slice(),
+        };
+        load_types.iter()
+            .filter_map(|load_type| match load_type {
+                ResourceLoadType::Path(path) => Some(path.clone()),
+                ResourceLoadType::Bytes(_) | ResourceLoadType::Archive(_) | ResourceLoadType::Generated => None,
+            })
+            .collect()
+    }
+
+    fn get_data_loader(&self, resource_manager: &ResourceManager) -> Option<ResourceDataLoader> {
+        // Render targets are created without loading so they are added synchronously
+        if self.render_target_size.is_some() {
+            return None;
+        }
+
+        // Asset loader is chosen here since resource manager is not available on worker thread
+        let texture_data_load = prepare_texture_data_load(resource_manager, self);
+        Some(Box::new(move || Ok(Box::new(texture_data_load?()?) as ResourceData)))
+    }
+
+    fn initialize_with_data<H: PillSlotMapKey>(&mut self, engine: &mut Engine, self_handle: H, data: ResourceData) -> Result<()> {
+        let error_message = format!("Initializing {} {} {} failed", "Resource".gobj_style(), get_type_name::<Self>().sobj_style(), self.name.name_style());
+
+        // Create renderer texture resource from loaded data (materials were using default texture until now)
+        let texture_data = data.downcast::<TextureData>().expect("Critical: Wrong resource data type");
+        self.set_texture_data(engine, self_handle, &texture_data).context(error_message)?;
+
+        Ok(())
+    }
+
+    fn reload<H: PillSlotMapKey>(&mut self, engine: &mut Engine, self_handle: H) -> Result<()> {
+        let error_message = format!("Reloading {} {} {} failed", "Resource".gobj_style(), get_type_name::<Self>().sobj_style(), self.name.name_style());
+
+        // Create new renderer texture resource (old one is kept if loading fails)
+        let texture_data = load_texture_data(&engine.resource_manager, self).context(error_message.clone())?;
+        self.set_texture_data(engine, self_handle, &texture_data).context(error_message)?;
+
+        Ok(())
+    }
+
+    fn get_reference_count<H: PillSlotMapKey>(&self, engine: &Engine, self_handle: H) -> Result<usize> {
+        // Count materials, global color grading LUT and camera components in all scenes that use this texture
+        let mut reference_count = get_dependent_material_handles(engine, self_handle).len();
+        if engine.post_process_settings.color_grading_lut == Some(TextureHandle::from(self_handle.data())) {
+            reference_count += 1;
+        }
+        for (scene_handle, scene) in engine.scene_manager.scenes.iter() {
+            // Scenes without camera components have no cameras to count
+            if let Ok(camera_components) = scene.get_one_component_iterator::<CameraComponent>() {
+                reference_count += camera_components.filter(|(_, v)| v.uses_texture(&TextureHandle::from(self_handle.data()))).count();
+            }
+        }
+
+        Ok(reference_count)
+    }
+
+    fn remove_dependent_resources<H: PillSlotMapKey>(&mut self, engine: &mut Engine, self_handle: H) -> Result<()> {
+        // Remove materials that use this texture
+        for material_handle in get_dependent_material_handles(engine, self_handle) {
+            engine.remove_resource::<Material>(&material_handle)?;
+        }
+
+        Ok(())
+    }
+
     fn destroy<H: PillSlotMapKey>(&mut self, engine: &mut Engine, self_handle: H) -> Result<()> {
         // Destroy renderer resource
         if let Some(v) = self.renderer_resource_handle {
@@ -112,6 +376,20 @@ impl Resource for Texture {
             }
         }
 
+        // Disable global color grading if this texture is its LUT
+        if engine.post_process_settings.color_grading_lut == Some(TextureHandle::from(self_handle.data())) {
+            engine.post_process_settings.color_grading_lut = None;
+        }
+
+        // Find camera components that use this texture and update them
+        for (scene_handle, scene) in engine.scene_manager.scenes.iter_mut() {
+            if let Ok(camera_components) = scene.get_one_component_iterator_mut::<CameraComponent>() {
+                for (entity_handle, camera_component) in camera_components {
+                    camera_component.remove_texture_handle(&TextureHandle::from(self_handle.data()));
+                }
+            }
+        }
+
         Ok(())
     }
 }
\ No newline at end of file
@@ -0,0 +1,379 @@
+use pill_core::{ EngineError, TextureCompressionFormat, decompress_texture };
+
+use anyhow::{ Result, Context, Error };
+use image::GenericImageView;
+use std::f32::consts::PI;
+
+// --- Texture data ---
+
+// Data texture is created from, images are decoded on load while compressed images are uploaded to GPU as they are
+pub enum TextureData {
+    Image(image::DynamicImage),
+    Hdr(HdrImage),
+    Compressed(CompressedImage),
+    Cubemap(CubemapImage),
+}
+
+impl TextureData {
+    pub fn get_dimensions(&self) -> (u32, u32) {
+        match self {
+            TextureData::Image(v) => v.dimensions(),
+            TextureData::Hdr(v) => (v.width, v.height),
+            TextureData::Compressed(v) => (v.width, v.height),
+            TextureData::Cubemap(v) => (v.size, v.size),
+        }
+    }
+
+    // Returns pixels as RGBA floats (0-1 range for images, unbounded for HDR images) and flag telling if data is HDR
+    fn get_rgba_pixels(&self) -> Result<(Vec<[f32; 4]>, bool)> {
+        match self {
+            TextureData::Image(v) => {
+                let pixels = v.to_rgba8().pixels()
+                    .map(|v| [v[0] as f32 / 255.0, v[1] as f32 / 255.0, v[2] as f32 / 255.0, v[3] as f32 / 255.0])
+                    .collect();
+                Ok((pixels, false))
+            },
+            TextureData::Hdr(v) => Ok((v.pixels.clone(), true)),
+            TextureData::Compressed(_) | TextureData::Cubemap(_) => 
+                Err(Error::new(EngineError::InvalidTextureData("Cubemap can be created only from uncompressed 2D images".to_string()))),
+        }
+    }
+}
+
+// --- HDR image ---
+
+#[derive(Clone, Debug)]
+pub struct HdrImage {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<[f32; 4]>, // RGBA pixels in linear color space, row by row
+}
+
+impl HdrImage {
+    pub fn from_hdr_bytes(bytes: &[u8]) -> Result<Self> {
+        let decoder = image::codecs::hdr::HdrDecoder::new(bytes).context(EngineError::InvalidTextureData("Invalid HDR file".to_string()))?;
+        let metadata = decoder.metadata();
+        let pixels = decoder.read_image_hdr().context(EngineError::InvalidTextureData("Invalid HDR file".to_string()))?
+            .iter()
+            .map(|v| [v[0], v[1], v[2], 1.0])
+            .collect();
+
+        Ok(Self {
+            width: metadata.width,
+            height: metadata.height,
+            pixels,
+        })
+    }
+
+    // Returns pixels as RGBA16 float data
+    pub fn to_half_float_bytes(&self) -> Vec<u8> {
+        encode_pixels(&self.pixels, true)
+    }
+}
+
+// --- Cubemap image ---
+
+#[derive(Clone, Debug)]
+pub struct CubemapImage {
+    pub size: u32, // Width and height of each face
+    pub hdr: bool, // Faces are stored as RGBA16 float if true, otherwise as RGBA8
+    pub faces: Vec<Vec<Vec<u8>>>, // Data of mip levels of faces in order +X, -X, +Y, -Y, +Z, -Z (used by renderer if mipmaps are enabled)
+}
+
+impl CubemapImage {
+    // Faces have to be square images of the same size, given in order +X, -X, +Y, -Y, +Z, -Z
+    pub fn from_faces(faces: &[TextureData]) -> Result<Self> {
+        if faces.len() != 6 {
+            return Err(Error::new(EngineError::InvalidTextureData(format!("Cubemap needs 6 faces, {} given", faces.len()))));
+        }
+
+        // Check sizes of faces
+        let size = faces[0].get_dimensions().0;
+        if faces.iter().any(|v| v.get_dimensions() != (size, size)) {
+            return Err(Error::new(EngineError::InvalidTextureData("Cubemap faces have to be square and of the same size".to_string())));
+        }
+
+        // Store all faces as HDR if any of them is HDR
+        let face_pixels = faces.iter().map(|v| v.get_rgba_pixels()).collect::<Result<Vec<(Vec<[f32; 4]>, bool)>>>()?;
+        let hdr = face_pixels.iter().any(|v| v.1);
+        let faces = face_pixels.iter().map(|v| get_face_mip_levels(&v.0, size, hdr)).collect();
+
+        Ok(Self {
+            size,
+            hdr,
+            faces,
+        })
+    }
+
+    // Projects equirectangular (latitude-longitude) image onto faces of the cube, size of face is quarter of image width
+    pub fn from_equirectangular(texture_data: &TextureData) -> Result<Self> {
+        let (width, height) = texture_data.get_dimensions();
+        let (pixels, hdr) = texture_data.get_rgba_pixels()?;
+        let size = (width / 4).max(1);
+
+        let mut faces = Vec::<Vec<Vec<u8>>>::with_capacity(6);
+        for face in 0..6 {
+            let mut face_pixels = Vec::<[f32; 4]>::with_capacity((size * size) as usize);
+            for y in 0..size {
+                for x in 0..size {
+                    // Get direction pointing to the texel
+                    let u = 2.0 * (x as f32 + 0.5) / size as f32 - 1.0;
+                    let v = 2.0 * (y as f32 + 0.5) / size as f32 - 1.0;
+                    let direction = get_cubemap_direction(face, u, v);
+                    let length = (direction[0] * direction[0] + direction[1] * direction[1] + direction[2] * direction[2]).sqrt();
+
+                    // Convert direction to position in equirectangular image
+                    let longitude = direction[2].atan2(direction[0]);
+                    let latitude = (direction[1] / length).clamp(-1.0, 1.0).acos();
+                    let image_x = (0.5 + longitude / (2.0 * PI)) * width as f32;
+                    let image_y = (latitude / PI) * height as f32;
+
+                    face_pixels.push(sample_bilinear(&pixels, width, height, image_x, image_y));
+                }
+            }
+            faces.push(get_face_mip_levels(&face_pixels, size, hdr));
+        }
+
+        Ok(Self {
+            size,
+            hdr,
+            faces,
+        })
+    }
+}
+
+// --- Compressed image ---
+
+#[derive(Clone, Debug)]
+pub struct CompressedImage {
+    pub format: TextureCompressionFormat,
+    pub width: u32,
+    pub height: u32,
+    pub mip_levels: Vec<Vec<u8>>, // Data of each mip level, starting from the full size one
+}
+
+impl CompressedImage {
+    pub fn new(format: TextureCompressionFormat, width: u32, height: u32, mip_levels: Vec<Vec<u8>>) -> Result<Self> {
+        let compressed_image = Self {
+            format,
+            width,
+            height,
+            mip_levels,
+        };
+
+        // Check if there is enough data for all mip levels
+        if compressed_image.mip_levels.is_empty() {
+            return Err(Error::new(EngineError::InvalidTextureData("No mip levels".to_string())));
+        }
+        for (mip_level, data) in compressed_image.mip_levels.iter().enumerate() {
+            let (width, height) = compressed_image.get_mip_level_size(mip_level as u32);
+            if data.len() < format.get_compressed_size(width, height) {
+                return Err(Error::new(EngineError::InvalidTextureData(format!("Not enough data for mip level {}", mip_level))));
+            }
+        }
+
+        Ok(compressed_image)
+    }
+
+    // Only 2D textures with BC1-BC7 formats are supported, sRGB flag of the format is ignored (it is set in texture settings)
+    pub fn from_ktx2_bytes(bytes: &[u8]) -> Result<Self> {
+        let reader = ktx2::Reader::new(bytes).map_err(|v| Error::new(EngineError::InvalidTextureData(format!("Invalid KTX2 file ({})", v))))?;
+        let header = reader.header();
+
+        if header.pixel_depth > 1 || header.layer_count > 1 || header.face_count != 1 {
+            return Err(Error::new(EngineError::InvalidTextureData("Only 2D KTX2 textures are supported".to_string())));
+        }
+
+        // Get format
+        let format = match header.format {
+            Some(ktx2::Format::BC1_RGB_UNORM_BLOCK) | Some(ktx2::Format::BC1_RGB_SRGB_BLOCK) |
+            Some(ktx2::Format::BC1_RGBA_UNORM_BLOCK) | Some(ktx2::Format::BC1_RGBA_SRGB_BLOCK) => TextureCompressionFormat::Bc1,
+            Some(ktx2::Format::BC2_UNORM_BLOCK) | Some(ktx2::Format::BC2_SRGB_BLOCK) => TextureCompressionFormat::Bc2,
+            Some(ktx2::Format::BC3_UNORM_BLOCK) | Some(ktx2::Format::BC3_SRGB_BLOCK) => TextureCompressionFormat::Bc3,
+            Some(ktx2::Format::BC4_UNORM_BLOCK) => TextureCompressionFormat::Bc4,
+            Some(ktx2::Format::BC4_SNORM_BLOCK) => TextureCompressionFormat::Bc4Signed,
+            Some(ktx2::Format::BC5_UNORM_BLOCK) => TextureCompressionFormat::Bc5,
+            Some(ktx2::Format::BC5_SNORM_BLOCK) => TextureCompressionFormat::Bc5Signed,
+            Some(ktx2::Format::BC6H_UFLOAT_BLOCK) => TextureCompressionFormat::Bc6h,
+            Some(ktx2::Format::BC6H_SFLOAT_BLOCK) => TextureCompressionFormat::Bc6hSigned,
+            Some(ktx2::Format::BC7_UNORM_BLOCK) | Some(ktx2::Format::BC7_SRGB_BLOCK) => TextureCompressionFormat::Bc7,
+            v => return Err(Error::new(EngineError::InvalidTextureData(format!("KTX2 format {:?} is not supported", v)))),
+        };
+
+        // Read mip levels and decompress them if needed
+        let mut mip_levels = Vec::<Vec<u8>>::new();
+        for level in reader.levels() {
+            let data = match header.supercompression_scheme {
+                None => level.data.to_vec(),
+                Some(ktx2::SupercompressionScheme::ZLIB) => pill_core::miniz_oxide::inflate::decompress_to_vec_zlib(level.data)
+                    .map_err(|_| Error::new(EngineError::InvalidTextureData("Cannot decompress KTX2 mip level".to_string())))?,
+                Some(v) => return Err(Error::new(EngineError::InvalidTextureData(format!("KTX2 supercompression {:?} is not supported", v)))),
+            };
+            mip_levels.push(data);
+        }
+
+        Self::new(format, header.pixel_width, header.pixel_height.max(1), mip_levels)
+    }
+
+    // Only BC1-BC7 formats are supported, for cubemaps and arrays only the first image is used
+    pub fn from_dds_bytes(bytes: &[u8]) -> Result<Self> {
+        let dds = ddsfile::Dds::read(bytes).context(EngineError::InvalidTextureData("Invalid DDS file".to_string()))?;
+
+        // Get format (from DX10 header, or from FourCC code of legacy files)
+        let format = match (dds.get_dxgi_format(), dds.get_d3d_format()) {
+            (Some(ddsfile::DxgiFormat::BC1_UNorm), _) | (Some(ddsfile::DxgiFormat::BC1_UNorm_sRGB), _) |
+            (Some(ddsfile::DxgiFormat::BC1_Typeless), _) | (_, Some(ddsfile::D3DFormat::DXT1)) => TextureCompressionFormat::Bc1,
+            (Some(ddsfile::DxgiFormat::BC2_UNorm), _) | (Some(ddsfile::DxgiFormat::BC2_UNorm_sRGB), _) |
+            (Some(ddsfile::DxgiFormat::BC2_Typeless), _) | (_, Some(ddsfile::D3DFormat::DXT2)) |
+            (_, Some(ddsfile::D3DFormat::DXT3)) => TextureCompressionFormat::Bc2,
+            (Some(ddsfile::DxgiFormat::BC3_UNorm), _) | (Some(ddsfile::DxgiFormat::BC3_UNorm_sRGB), _) |
+            (Some(ddsfile::DxgiFormat::BC3_Typeless), _) | (_, Some(ddsfile::D3DFormat::DXT4)) |
+            (_, Some(ddsfile::D3DFormat::DXT5)) => TextureCompressionFormat::Bc3,
+            (Some(ddsfile::DxgiFormat::BC4_UNorm), _) | (Some(ddsfile::DxgiFormat::BC4_Typeless), _) => TextureCompressionFormat::Bc4,
+            (Some(ddsfile::DxgiFormat::BC4_SNorm), _) => TextureCompressionFormat::Bc4Signed,
+            (Some(ddsfile::DxgiFormat::BC5_UNorm), _) | (Some(ddsfile::DxgiFormat::BC5_Typeless), _) => TextureCompressionFormat::Bc5,
+            (Some(ddsfile::DxgiFormat::BC5_SNorm), _) => TextureCompressionFormat::Bc5Signed,
+            (Some(ddsfile::DxgiFormat::BC6H_UF16), _) | (Some(ddsfile::DxgiFormat::BC6H_Typeless), _) => TextureCompressionFormat::Bc6h,
+            (Some(ddsfile::DxgiFormat::BC6H_SF16), _) => TextureCompressionFormat::Bc6hSigned,
+            (Some(ddsfile::DxgiFormat::BC7_UNorm), _) | (Some(ddsfile::DxgiFormat::BC7_UNorm_sRGB), _) |
+            (Some(ddsfile::DxgiFormat::BC7_Typeless), _) => TextureCompressionFormat::Bc7,
+            _ => return Err(Error::new(EngineError::InvalidTextureData("DDS format is not supported, only BC1-BC7 formats can be loaded".to_string()))),
+        };
+
+        // Split data of the first image into mip levels
+        let (width, height) = (dds.get_width(), dds.get_height());
+        let data = dds.get_data(0).context(EngineError::InvalidTextureData("Invalid DDS file".to_string()))?;
+        let mut mip_levels = Vec::<Vec<u8>>::new();
+        let mut offset = 0;
+        for mip_level in 0..dds.get_num_mipmap_levels().max(1) {
+            let size = format.get_compressed_size((width >> mip_level).max(1), (height >> mip_level).max(1));
+            let mip_level_data = data.get(offset..offset + size)
+                .ok_or(Error::new(EngineError::InvalidTextureData(format!("Not enough data for mip level {}", mip_level))))?;
+            mip_levels.push(mip_level_data.to_vec());
+            offset += size;
+        }
+
+        Self::new(format, width, height, mip_levels)
+    }
+
+    pub fn get_mip_level_size(&self, mip_level: u32) -> (u32, u32) {
+        ((self.width >> mip_level).max(1), (self.height >> mip_level).max(1))
+    }
+
+    // Returns decompressed data of all mip levels (RGBA8 or RGBA16 float for HDR formats)
+    pub fn decompress(&self) -> Result<Vec<Vec<u8>>> {
+        self.mip_levels.iter().enumerate()
+            .map(|(mip_level, data)| {
+                let (width, height) = self.get_mip_level_size(mip_level as u32);
+                decompress_texture(self.format, width, height, data)
+            })
+            .collect()
+    }
+}
+
+// Returns direction (not normalized) of cubemap texel, u and v are in range from -1 to 1 going right and down on the face
+fn get_cubemap_direction(face: usize, u: f32, v: f32) -> [f32; 3] {
+    match face {
+        0 => [1.0, -v, -u],
+        1 => [-1.0, -v, u],
+        2 => [u, 1.0, v],
+        3 => [u, -1.0, -v],
+        4 => [u, -v, 1.0],
+        _ => [-u, -v, -1.0],
+    }
+}
+
+// Returns encoded mip levels of cubemap face, each level averages 2x2 texels of the previous one down to 1x1
+fn get_face_mip_levels(pixels: &[[f32; 4]], size: u32, hdr: bool) -> Vec<Vec<u8>> {
+    let mut mip_levels = vec![encode_pixels(pixels, hdr)];
+    let mut previous_pixels = pixels.to_vec();
+    let mut previous_size = size as usize;
+    while previous_size > 1 {
+        let mip_level_size = previous_size / 2;
+        let mut mip_level_pixels = Vec::<[f32; 4]>::with_capacity(mip_level_size * mip_level_size);
+        for y in 0..mip_level_size {
+            for x in 0..mip_level_size {
+                let mut pixel = [0.0; 4];
+                for (offset_x, offset_y) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                    let previous_pixel = previous_pixels[(2 * y + offset_y) * previous_size + 2 * x + offset_x];
+                    for channel in 0..4 {
+                        pixel[channel] += previous_pixel[channel] * 0.25;
+                    }
+                }
+                mip_level_pixels.push(pixel);
+            }
+        }
+
+        mip_levels.push(encode_pixels(&mip_level_pixels, hdr));
+        previous_pixels = mip_level_pixels;
+        previous_size = mip_level_size;
+    }
+    mip_levels
+}
+
+// Samples image with horizontal wrapping and vertical clamping (as needed for equirectangular images)
+fn sample_bilinear(pixels: &[[f32; 4]], width: u32, height: u32, x: f32, y: f32) -> [f32; 4] {
+    let x = x - 0.5;
+    let y = (y - 0.5).clamp(0.0, (height - 1) as f32);
+    let (x0, y0) = (x.floor(), y.floor());
+    let (fx, fy) = (x - x0, y - y0);
+
+    let get_pixel = |x: i64, y: i64| {
+        let x = x.rem_euclid(width as i64) as usize;
+        let y = y.clamp(0, height as i64 - 1) as usize;
+        pixels[y * width as usize + x]
+    };
+    let (x0, y0) = (x0 as i64, y0 as i64);
+    let (p00, p10, p01, p11) = (get_pixel(x0, y0), get_pixel(x0 + 1, y0), get_pixel(x0, y0 + 1), get_pixel(x0 + 1, y0 + 1));
+
+    let mut result = [0.0; 4];
+    for channel in 0..4 {
+        let top = p00[channel] * (1.0 - fx) + p10[channel] * fx;
+        let bottom = p01[channel] * (1.0 - fx) + p11[channel] * fx;
+        result[channel] = top * (1.0 - fy) + bottom * fy;
+    }
+    result
+}
+
+// Encodes pixels as RGBA16 float or RGBA8 data
+fn encode_pixels(pixels: &[[f32; 4]], hdr: bool) -> Vec<u8> {
+    match hdr {
+        true => pixels.iter().flatten().flat_map(|v| get_half_float_bits(*v).to_le_bytes()).collect(),
+        false => pixels.iter().flatten().map(|v| (v.clamp(0.0, 1.0) * 255.0).round() as u8).collect(),
+    }
+}
+
+// Converts 32-bit float to 16-bit float with rounding to nearest
+fn get_half_float_bits(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xFF) as i32;
+    let mantissa = bits & 0x7F_FFFF;
+
+    // Infinity and NaN
+    if exponent == 0xFF {
+        return sign | 0x7C00 | if mantissa != 0 { 0x200 } else { 0 };
+    }
+
+    let exponent = exponent - 127 + 15;
+
+    // Too big values become infinity
+    if exponent >= 0x1F {
+        return sign | 0x7C00;
+    }
+
+    // Too small values become subnormal numbers or zero
+    if exponent <= 0 {
+        if exponent < -10 {
+            return sign;
+        }
+        let mantissa = mantissa | 0x80_0000;
+        let shift = (14 - exponent) as u32;
+        let round = ((mantissa >> (shift - 1)) & 1) as u16;
+        return sign | (((mantissa >> shift) as u16) + round);
+    }
+
+    // Rounding can carry over to exponent which is still correct
+    let half = sign | ((exponent as u16) << 10) | ((mantissa >> 13) as u16);
+    half + ((mantissa >> 12) & 1) as u16
+}
@@ -41,10 +41,10 @@ impl PillGame for Game {
 
         // Add materials
         let mut pill_material = Material::new("Pill");
-        pill_material.set_texture("Color", pill_color_texture_handle)?;
+        pill_material.set_texture("BaseColor", pill_color_texture_handle)?;
         pill_material.set_texture("Normal", pill_normal_texture_handle)?;
-        pill_material.set_color("Tint", Color::new( 1.0, 1.0, 1.0))?;
-        pill_material.set_scalar("Specularity", 0.5)?; 
+        pill_material.set_color("BaseColor", Color::new( 1.0, 1.0, 1.0))?;
+        pill_material.set_scalar("Roughness", 0.5)?; 
         let pill_material_handle = engine.add_resource::<Material>(pill_material)?; 
 
         // Create camera entity
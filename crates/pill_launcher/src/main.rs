@@ -8,6 +8,7 @@ use std::{
     env
 };
 use config::Config;
+use pill_core::PillArchiveWriter;
 use fs_extra::dir::CopyOptions;
 use anyhow::*;
 use clap::{ Arg, App };
@@ -181,8 +182,12 @@ fn run_game_project(game_path: &String, compile_mode: &String) -> Result<()> {
     Ok(())
 }
 
-// Runs "cargo build" command on pill_standalone, clears build directory in game project folder and copy exe and res folder to it
-fn build_game_project(game_path: &String, output_path: &String, compile_mode: &String) -> Result<()> {
+// Files that are read before engine starts so they are copied next to the archive instead of being packed
+const UNPACKED_RESOURCE_FILES: [&str; 2] = ["config.ini", "icon.ico"];
+const RESOURCE_ARCHIVE_NAME: &str = "res.pak";
+
+// Runs "cargo build" command on pill_standalone, clears build directory in game project folder, copies exe to it and packs res folder into archive
+fn build_game_project(game_path: &String, output_path: &String, compile_mode: &String, compress: bool) -> Result<()> {
     // Prepare game path
     let mut game_path = PathBuf::from(game_path);
     if game_path.to_str().unwrap() == "." { // Use current directory absolute path if no argument is specified
@@ -247,10 +252,19 @@ fn build_game_project(game_path: &String, output_path: &String, compile_mode: &S
     // Copy built executable to build directory and rename it according to variable in config file
     fs::copy(&engine_build_path, &output_path.join(game_title + ".exe"))?;
     
-    // Copy game res directory to build directory
-    let mut copy_options = CopyOptions::new();
-    copy_options.overwrite = true;
-    fs_extra::dir::copy(&game_project_resources_path, &output_path, &copy_options).context("Cannot copy game res directory")?;
+    // Pack game res directory into archive
+    let mut archive_writer = PillArchiveWriter::new(compress);
+    archive_writer.add_directory(&game_project_resources_path, &UNPACKED_RESOURCE_FILES)?;
+    archive_writer.write(&output_path.join(RESOURCE_ARCHIVE_NAME)).context("Cannot create resource archive")?;
+
+    // Copy files that are not packed
+    fs::create_dir_all(output_path.join("res"))?;
+    for file_name in UNPACKED_RESOURCE_FILES {
+        let file_path = game_project_resources_path.join(file_name);
+        if file_path.exists() {
+            fs::copy(&file_path, &output_path.join("res").join(file_name)).context(format!("Cannot copy {}", file_name))?;
+        }
+    }
 
     // Success
     println!("Game built succesully!");
@@ -371,8 +385,15 @@ fn main() {
         .default_value("debug")
         .required(false);
 
+    let compress_option = Arg::with_name("compress")
+        .short("z")
+        .long("compress")
+        .takes_value(false)
+        .required(false)
+        .help("Compress resources packed into archive when building the game project");
+
     // Addition of the options to the CLI
-    let app = app.arg(action_option).arg(name_option).arg(path_option).arg(output_path_option).arg(compile_mode_option);
+    let app = app.arg(action_option).arg(name_option).arg(path_option).arg(output_path_option).arg(compile_mode_option).arg(compress_option);
 
     // Extraction of the arguments
     let matches = app.get_matches();
@@ -383,6 +404,7 @@ fn main() {
     let game_name = matches.value_of("name");
     let output_path = matches.value_of("output-path");
     let compile_mode = matches.value_of("compile-mode");
+    let compress = matches.is_present("compress");
 
     match action {
         "create" => {
@@ -402,7 +424,7 @@ fn main() {
             let output_path = String::from(output_path.unwrap());
             let compile_mode = String::from(compile_mode.unwrap());
 
-            build_game_project(&game_path, &output_path, &compile_mode).context("Failed to build game project").unwrap();
+            build_game_project(&game_path, &output_path, &compile_mode, compress).context("Failed to build game project").unwrap();
         },
         "docs" => {
             let output_path = String::from(output_path.unwrap());
@@ -16,6 +16,8 @@ pub struct EguiRenderer {
     renderer: Renderer,
     pub window_scale_factor: f32,
     pub window: Arc<winit::window::Window>,
+    frame_started: bool,
+    pending_textures_delta: egui::TexturesDelta, // Texture changes of frames that were discarded, uploaded with the next drawn frame
 }
 
 impl EguiRenderer {
@@ -52,7 +54,9 @@ impl EguiRenderer {
             state: egui_state,
             renderer: egui_renderer,
             window_scale_factor,
-            window
+            window,
+            frame_started: false,
+            pending_textures_delta: egui::TexturesDelta::default(),
         }
     }
 
@@ -60,6 +64,26 @@ impl EguiRenderer {
         let _ = self.state.on_window_event(&self.window, event);
     }
 
+    // Starts new egui frame, UI can be added to returned context until draw is called
+    pub fn begin_frame(&mut self) -> Context {
+        let raw_input = self.state.take_egui_input(&self.window);
+        self.context.begin_frame(raw_input);
+        self.frame_started = true;
+        self.context.clone()
+    }
+
+    // Ends started frame without drawing it (used when rendering of the frame failed), so next frame starts from clean state
+    pub fn discard_frame(&mut self) {
+        if !self.frame_started {
+            return;
+        }
+        self.frame_started = false;
+
+        let full_output = self.context.end_frame();
+        self.state.handle_platform_output(&self.window, full_output.platform_output);
+        self.pending_textures_delta.append(full_output.textures_delta);
+    }
+
     pub fn draw(
         &mut self,
         device: &Device,
@@ -67,17 +91,24 @@ impl EguiRenderer {
         encoder: &mut CommandEncoder,
         window_surface_view: &TextureView,
         screen_descriptor: ScreenDescriptor,
-        run_ui: impl FnOnce(&Context),
+        timestamp_writes: Option<wgpu::RenderPassTimestampWrites>, // UI is the last render pass of the frame so GPU timer ends here
     ) {
+        // Make sure that frame is started even if no UI was added
+        if !self.frame_started {
+            self.begin_frame();
+        }
+        self.frame_started = false;
+
         let window = &self.window;
-        let raw_input = self.state.take_egui_input(&window);
-        let full_output = self.context.run(raw_input, |ui| {
-            run_ui(&self.context);
-        });
+        let mut full_output = self.context.end_frame();
 
         self.state
             .handle_platform_output(&window, full_output.platform_output);
 
+        let mut textures_delta = std::mem::take(&mut self.pending_textures_delta);
+        textures_delta.append(full_output.textures_delta);
+        full_output.textures_delta = textures_delta;
+
         let tris = self
             .context
             .tessellate(full_output.shapes, full_output.pixels_per_point);
@@ -98,7 +129,7 @@ impl EguiRenderer {
             })],
             depth_stencil_attachment: None,
             label: Some("egui main render pass"),
-            timestamp_writes: None,
+            timestamp_writes,
             occlusion_query_set: None,
         });
         self.renderer.render(&mut rpass, &tris, &screen_descriptor);
@@ -0,0 +1,126 @@
+use std::sync::{ Arc, atomic::{ AtomicU8, Ordering } };
+
+const GPU_TIMER_STATE_IDLE: u8 = 0; // Readback buffer can be written
+const GPU_TIMER_STATE_MAPPING: u8 = 1; // Readback buffer is being mapped
+const GPU_TIMER_STATE_MAPPED: u8 = 2; // Readback buffer is mapped and can be read
+
+const GPU_TIMER_QUERY_COUNT: u32 = 2; // Begin and end of the measured pass
+const GPU_TIMER_BUFFER_SIZE: u64 = GPU_TIMER_QUERY_COUNT as u64 * std::mem::size_of::<u64>() as u64;
+
+// --- GPU Timer ---
+
+// Measures GPU time of a render pass using timestamp queries
+// Results are read back asynchronously so reported time is few frames late
+pub struct GpuTimer {
+    query_set: wgpu::QuerySet,
+    resolve_buffer: wgpu::Buffer,
+    readback_buffer: wgpu::Buffer,
+    timestamp_period: f32, // Nanoseconds per timestamp tick
+    state: Arc<AtomicU8>,
+    resolved_this_frame: bool,
+    last_frame_time: Option<f32>, // Milliseconds
+}
+
+impl GpuTimer {
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue) -> Self {
+        let query_set = device.create_query_set(&wgpu::QuerySetDescriptor {
+            label: Some("gpu_timer_query_set"),
+            ty: wgpu::QueryType::Timestamp,
+            count: GPU_TIMER_QUERY_COUNT,
+        });
+
+        let resolve_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("gpu_timer_resolve_buffer"),
+            size: GPU_TIMER_BUFFER_SIZE,
+            usage: wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+
+        let readback_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("gpu_timer_readback_buffer"),
+            size: GPU_TIMER_BUFFER_SIZE,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        Self {
+            query_set,
+            resolve_buffer,
+            readback_buffer,
+            timestamp_period: queue.get_timestamp_period(),
+            state: Arc::new(AtomicU8::new(GPU_TIMER_STATE_IDLE)),
+            resolved_this_frame: false,
+            last_frame_time: None,
+        }
+    }
+
+    // Returns timestamp writes that should be passed to the first render pass of the frame
+    pub fn get_beginning_timestamp_writes(&self) -> wgpu::RenderPassTimestampWrites {
+        wgpu::RenderPassTimestampWrites {
+            query_set: &self.query_set,
+            beginning_of_pass_write_index: Some(0),
+            end_of_pass_write_index: None,
+        }
+    }
+
+    // Returns timestamp writes that should be passed to the last render pass of the frame
+    pub fn get_end_timestamp_writes(&self) -> wgpu::RenderPassTimestampWrites {
+        wgpu::RenderPassTimestampWrites {
+            query_set: &self.query_set,
+            beginning_of_pass_write_index: None,
+            end_of_pass_write_index: Some(1),
+        }
+    }
+
+    // Reads results of previous frames if they are ready
+    pub fn collect(&mut self, device: &wgpu::Device) {
+        device.poll(wgpu::Maintain::Poll);
+
+        if self.state.load(Ordering::Acquire) != GPU_TIMER_STATE_MAPPED {
+            return;
+        }
+
+        {
+            let data = self.readback_buffer.slice(..).get_mapped_range();
+            let timestamps: &[u64] = bytemuck::cast_slice(&data);
+            let ticks = timestamps[1].saturating_sub(timestamps[0]);
+            self.last_frame_time = Some(ticks as f32 * self.timestamp_period / 1_000_000.0);
+        }
+        self.readback_buffer.unmap();
+        self.state.store(GPU_TIMER_STATE_IDLE, Ordering::Release);
+    }
+
+    // Resolves queries and copies them to readback buffer (only if previous results were already read)
+    pub fn resolve(&mut self, encoder: &mut wgpu::CommandEncoder) {
+        self.resolved_this_frame = false;
+        if self.state.load(Ordering::Acquire) != GPU_TIMER_STATE_IDLE {
+            return;
+        }
+
+        encoder.resolve_query_set(&self.query_set, 0..GPU_TIMER_QUERY_COUNT, &self.resolve_buffer, 0);
+        encoder.copy_buffer_to_buffer(&self.resolve_buffer, 0, &self.readback_buffer, 0, GPU_TIMER_BUFFER_SIZE);
+        self.resolved_this_frame = true;
+    }
+
+    // Starts mapping readback buffer, has to be called after command buffer with resolved queries is submitted
+    pub fn request_readback(&mut self) {
+        if !self.resolved_this_frame {
+            return;
+        }
+        self.resolved_this_frame = false;
+
+        self.state.store(GPU_TIMER_STATE_MAPPING, Ordering::Release);
+        let state = self.state.clone();
+        self.readback_buffer.slice(..).map_async(wgpu::MapMode::Read, move |result| {
+            let new_state = match result {
+                Ok(_) => GPU_TIMER_STATE_MAPPED,
+                Err(_) => GPU_TIMER_STATE_IDLE,
+            };
+            state.store(new_state, Ordering::Release);
+        });
+    }
+
+    pub fn get_last_frame_time(&self) -> Option<f32> {
+        self.last_frame_time
+    }
+}
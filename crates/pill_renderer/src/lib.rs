@@ -5,6 +5,8 @@ pub mod resources;
 pub mod renderer_resource_storage;
 pub mod instance;
 pub mod egui;
+pub mod gpu_timer;
+pub mod post_process;
 
 
 // --- Use ---
@@ -0,0 +1,440 @@
+use pill_engine::internal::{
+    PostProcessSettings,
+    PostProcessShaders,
+    Tonemapping,
+};
+
+use anyhow::{ Result };
+
+pub const HDR_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
+// Settings as they are laid out in uniform buffer of post-process shaders
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct PostProcessUniform {
+    exposure: f32,
+    tonemapping: u32,
+    bloom_threshold: f32,
+    bloom_intensity: f32,
+    vignette_intensity: f32,
+    vignette_smoothness: f32,
+    color_grading_intensity: f32,
+    color_grading_lut_size: f32,
+}
+
+impl PostProcessUniform {
+    // Intensity of disabled effects is set to zero
+    fn new(settings: &PostProcessSettings, color_grading_lut_size: Option<u32>) -> Self {
+        let tonemapping = match settings.tonemapping {
+            Tonemapping::None => 0,
+            Tonemapping::Reinhard => 1,
+            Tonemapping::Aces => 2,
+        };
+
+        Self {
+            exposure: settings.exposure,
+            tonemapping,
+            bloom_threshold: settings.bloom_threshold,
+            bloom_intensity: if settings.bloom_enabled { settings.bloom_intensity } else { 0.0 },
+            vignette_intensity: if settings.vignette_enabled { settings.vignette_intensity } else { 0.0 },
+            vignette_smoothness: settings.vignette_smoothness,
+            color_grading_intensity: if color_grading_lut_size.is_some() { settings.color_grading_intensity } else { 0.0 },
+            color_grading_lut_size: color_grading_lut_size.unwrap_or(1) as f32,
+        }
+    }
+}
+
+// --- Post-process targets ---
+
+// Textures that depend on window size
+struct PostProcessTargets {
+    hdr_texture_view: wgpu::TextureView,
+    ldr_texture_view: wgpu::TextureView, // Tonemapped image used as input of FXAA
+    bloom_texture_views: Vec<wgpu::TextureView>, // Mip levels of bloom texture, the first one has half size of the window
+    bloom_prefilter_bind_group: wgpu::BindGroup,
+    bloom_downsample_bind_groups: Vec<wgpu::BindGroup>, // Reads level N and writes level N + 1
+    bloom_upsample_bind_groups: Vec<wgpu::BindGroup>, // Reads level N + 1 and adds it to level N
+    fxaa_bind_group: wgpu::BindGroup,
+}
+
+// --- Post-processor ---
+
+// Scene is rendered to HDR texture and post-processed into the surface
+// Passes: bloom (prefilter, downsample and upsample chain), composite (exposure, tonemapping, color grading, vignette), FXAA
+pub struct PostProcessor {
+    source_bind_group_layout: wgpu::BindGroupLayout,
+    composite_bind_group_layout: wgpu::BindGroupLayout,
+    settings_bind_group: wgpu::BindGroup,
+    settings_buffer: wgpu::Buffer,
+    sampler: wgpu::Sampler,
+    empty_color_grading_lut_view: wgpu::TextureView,
+    bloom_prefilter_pipeline: wgpu::RenderPipeline,
+    bloom_downsample_pipeline: wgpu::RenderPipeline,
+    bloom_upsample_pipeline: wgpu::RenderPipeline,
+    composite_pipeline: wgpu::RenderPipeline,
+    fxaa_pipeline: wgpu::RenderPipeline,
+    output_format: wgpu::TextureFormat,
+    max_bloom_mip_count: u32,
+    targets: PostProcessTargets,
+}
+
+impl PostProcessor {
+    pub fn new(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        post_process_shaders: &PostProcessShaders,
+        output_format: wgpu::TextureFormat,
+        size: (u32, u32),
+        max_bloom_mip_count: u32,
+    ) -> Result<Self> {
+
+        // Create shaders
+        let create_shader_module = |label: &str, source: &str| device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some(label),
+            source: wgpu::ShaderSource::Wgsl(source.into()),
+        });
+        let vertex_shader = create_shader_module("post_process_vertex_shader", post_process_shaders.vertex_shader_source);
+        let bloom_prefilter_shader = create_shader_module("bloom_prefilter_shader", post_process_shaders.bloom_prefilter_shader_source);
+        let bloom_downsample_shader = create_shader_module("bloom_downsample_shader", post_process_shaders.bloom_downsample_shader_source);
+        let bloom_upsample_shader = create_shader_module("bloom_upsample_shader", post_process_shaders.bloom_upsample_shader_source);
+        let composite_shader = create_shader_module("composite_shader", post_process_shaders.composite_shader_source);
+        let fxaa_shader = create_shader_module("fxaa_shader", post_process_shaders.fxaa_shader_source);
+
+        // Define bind group layouts
+        let source_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("post_process_source_bind_group_layout"),
+            entries: &[
+                get_texture_layout_entry(0),
+                get_sampler_layout_entry(1),
+            ],
+        });
+
+        let composite_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("post_process_composite_bind_group_layout"),
+            entries: &[
+                get_texture_layout_entry(0),
+                get_texture_layout_entry(1),
+                get_texture_layout_entry(2),
+                get_sampler_layout_entry(3),
+            ],
+        });
+
+        let settings_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("post_process_settings_bind_group_layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        // Create settings buffer (it is written every frame)
+        let settings_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("post_process_settings_buffer"),
+            size: std::mem::size_of::<PostProcessUniform>() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let settings_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("post_process_settings_bind_group"),
+            layout: &settings_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: settings_buffer.as_entire_binding(),
+                },
+            ],
+        });
+
+        // Create sampler shared by all passes (edges are clamped so that opposite sides of the screen do not blend)
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("post_process_sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        // Create LUT bound when color grading is disabled
+        let empty_color_grading_lut_texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("empty_color_grading_lut_texture"),
+            size: wgpu::Extent3d { width: 1, height: 1, depth_or_array_layers: 1 },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        queue.write_texture(
+            empty_color_grading_lut_texture.as_image_copy(),
+            &[255, 255, 255, 255],
+            wgpu::ImageDataLayout { offset: 0, bytes_per_row: Some(4), rows_per_image: Some(1) },
+            wgpu::Extent3d { width: 1, height: 1, depth_or_array_layers: 1 },
+        );
+        let empty_color_grading_lut_view = empty_color_grading_lut_texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        // Create pipelines
+        let create_pipeline = |label: &str, fragment_shader: &wgpu::ShaderModule, bind_group_layouts: &[&wgpu::BindGroupLayout], format: wgpu::TextureFormat, blend: wgpu::BlendState| {
+            let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some(&format!("{}_layout", label)),
+                bind_group_layouts,
+                push_constant_ranges: &[],
+            });
+
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(label),
+                layout: Some(&layout),
+                vertex: wgpu::VertexState {
+                    module: &vertex_shader,
+                    entry_point: "main",
+                    buffers: &[], // Full screen triangle is created in vertex shader
+                    compilation_options: Default::default(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: fragment_shader,
+                    entry_point: "main",
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format,
+                        blend: Some(blend),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                    compilation_options: Default::default(),
+                }),
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None,
+            })
+        };
+
+        let additive_blend = wgpu::BlendState {
+            color: wgpu::BlendComponent { src_factor: wgpu::BlendFactor::One, dst_factor: wgpu::BlendFactor::One, operation: wgpu::BlendOperation::Add },
+            alpha: wgpu::BlendComponent::REPLACE,
+        };
+        let bloom_prefilter_pipeline = create_pipeline("bloom_prefilter_pipeline", &bloom_prefilter_shader, &[&source_bind_group_layout, &settings_bind_group_layout], HDR_FORMAT, wgpu::BlendState::REPLACE);
+        let bloom_downsample_pipeline = create_pipeline("bloom_downsample_pipeline", &bloom_downsample_shader, &[&source_bind_group_layout], HDR_FORMAT, wgpu::BlendState::REPLACE);
+        let bloom_upsample_pipeline = create_pipeline("bloom_upsample_pipeline", &bloom_upsample_shader, &[&source_bind_group_layout], HDR_FORMAT, additive_blend);
+        let composite_pipeline = create_pipeline("composite_pipeline", &composite_shader, &[&composite_bind_group_layout, &settings_bind_group_layout], output_format, wgpu::BlendState::REPLACE);
+        let fxaa_pipeline = create_pipeline("fxaa_pipeline", &fxaa_shader, &[&source_bind_group_layout], output_format, wgpu::BlendState::REPLACE);
+
+        // Create targets
+        let targets = Self::create_targets(device, &source_bind_group_layout, &sampler, output_format, size, max_bloom_mip_count);
+
+        let post_processor = Self {
+            source_bind_group_layout,
+            composite_bind_group_layout,
+            settings_bind_group,
+            settings_buffer,
+            sampler,
+            empty_color_grading_lut_view,
+            bloom_prefilter_pipeline,
+            bloom_downsample_pipeline,
+            bloom_upsample_pipeline,
+            composite_pipeline,
+            fxaa_pipeline,
+            output_format,
+            max_bloom_mip_count,
+            targets,
+        };
+
+        Ok(post_processor)
+    }
+
+    pub fn resize(&mut self, device: &wgpu::Device, size: (u32, u32)) {
+        self.targets = Self::create_targets(device, &self.source_bind_group_layout, &self.sampler, self.output_format, size, self.max_bloom_mip_count);
+    }
+
+    // Scene has to be rendered to this view before post-process passes are recorded
+    pub fn get_hdr_texture_view(&self) -> &wgpu::TextureView {
+        &self.targets.hdr_texture_view
+    }
+
+    pub fn record_commands(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        settings: &PostProcessSettings,
+        color_grading_lut: Option<&wgpu::Texture>,
+        output_view: &wgpu::TextureView,
+    ) {
+        // Update settings
+        let uniform = PostProcessUniform::new(settings, color_grading_lut.map(|v| v.height()));
+        queue.write_buffer(&self.settings_buffer, 0, bytemuck::cast_slice(&[uniform]));
+
+        // Bloom
+        if settings.bloom_enabled {
+            let bloom_texture_views = &self.targets.bloom_texture_views;
+
+            // Extract bright parts of the scene to the first mip level
+            draw_full_screen_pass(encoder, "bloom_prefilter_pass", &bloom_texture_views[0], wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                &self.bloom_prefilter_pipeline, &[&self.targets.bloom_prefilter_bind_group, &self.settings_bind_group]);
+
+            // Downsample to smaller mip levels
+            for (index, bind_group) in self.targets.bloom_downsample_bind_groups.iter().enumerate() {
+                draw_full_screen_pass(encoder, "bloom_downsample_pass", &bloom_texture_views[index + 1], wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    &self.bloom_downsample_pipeline, &[bind_group]);
+            }
+
+            // Upsample and add blurred smaller mip levels back to the first one
+            for (index, bind_group) in self.targets.bloom_upsample_bind_groups.iter().enumerate().rev() {
+                draw_full_screen_pass(encoder, "bloom_upsample_pass", &bloom_texture_views[index], wgpu::LoadOp::Load,
+                    &self.bloom_upsample_pipeline, &[bind_group]);
+            }
+        }
+
+        // Composite scene and bloom (rendered to LDR texture if FXAA is used after it)
+        let color_grading_lut_view = color_grading_lut
+            .map(|v| v.create_view(&wgpu::TextureViewDescriptor { mip_level_count: Some(1), ..Default::default() }));
+        let composite_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("post_process_composite_bind_group"),
+            layout: &self.composite_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(&self.targets.hdr_texture_view) },
+                wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::TextureView(&self.targets.bloom_texture_views[0]) },
+                wgpu::BindGroupEntry { binding: 2, resource: wgpu::BindingResource::TextureView(color_grading_lut_view.as_ref().unwrap_or(&self.empty_color_grading_lut_view)) },
+                wgpu::BindGroupEntry { binding: 3, resource: wgpu::BindingResource::Sampler(&self.sampler) },
+            ],
+        });
+        let composite_view = match settings.fxaa_enabled {
+            true => &self.targets.ldr_texture_view,
+            false => output_view,
+        };
+        draw_full_screen_pass(encoder, "composite_pass", composite_view, wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+            &self.composite_pipeline, &[&composite_bind_group, &self.settings_bind_group]);
+
+        // FXAA
+        if settings.fxaa_enabled {
+            draw_full_screen_pass(encoder, "fxaa_pass", output_view, wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                &self.fxaa_pipeline, &[&self.targets.fxaa_bind_group]);
+        }
+    }
+
+    fn create_targets(
+        device: &wgpu::Device,
+        source_bind_group_layout: &wgpu::BindGroupLayout,
+        sampler: &wgpu::Sampler,
+        output_format: wgpu::TextureFormat,
+        size: (u32, u32),
+        max_bloom_mip_count: u32,
+    ) -> PostProcessTargets {
+        let (width, height) = (size.0.max(1), size.1.max(1));
+
+        // Create HDR and LDR textures
+        let hdr_texture_view = create_target_texture(device, "hdr_texture", HDR_FORMAT, (width, height), 1)
+            .create_view(&wgpu::TextureViewDescriptor::default());
+        let ldr_texture_view = create_target_texture(device, "ldr_texture", output_format, (width, height), 1)
+            .create_view(&wgpu::TextureViewDescriptor::default());
+
+        // Create bloom texture (mip levels are limited so that the smallest one is at least 1 texel)
+        let bloom_size = ((width / 2).max(1), (height / 2).max(1));
+        let bloom_mip_count = max_bloom_mip_count.clamp(1, 32 - bloom_size.0.min(bloom_size.1).leading_zeros());
+        let bloom_texture = create_target_texture(device, "bloom_texture", HDR_FORMAT, bloom_size, bloom_mip_count);
+        let bloom_texture_views = (0..bloom_mip_count)
+            .map(|mip_level| bloom_texture.create_view(&wgpu::TextureViewDescriptor {
+                label: Some("bloom_texture_view"),
+                base_mip_level: mip_level,
+                mip_level_count: Some(1),
+                ..Default::default()
+            }))
+            .collect::<Vec<wgpu::TextureView>>();
+
+        // Create bind groups reading targets
+        let create_source_bind_group = |texture_view: &wgpu::TextureView| device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("post_process_source_bind_group"),
+            layout: source_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(texture_view) },
+                wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::Sampler(sampler) },
+            ],
+        });
+        let bloom_prefilter_bind_group = create_source_bind_group(&hdr_texture_view);
+        let bloom_downsample_bind_groups = bloom_texture_views[..bloom_texture_views.len() - 1].iter().map(create_source_bind_group).collect();
+        let bloom_upsample_bind_groups = bloom_texture_views[1..].iter().map(create_source_bind_group).collect();
+        let fxaa_bind_group = create_source_bind_group(&ldr_texture_view);
+
+        PostProcessTargets {
+            hdr_texture_view,
+            ldr_texture_view,
+            bloom_texture_views,
+            bloom_prefilter_bind_group,
+            bloom_downsample_bind_groups,
+            bloom_upsample_bind_groups,
+            fxaa_bind_group,
+        }
+    }
+}
+
+fn create_target_texture(device: &wgpu::Device, label: &str, format: wgpu::TextureFormat, size: (u32, u32), mip_level_count: u32) -> wgpu::Texture {
+    device.create_texture(&wgpu::TextureDescriptor {
+        label: Some(label),
+        size: wgpu::Extent3d { width: size.0, height: size.1, depth_or_array_layers: 1 },
+        mip_level_count,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+        view_formats: &[],
+    })
+}
+
+fn get_texture_layout_entry(binding: u32) -> wgpu::BindGroupLayoutEntry {
+    wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Texture {
+            multisampled: false,
+            view_dimension: wgpu::TextureViewDimension::D2,
+            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+        },
+        count: None,
+    }
+}
+
+fn get_sampler_layout_entry(binding: u32) -> wgpu::BindGroupLayoutEntry {
+    wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+        count: None,
+    }
+}
+
+fn draw_full_screen_pass(
+    encoder: &mut wgpu::CommandEncoder,
+    label: &str,
+    view: &wgpu::TextureView,
+    load: wgpu::LoadOp<wgpu::Color>,
+    pipeline: &wgpu::RenderPipeline,
+    bind_groups: &[&wgpu::BindGroup],
+) {
+    let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: Some(label),
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view,
+            resolve_target: None,
+            ops: wgpu::Operations { load, store: wgpu::StoreOp::Store },
+        })],
+        depth_stencil_attachment: None,
+        timestamp_writes: None,
+        occlusion_query_set: None,
+    });
+
+    render_pass.set_pipeline(pipeline);
+    for (index, bind_group) in bind_groups.iter().enumerate() {
+        render_pass.set_bind_group(index as u32, bind_group, &[]);
+    }
+    render_pass.draw(0..3, 0..1);
+}
@@ -8,7 +8,12 @@ use crate::{
         RendererMaterial,
         RendererMesh,
         RendererPipeline,
+        RendererSkyboxPipeline,
+        RendererClearPipeline,
+        RendererShader,
         RendererTexture,
+        RendererVertexLayout,
+        RendererBlendState,
         Vertex
     }, 
     instance::Instance, 
@@ -19,8 +24,17 @@ use pill_engine::internal::{
     PillRenderer, 
     EntityHandle, 
     RenderQueueItem, 
+    RenderView,
     RendererError, 
-    TextureType,
+    CameraViewport,
+    CameraClearMode,
+    TextureSettings,
+    TextureData,
+    ShaderData,
+    BlendMode,
+    PostProcessSettings,
+    PostProcessShaders,
+    VsyncMode,
     MeshData, 
     MaterialTextureMap,
     TransformComponent,
@@ -31,34 +45,36 @@ use pill_engine::internal::{
     RendererMaterialHandle,
     RendererMeshHandle,
     RendererPipelineHandle,
+    RendererShaderHandle,
     RendererTextureHandle, 
     RENDER_QUEUE_KEY_ORDER,
+    POST_PROCESS_BLOOM_MIP_COUNT,
+    VSYNC_MODE,
+    MSAA_SAMPLE_COUNT,
     get_renderer_resource_handle_from_camera_component,
 };
 
 use pill_core::{ 
+    EngineError,
     PillSlotMapKey, 
     PillSlotMapKeyData, 
     PillStyle 
 };
 
 use std::{
-    iter, mem::size_of, num::NonZeroU32, ops::Range, sync::Arc
+    collections::HashMap, iter, mem::size_of, num::NonZeroU32, ops::Range, sync::Arc
 };
 
 use anyhow::{ Result };
-use log::{ info };
+use log::{ info, warn };
 
 use crate::egui::EguiRenderer;
+use crate::gpu_timer::GpuTimer;
+use crate::post_process::{ PostProcessor, HDR_FORMAT };
 
 pub const MAX_INSTANCE_PER_DRAWCALL_COUNT: usize = 10000;
 pub const INITIAL_INSTANCE_VECTOR_CAPACITY: usize = 10000;
 
-// Default resource handle - Master pipeline
-pub const MASTER_PIPELINE_HANDLE: RendererPipelineHandle = RendererPipelineHandle { 
-    0: PillSlotMapKeyData { index: 1, version: unsafe { std::num::NonZeroU32::new_unchecked(1) } } 
-};
-
 pub struct Renderer {
     pub state: State,
 }
@@ -78,36 +94,100 @@ impl PillRenderer for Renderer {
         self.state.resize(new_window_size)
     }
 
-    fn set_master_pipeline(&mut self, vertex_shader_bytes: &[u8], fragment_shader_bytes: &[u8]) -> Result<()> {
-        
+    fn set_skybox_pipeline(&mut self, vertex_shader_source: &str, fragment_shader_source: &str) -> Result<()> {
+
         // Create shaders
         let vertex_shader = wgpu::ShaderModuleDescriptor {
-            label: Some("master_vertex_shader"),
-            source: wgpu::util::make_spirv(vertex_shader_bytes),
+            label: Some("skybox_vertex_shader"),
+            source: wgpu::ShaderSource::Wgsl(vertex_shader_source.into()),
         };
         let vertex_shader = self.state.device.create_shader_module(vertex_shader);
 
         let fragment_shader = wgpu::ShaderModuleDescriptor {
-            label: Some("master_fragment_shader"),
-            source: wgpu::util::make_spirv(fragment_shader_bytes),
+            label: Some("skybox_fragment_shader"),
+            source: wgpu::ShaderSource::Wgsl(fragment_shader_source.into()),
         };
         let fragment_shader = self.state.device.create_shader_module(fragment_shader);
 
-        // Create master pipeline
-        let master_pipeline = RendererPipeline::new(
+        // Create skybox pipeline
+        let skybox_pipeline = RendererSkyboxPipeline::new(
+            &self.state.device,
+            vertex_shader,
+            fragment_shader,
+            self.state.color_format,
+            self.state.depth_format,
+            self.state.sample_count,
+            &self.state.camera_bind_group_layout,
+        )?;
+
+        self.state.skybox_pipeline = Some(skybox_pipeline);
+
+        Ok(())
+    }
+
+    fn set_clear_pipelines(&mut self, vertex_shader_source: &str, fragment_shader_source: &str) -> Result<()> {
+
+        // Create shaders
+        let vertex_shader = self.state.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("clear_vertex_shader"),
+            source: wgpu::ShaderSource::Wgsl(vertex_shader_source.into()),
+        });
+        let fragment_shader = self.state.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("clear_fragment_shader"),
+            source: wgpu::ShaderSource::Wgsl(fragment_shader_source.into()),
+        });
+
+        // Create clear pipelines
+        let clear_pipeline = RendererClearPipeline::new(
             &self.state.device,
             vertex_shader,
             fragment_shader,
             self.state.color_format,
-            Some(self.state.depth_format),
-            &[RendererMesh::data_layout_descriptor(), Instance::data_layout_descriptor()],
-        ).unwrap();
+            self.state.depth_format,
+            self.state.sample_count,
+        )?;
 
-        self.state.renderer_resource_storage.pipelines.insert(master_pipeline);
+        self.state.clear_pipeline = Some(clear_pipeline);
 
         Ok(())
     }
 
+    fn set_post_process_pipelines(&mut self, post_process_shaders: &PostProcessShaders) -> Result<()> {
+        let max_bloom_mip_count = self.state.config.get_int("POST_PROCESS_BLOOM_MIP_COUNT").unwrap_or(POST_PROCESS_BLOOM_MIP_COUNT as i64) as u32;
+        let post_processor = PostProcessor::new(
+            &self.state.device,
+            &self.state.queue,
+            post_process_shaders,
+            self.state.surface_configuration.format,
+            (self.state.surface_configuration.width, self.state.surface_configuration.height),
+            max_bloom_mip_count,
+        )?;
+
+        self.state.post_processor = Some(post_processor);
+
+        Ok(())
+    }
+
+    fn set_vsync_mode(&mut self, vsync_mode: VsyncMode) {
+        self.state.set_vsync_mode(vsync_mode)
+    }
+
+    fn set_msaa_sample_count(&mut self, sample_count: u32) -> Result<()> {
+        self.state.set_msaa_sample_count(sample_count)
+    }
+
+    fn get_vsync_mode(&self) -> VsyncMode {
+        self.state.vsync_mode
+    }
+
+    fn get_msaa_sample_count(&self) -> u32 {
+        self.state.sample_count
+    }
+
+    fn get_supported_msaa_sample_counts(&self) -> Vec<u32> {
+        self.state.supported_sample_counts.clone()
+    }
+
     fn create_mesh(&mut self, name: &str, mesh_data: &MeshData) -> Result<RendererMeshHandle> {
         let mesh = RendererMesh::new(&self.state.device, name, mesh_data)?;
         let handle = self.state.renderer_resource_storage.meshes.insert(mesh);
@@ -115,28 +195,45 @@ impl PillRenderer for Renderer {
         Ok(handle)
     }
 
-    fn create_texture(&mut self, name: &str, image_data: &image::DynamicImage, texture_type: TextureType) -> Result<RendererTextureHandle> {
-        let texture = RendererTexture::new_texture(&self.state.device, &self.state.queue, Some(name), image_data, texture_type)?;
+    fn create_texture(&mut self, name: &str, texture_data: &TextureData, settings: &TextureSettings) -> Result<RendererTextureHandle> {
+        let texture = RendererTexture::new_texture(&self.state.device, &self.state.queue, Some(name), texture_data, settings)?;
+        let handle = self.state.renderer_resource_storage.textures.insert(texture);
+
+        Ok(handle)
+    }
+
+    fn create_render_target_texture(&mut self, name: &str, width: u32, height: u32, settings: &TextureSettings) -> Result<RendererTextureHandle> {
+        let texture = RendererTexture::new_render_target_texture(&self.state.device, Some(name), (width, height), self.state.color_format, settings)?;
         let handle = self.state.renderer_resource_storage.textures.insert(texture);
 
+        // Create depth and color texture used while rendering to it
+        let render_target = create_render_targets(&self.state.device, (width, height), self.state.color_format, self.state.sample_count);
+        self.state.render_targets.insert(handle, render_target);
+
         Ok(handle)
     }
 
-    fn create_material(&mut self, name: &str, textures: &MaterialTextureMap, parameters: &MaterialParameterMap) -> Result<RendererMaterialHandle> {
-        let pipeline_handle = MASTER_PIPELINE_HANDLE;
-        let pipeline = self.state.renderer_resource_storage.pipelines.get(pipeline_handle).unwrap();
+    fn create_shader(&mut self, name: &str, shader_data: &ShaderData) -> Result<RendererShaderHandle> {
+        let shader = RendererShader::new(&self.state.device, name, shader_data)?;
+        let handle = self.state.renderer_resource_storage.shaders.insert(shader);
+
+        Ok(handle)
+    }
+
+    fn create_material(&mut self, name: &str, renderer_shader_handle: RendererShaderHandle, blend_mode: BlendMode, textures: &MaterialTextureMap, parameters: &MaterialParameterMap) -> Result<RendererMaterialHandle> {
+        // Get pipeline for shader and blend mode of the material (it is created when they are used for the first time)
+        let blend_state = RendererBlendState::from_blend_mode(blend_mode);
+        let pipeline_handle = self.state.get_pipeline(renderer_shader_handle, RendererVertexLayout::MeshInstanced, blend_state)?;
 
         let material = RendererMaterial::new(
             &self.state.device,
-            &self.state.queue,
             &self.state.renderer_resource_storage,
             name,
+            renderer_shader_handle,
             pipeline_handle,
-            &pipeline.material_texture_bind_group_layout,
             textures,
-            &pipeline.material_parameter_bind_group_layout,
             parameters,
-        ).unwrap();
+        )?;
 
         let handle = self.state.renderer_resource_storage.materials.insert(material);
 
@@ -144,21 +241,75 @@ impl PillRenderer for Renderer {
     }
 
     fn create_camera(&mut self) -> Result<RendererCameraHandle> {
-        let pipeline_handle = MASTER_PIPELINE_HANDLE;
-        let pipeline = self.state.renderer_resource_storage.pipelines.get(pipeline_handle).unwrap();
-        let camera_bind_group_layout = &pipeline.camera_bind_group_layout;
-        let camera = RendererCamera::new(&self.state.device, camera_bind_group_layout)?;
+        let camera = RendererCamera::new(&self.state.device, &self.state.camera_bind_group_layout)?;
         let handle = self.state.renderer_resource_storage.cameras.insert(camera);
 
         Ok(handle)
     }
 
+    fn update_mesh(&mut self, renderer_mesh_handle: RendererMeshHandle, mesh_data: &MeshData) -> Result<()> {
+        let mesh = self.state.renderer_resource_storage.meshes.get_mut(renderer_mesh_handle)
+            .ok_or(RendererError::RendererResourceNotFound)?;
+        mesh.update(&self.state.device, &self.state.queue, mesh_data)
+    }
+
+    fn update_shader(&mut self, renderer_shader_handle: RendererShaderHandle, shader_data: &ShaderData) -> Result<()> {
+        let name = self.state.renderer_resource_storage.shaders.get(renderer_shader_handle)
+            .ok_or(RendererError::RendererResourceNotFound)?.name.clone();
+
+        // Replace shader (handle stays the same)
+        let shader = RendererShader::new(&self.state.device, &name, shader_data)?;
+        *self.state.renderer_resource_storage.shaders.get_mut(renderer_shader_handle).unwrap() = shader;
+
+        // Recreate pipelines created for this shader (their handles stay the same so render queue keys remain valid)
+        let pipeline_keys = self.state.renderer_resource_storage.pipeline_cache.iter()
+            .filter(|(key, _)| key.0 == renderer_shader_handle)
+            .map(|(key, pipeline_handle)| (key.1, key.2, *pipeline_handle))
+            .collect::<Vec<(RendererVertexLayout, RendererBlendState, RendererPipelineHandle)>>();
+        for (vertex_layout, blend_state, pipeline_handle) in pipeline_keys {
+            let pipeline = self.state.create_pipeline(renderer_shader_handle, vertex_layout, blend_state, self.state.sample_count)?;
+            *self.state.renderer_resource_storage.pipelines.get_mut(pipeline_handle).unwrap() = pipeline;
+        }
+
+        // Recreate parameter buffers of materials using this shader (values are written when material parameters are updated)
+        let material_handles = self.state.renderer_resource_storage.materials.iter()
+            .filter(|(_, material)| material.shader_handle == renderer_shader_handle)
+            .map(|(material_handle, _)| material_handle)
+            .collect::<Vec<RendererMaterialHandle>>();
+        for material_handle in material_handles {
+            RendererMaterial::update_parameter_buffer(&self.state.device, material_handle, &mut self.state.renderer_resource_storage)?;
+        }
+
+        Ok(())
+    }
+
     fn update_material_textures(&mut self, renderer_material_handle: RendererMaterialHandle, textures: &MaterialTextureMap) -> Result<()> {
         RendererMaterial::update_textures(&self.state.device, renderer_material_handle, &mut self.state.renderer_resource_storage, textures)
     }
 
     fn update_material_parameters(&mut self, renderer_material_handle: RendererMaterialHandle, parameters: &MaterialParameterMap) -> Result<()> {
-        RendererMaterial::update_parameters(&self.state.device, &self.state.queue, renderer_material_handle, &mut self.state.renderer_resource_storage, parameters)
+        RendererMaterial::update_parameters(&self.state.queue, renderer_material_handle, &mut self.state.renderer_resource_storage, parameters)
+    }
+
+    fn update_material_blend_mode(&mut self, renderer_material_handle: RendererMaterialHandle, blend_mode: BlendMode) -> Result<()> {
+        let material = self.state.renderer_resource_storage.materials.get(renderer_material_handle)
+            .ok_or(RendererError::RendererResourceNotFound)?;
+
+        // Get pipeline with new blend state (bind groups stay the same since shader does not change)
+        let blend_state = RendererBlendState::from_blend_mode(blend_mode);
+        let pipeline_handle = self.state.get_pipeline(material.shader_handle, RendererVertexLayout::MeshInstanced, blend_state)?;
+
+        let material = self.state.renderer_resource_storage.materials.get_mut(renderer_material_handle).unwrap();
+        material.pipeline_handle = pipeline_handle;
+
+        Ok(())
+    }
+
+    fn get_material_pipeline_handle(&self, renderer_material_handle: RendererMaterialHandle) -> Result<RendererPipelineHandle> {
+        let material = self.state.renderer_resource_storage.materials.get(renderer_material_handle)
+            .ok_or(RendererError::RendererResourceNotFound)?;
+
+        Ok(material.pipeline_handle)
     }
 
     fn destroy_mesh(&mut self, renderer_mesh_handle: RendererMeshHandle) -> Result<()> {
@@ -168,11 +319,30 @@ impl PillRenderer for Renderer {
     }
 
     fn destroy_texture(&mut self, renderer_texture_handle: RendererTextureHandle) -> Result<()> {
+        self.state.skybox_bind_groups.remove(&renderer_texture_handle);
+        self.state.render_targets.remove(&renderer_texture_handle);
         self.state.renderer_resource_storage.textures.remove(renderer_texture_handle).unwrap();
 
         Ok(())
     }
 
+    fn destroy_shader(&mut self, renderer_shader_handle: RendererShaderHandle) -> Result<()> {
+        // Destroy pipelines created for this shader
+        let renderer_resource_storage = &mut self.state.renderer_resource_storage;
+        let pipeline_handles = renderer_resource_storage.pipeline_cache.iter()
+            .filter(|(key, _)| key.0 == renderer_shader_handle)
+            .map(|(_, pipeline_handle)| *pipeline_handle)
+            .collect::<Vec<RendererPipelineHandle>>();
+        for pipeline_handle in pipeline_handles {
+            renderer_resource_storage.pipelines.remove(pipeline_handle).unwrap();
+        }
+        renderer_resource_storage.pipeline_cache.retain(|key, _| key.0 != renderer_shader_handle);
+
+        self.state.renderer_resource_storage.shaders.remove(renderer_shader_handle).unwrap();
+
+        Ok(())
+    }
+
     fn destroy_material(&mut self, renderer_material_handle: RendererMaterialHandle) -> Result<()> {
         self.state.renderer_resource_storage.materials.remove(renderer_material_handle).unwrap();
 
@@ -187,18 +357,25 @@ impl PillRenderer for Renderer {
 
     fn render(
         &mut self,
-        active_camera_entity_handle: EntityHandle,
-        render_queue: &Vec<RenderQueueItem>, 
+        render_views: &[RenderView],
+        post_process_settings: &PostProcessSettings,
+        color_grading_lut_handle: Option<RendererTextureHandle>,
         camera_component_storage: &ComponentStorage<CameraComponent>,
         transform_component_storage: &ComponentStorage<TransformComponent>,
-        egui_ui: Box<dyn Fn(&egui::Context)>
     ) -> Result<(), RendererError> {
-        self.state.render(
-            active_camera_entity_handle,
-            render_queue,
+        let result = self.state.render(
+            render_views,
+            post_process_settings,
+            color_grading_lut_handle,
             camera_component_storage,
-            transform_component_storage,
-            egui_ui)
+            transform_component_storage);
+
+        // UI frame is not drawn if rendering failed before it (e.g. surface was lost), so it has to be ended here
+        if result.is_err() {
+            self.state.egui_renderer.discard_frame();
+        }
+
+        result
     }
     
     fn pass_input_to_egui(&mut self, event: &winit::event::WindowEvent) -> Result<()> {
@@ -206,6 +383,14 @@ impl PillRenderer for Renderer {
         Ok(())
     }
 
+    fn begin_egui_frame(&mut self) -> egui::Context {
+        self.state.egui_renderer.begin_frame()
+    }
+
+    fn get_gpu_frame_time(&self) -> Option<f32> {
+        self.state.gpu_timer.as_ref().and_then(|gpu_timer| gpu_timer.get_last_frame_time())
+    }
+
 }
 
 pub struct State {
@@ -217,10 +402,22 @@ pub struct State {
     queue: wgpu::Queue,
     surface_configuration: wgpu::SurfaceConfiguration,
     window_size: winit::dpi::PhysicalSize<u32>, 
-    color_format: wgpu::TextureFormat,
+    color_format: wgpu::TextureFormat, // Format scene is rendered in (HDR), it is converted to surface format by post-processing
     depth_format: wgpu::TextureFormat,
     depth_texture: RendererTexture,
+    multisampled_color_texture: Option<RendererTexture>, // Scene is rendered to it and resolved to HDR texture of post-processor if MSAA is enabled
+    render_targets: HashMap<RendererTextureHandle, (RendererTexture, Option<RendererTexture>)>, // Depth and multisampled color textures of render target textures
+    sample_count: u32,
+    supported_sample_counts: Vec<u32>,
+    vsync_mode: VsyncMode,
+    supported_present_modes: Vec<wgpu::PresentMode>,
+    camera_bind_group_layout: wgpu::BindGroupLayout,
     mesh_drawer: MeshDrawer,
+    skybox_pipeline: Option<RendererSkyboxPipeline>,
+    skybox_bind_groups: HashMap<RendererTextureHandle, wgpu::BindGroup>, // Bind groups of skybox textures used by cameras
+    clear_pipeline: Option<RendererClearPipeline>,
+    post_processor: Option<PostProcessor>,
+    gpu_timer: Option<GpuTimer>,
     // Other
     config: config::Config,
     egui_renderer: crate::egui::EguiRenderer,
@@ -258,7 +455,33 @@ impl State {
         let adapter_info = adapter.get_info();
         info!("Using GPU: {} ({:?})", adapter_info.name, adapter_info.backend);
         
-        let features = wgpu::Features::DEPTH_CLIP_CONTROL;
+        let mut features = wgpu::Features::DEPTH_CLIP_CONTROL;
+
+        // Enable GPU timestamps for profiling if adapter supports them
+        let gpu_timing_supported = adapter.features().contains(wgpu::Features::TIMESTAMP_QUERY);
+        if gpu_timing_supported {
+            features |= wgpu::Features::TIMESTAMP_QUERY;
+        }
+
+        // Enable compressed textures if adapter supports them (otherwise they are decompressed when loaded)
+        if adapter.features().contains(wgpu::Features::TEXTURE_COMPRESSION_BC) {
+            features |= wgpu::Features::TEXTURE_COMPRESSION_BC;
+        }
+
+        // Enable sample counts other than 4 if adapter supports them (otherwise only 1 and 4 are allowed)
+        let color_format = HDR_FORMAT;
+        let depth_format = RendererTexture::DEPTH_FORMAT;
+        let supported_sample_counts = match adapter.features().contains(wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES) {
+            true => {
+                features |= wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES;
+                let color_format_flags = adapter.get_texture_format_features(color_format).flags;
+                let depth_format_flags = adapter.get_texture_format_features(depth_format).flags;
+                color_format_flags.supported_sample_counts().into_iter()
+                    .filter(|sample_count| depth_format_flags.sample_count_supported(*sample_count))
+                    .collect::<Vec<u32>>()
+            },
+            false => vec![1, 4],
+        };
 
         // Create device descriptor
         let device_descriptor = wgpu::DeviceDescriptor {
@@ -271,6 +494,22 @@ impl State {
         // Create device and queue
         let (device, queue) = adapter.request_device(&device_descriptor,None).await.unwrap();
 
+        // Get vsync mode and MSAA sample count
+        let vsync_mode = config.get_str("VSYNC_MODE").ok()
+            .and_then(|v| VsyncMode::from_name(&v))
+            .unwrap_or(VsyncMode::from_name(VSYNC_MODE).expect("Critical: Invalid default vsync mode"));
+        let supported_present_modes = surface.get_capabilities(&adapter).present_modes;
+
+        let requested_sample_count = config.get_int("MSAA_SAMPLE_COUNT").unwrap_or(MSAA_SAMPLE_COUNT as i64).max(1) as u32;
+        let sample_count = match supported_sample_counts.contains(&requested_sample_count) {
+            true => requested_sample_count,
+            false => {
+                let sample_count = supported_sample_counts.iter().copied().filter(|v| *v <= requested_sample_count).max().unwrap_or(1);
+                warn!("MSAA sample count {} is not supported, using {} instead", requested_sample_count, sample_count);
+                sample_count
+            },
+        };
+
         // Specify surface configuration
         let format = wgpu::TextureFormat::Rgba8UnormSrgb;
         let surface_configuration = wgpu::SurfaceConfiguration {
@@ -279,7 +518,7 @@ impl State {
             width: window_size.width,
             height: window_size.height,
             desired_maximum_frame_latency: 2,
-            present_mode: wgpu::PresentMode::Mailbox, // Defines how to sync the surface with the display
+            present_mode: get_present_mode(vsync_mode, &supported_present_modes), // Defines how to sync the surface with the display
             alpha_mode: wgpu::CompositeAlphaMode::Auto,
             view_formats: vec![format],
         };
@@ -291,18 +530,23 @@ impl State {
         let renderer_resource_storage = RendererResourceStorage::new(&config);
 
         // Create depth and color texture
-        let depth_texture = RendererTexture::new_depth_texture(
-            &device, 
-            &surface_configuration, 
-            "depth_texture"
-        ).unwrap();
+        let (depth_texture, multisampled_color_texture) = create_render_targets(&device, (surface_configuration.width, surface_configuration.height), color_format, sample_count);
 
-        let color_format = surface_configuration.format;
-        let depth_format = wgpu::TextureFormat::Depth32Float;
+        // Create camera bind group layout shared by all pipelines
+        let camera_bind_group_layout = RendererCamera::create_bind_group_layout(&device);
 
         // Create drawing state
         let mesh_drawer = MeshDrawer::new(&device, MAX_INSTANCE_PER_DRAWCALL_COUNT as u32);
 
+        // Create GPU timer
+        let gpu_timer = match gpu_timing_supported {
+            true => Some(GpuTimer::new(&device, &queue)),
+            false => { 
+                info!("GPU timestamp queries not supported, GPU profiling disabled");
+                None
+            },
+        };
+
         let egui_renderer = EguiRenderer::new(
             &device,
             surface_configuration.format, 
@@ -324,34 +568,134 @@ impl State {
             color_format,
             depth_format,
             depth_texture,
+            multisampled_color_texture,
+            render_targets: HashMap::<RendererTextureHandle, (RendererTexture, Option<RendererTexture>)>::new(),
+            sample_count,
+            supported_sample_counts,
+            vsync_mode,
+            supported_present_modes,
+            camera_bind_group_layout,
             mesh_drawer,
+            skybox_pipeline: None,
+            skybox_bind_groups: HashMap::<RendererTextureHandle, wgpu::BindGroup>::new(),
+            clear_pipeline: None,
+            post_processor: None,
+            gpu_timer,
             // Other
             config,
             egui_renderer
         }
     }
 
+    // Returns pipeline for shader, vertex layout and blend state, pipeline is created if it does not exist yet
+    fn get_pipeline(&mut self, shader_handle: RendererShaderHandle, vertex_layout: RendererVertexLayout, blend_state: RendererBlendState) -> Result<RendererPipelineHandle> {
+        if let Some(pipeline_handle) = self.renderer_resource_storage.pipeline_cache.get(&(shader_handle, vertex_layout, blend_state)) {
+            return Ok(*pipeline_handle);
+        }
+
+        let pipeline = self.create_pipeline(shader_handle, vertex_layout, blend_state, self.sample_count)?;
+        let pipeline_handle = self.renderer_resource_storage.pipelines.insert(pipeline);
+        self.renderer_resource_storage.pipeline_cache.insert((shader_handle, vertex_layout, blend_state), pipeline_handle);
+
+        Ok(pipeline_handle)
+    }
+
+    fn create_pipeline(&self, shader_handle: RendererShaderHandle, vertex_layout: RendererVertexLayout, blend_state: RendererBlendState, sample_count: u32) -> Result<RendererPipeline> {
+        let shader = self.renderer_resource_storage.shaders.get(shader_handle).ok_or(RendererError::RendererResourceNotFound)?;
+        RendererPipeline::new(
+            &self.device,
+            shader_handle,
+            shader,
+            vertex_layout,
+            blend_state,
+            self.color_format,
+            Some(self.depth_format),
+            sample_count,
+            &self.camera_bind_group_layout,
+        )
+    }
+
+    fn set_vsync_mode(&mut self, vsync_mode: VsyncMode) {
+        self.vsync_mode = vsync_mode;
+        self.surface_configuration.present_mode = get_present_mode(vsync_mode, &self.supported_present_modes);
+        self.surface.configure(&self.device, &self.surface_configuration);
+    }
+
+    fn set_msaa_sample_count(&mut self, sample_count: u32) -> Result<()> {
+        if !self.supported_sample_counts.contains(&sample_count) {
+            return Err(EngineError::UnsupportedMsaaSampleCount(sample_count.to_string(), self.supported_sample_counts.clone()).into());
+        }
+        if self.sample_count == sample_count {
+            return Ok(());
+        }
+
+        // Create all pipelines with new sample count first so that nothing is changed if any of them fails
+        let pipeline_keys = self.renderer_resource_storage.pipeline_cache.iter()
+            .map(|(key, pipeline_handle)| (*key, *pipeline_handle))
+            .collect::<Vec<((RendererShaderHandle, RendererVertexLayout, RendererBlendState), RendererPipelineHandle)>>();
+        let mut pipelines = Vec::<(RendererPipelineHandle, RendererPipeline)>::with_capacity(pipeline_keys.len());
+        for ((shader_handle, vertex_layout, blend_state), pipeline_handle) in pipeline_keys {
+            self.renderer_resource_storage.pipelines.get(pipeline_handle).ok_or(RendererError::RendererResourceNotFound)?;
+            pipelines.push((pipeline_handle, self.create_pipeline(shader_handle, vertex_layout, blend_state, sample_count)?));
+        }
+
+        // Get sizes of render target textures
+        let render_target_sizes = self.render_targets.keys()
+            .map(|texture_handle| {
+                let size = self.renderer_resource_storage.textures.get(*texture_handle).ok_or(RendererError::RendererResourceNotFound)?.texture.size();
+                Ok((*texture_handle, (size.width, size.height)))
+            })
+            .collect::<Result<Vec<(RendererTextureHandle, (u32, u32))>>>()?;
+
+        // Replace pipelines (their handles stay the same so materials and render queue keys remain valid)
+        self.sample_count = sample_count;
+        for (pipeline_handle, pipeline) in pipelines {
+            if let Some(v) = self.renderer_resource_storage.pipelines.get_mut(pipeline_handle) {
+                *v = pipeline;
+            }
+        }
+
+        // Recreate render targets
+        let (depth_texture, multisampled_color_texture) = create_render_targets(&self.device, (self.surface_configuration.width, self.surface_configuration.height), self.color_format, sample_count);
+        self.depth_texture = depth_texture;
+        self.multisampled_color_texture = multisampled_color_texture;
+        for (texture_handle, size) in render_target_sizes {
+            self.render_targets.insert(texture_handle, create_render_targets(&self.device, size, self.color_format, sample_count));
+        }
+
+        if let Some(skybox_pipeline) = self.skybox_pipeline.as_mut() {
+            skybox_pipeline.set_sample_count(&self.device, self.color_format, self.depth_format, sample_count, &self.camera_bind_group_layout);
+        }
+
+        if let Some(clear_pipeline) = self.clear_pipeline.as_mut() {
+            clear_pipeline.set_sample_count(&self.device, self.color_format, self.depth_format, sample_count);
+        }
+
+        Ok(())
+    }
+
     fn resize(&mut self, new_window_size: winit::dpi::PhysicalSize<u32>) {
         if new_window_size.width > 0 && new_window_size.height > 0 {
             self.window_size = new_window_size;
             self.surface_configuration.width = new_window_size.width;
             self.surface_configuration.height = new_window_size.height;
             self.surface.configure(&self.device, &self.surface_configuration);
-            self.depth_texture = RendererTexture::new_depth_texture(
-                &self.device,
-                &self.surface_configuration,
-                "depth_texture",
-            ).unwrap();
+            let (depth_texture, multisampled_color_texture) = create_render_targets(&self.device, (new_window_size.width, new_window_size.height), self.color_format, self.sample_count);
+            self.depth_texture = depth_texture;
+            self.multisampled_color_texture = multisampled_color_texture;
+            if let Some(post_processor) = self.post_processor.as_mut() {
+                post_processor.resize(&self.device, (new_window_size.width, new_window_size.height));
+            }
         }
     }
   
     fn render(
         &mut self, 
-        active_camera_entity_handle: EntityHandle,
-        render_queue: &Vec<RenderQueueItem>, 
+        render_views: &[RenderView],
+        post_process_settings: &PostProcessSettings,
+        color_grading_lut_handle: Option<RendererTextureHandle>,
         camera_component_storage: &ComponentStorage<CameraComponent>,
         transform_component_storage: &ComponentStorage<TransformComponent>,
-        egui_ui: Box<dyn Fn(&egui::Context)>
     ) -> Result<(), RendererError> { 
     
         // Get frame or return mapped error if failed
@@ -368,55 +712,132 @@ impl State {
 
         let view = frame.texture.create_view(&wgpu::TextureViewDescriptor::default());
 
-        // Get active camera and update it
-        let camera_storage = camera_component_storage.data.get(active_camera_entity_handle.data().index as usize).unwrap();
-        let active_camera_component = camera_storage.as_ref().unwrap();
-        let renderer_camera = self.renderer_resource_storage.cameras.get_mut(get_renderer_resource_handle_from_camera_component(active_camera_component)).ok_or(RendererError::RendererResourceNotFound)?;
-        let camera_transform_storage = transform_component_storage.data.get(active_camera_entity_handle.data().index as usize).unwrap();
-        let active_camera_transform_component = camera_transform_storage.as_ref().unwrap();
-        renderer_camera.update(&self.queue, active_camera_component, active_camera_transform_component);
-        let renderer_camera = self.renderer_resource_storage.cameras.get(get_renderer_resource_handle_from_camera_component(active_camera_component)).unwrap();
-        let clear_color = active_camera_component.clear_color;
+        // Read GPU timings of previous frames
+        if let Some(gpu_timer) = self.gpu_timer.as_mut() {
+            gpu_timer.collect(&self.device);
+        }
+
+        // Update cameras of render views and create bind groups of their skyboxes if they do not exist yet
+        for render_view in render_views.iter() {
+            let camera_component = get_component(camera_component_storage, render_view.camera_entity_handle);
+            let transform_component = get_component(transform_component_storage, render_view.camera_entity_handle);
+            let renderer_camera = self.renderer_resource_storage.cameras.get_mut(get_renderer_resource_handle_from_camera_component(camera_component)).ok_or(RendererError::RendererResourceNotFound)?;
+            renderer_camera.update(&self.queue, camera_component, transform_component);
+
+            if let (Some(skybox_texture_handle), Some(skybox_pipeline)) = (render_view.skybox_texture_handle, self.skybox_pipeline.as_ref()) {
+                if !self.skybox_bind_groups.contains_key(&skybox_texture_handle) {
+                    let skybox_texture = self.renderer_resource_storage.textures.get(skybox_texture_handle).ok_or(RendererError::RendererResourceNotFound)?;
+                    let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                        layout: &skybox_pipeline.texture_bind_group_layout,
+                        entries: &[
+                            wgpu::BindGroupEntry {
+                                binding: 0,
+                                resource: wgpu::BindingResource::TextureView(&skybox_texture.texture_view),
+                            },
+                            wgpu::BindGroupEntry {
+                                binding: 1,
+                                resource: wgpu::BindingResource::Sampler(&skybox_texture.sampler),
+                            },
+                        ],
+                        label: Some("skybox_bind_group"),
+                    });
+                    self.skybox_bind_groups.insert(skybox_texture_handle, bind_group);
+                }
+            }
+        }
+
+        // Load instances of all render views to instance buffer
+        self.mesh_drawer.write_instances(&self.device, &self.queue, render_views, transform_component_storage);
         
         // Build a command buffer that can be sent to the GPU
         let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
             label: Some("render_encoder"),
         });
 
-        { // Additional scope to release mutable borrow of encoder done by begin_render_pass
-            
-            // Create color attachment
-            let color_attachment = wgpu::RenderPassColorAttachment {
-                view: &view, // Specifies what texture to save the colors to
-                resolve_target: None, // Specifies what texture will receive the resolved output
-                ops: wgpu::Operations { // Specifies what to do with the colors on the screen
-                    load: wgpu::LoadOp::Clear(wgpu::Color { r: clear_color.x as f64, g: clear_color.y as f64, b: clear_color.z as f64, a: 1.0, } ), // Specifies how to handle colors stored from the previous frame
-                    store: wgpu::StoreOp::Store,
-                },
+        let post_processor = self.post_processor.as_ref().expect("Critical: Post-process pipelines not set");
+
+        // Get index of the first instance of each render view in instance buffer
+        let mut first_instances = Vec::<u32>::with_capacity(render_views.len());
+        let mut instance_count = 0;
+        for render_view in render_views.iter() {
+            first_instances.push(instance_count);
+            instance_count += render_view.render_queue.len() as u32;
+        }
+
+        // Render views of render target textures, each render target is drawn in its own pass (engine orders views so that render targets are drawn before views that sample them)
+        let mut is_first_render_pass = true;
+        let mut render_view_group_start = 0;
+        for render_view_group in render_views.chunk_by(|a, b| a.render_target_texture_handle == b.render_target_texture_handle) {
+            let render_view_group_range = render_view_group_start..render_view_group_start + render_view_group.len();
+            render_view_group_start = render_view_group_range.end;
+            let render_target_texture_handle = match render_view_group[0].render_target_texture_handle {
+                Some(v) => v,
+                None => continue,
             };
 
-            // Create depth attachment
-            let depth_stencil_attachment = wgpu::RenderPassDepthStencilAttachment {
-                view: &self.depth_texture.texture_view,
-                depth_ops: Some(wgpu::Operations {
-                    load: wgpu::LoadOp::Clear(1.0),
-                    store: wgpu::StoreOp::Store,
-                }),
-                stencil_ops: None,
+            // Get render target texture and textures used while rendering to it
+            let render_target_texture = self.renderer_resource_storage.textures.get(render_target_texture_handle).ok_or(RendererError::RendererResourceNotFound)?;
+            let (depth_texture, multisampled_color_texture) = self.render_targets.get(&render_target_texture_handle).ok_or(RendererError::RendererResourceNotFound)?;
+            let render_target_size = render_target_texture.texture.size();
+
+            // GPU time is measured from the beginning of the first render pass to the end of the UI render pass
+            let timestamp_writes = match is_first_render_pass {
+                true => self.gpu_timer.as_ref().map(|gpu_timer| gpu_timer.get_beginning_timestamp_writes()),
+                false => None,
             };
+            is_first_render_pass = false;
+
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("render_target_render_pass"),
+                color_attachments: &[Some(get_color_attachment(&render_target_texture.texture_view, multisampled_color_texture.as_ref()))],
+                depth_stencil_attachment: Some(get_depth_stencil_attachment(depth_texture)),
+                timestamp_writes,
+                occlusion_query_set: None,
+            });
+
+            self.record_render_views(
+                &mut render_pass,
+                render_view_group.iter().zip(first_instances[render_view_group_range].iter().copied()),
+                (render_target_size.width, render_target_size.height),
+                camera_component_storage,
+            );
+        }
 
-            self.mesh_drawer.record_draw_commands(
-                &self.queue, 
-                &mut encoder, 
-                &self.renderer_resource_storage, 
-                color_attachment, 
-                depth_stencil_attachment, 
-                &renderer_camera,
-                &render_queue, 
-                &transform_component_storage
-            )
+        { // Additional scope to release mutable borrow of encoder done by begin_render_pass
+
+            // Scene is rendered to HDR texture, with MSAA it is rendered to multisampled texture and resolved to HDR texture
+            let color_attachment = get_color_attachment(post_processor.get_hdr_texture_view(), self.multisampled_color_texture.as_ref());
+            let depth_stencil_attachment = get_depth_stencil_attachment(&self.depth_texture);
+
+            let timestamp_writes = match is_first_render_pass {
+                true => self.gpu_timer.as_ref().map(|gpu_timer| gpu_timer.get_beginning_timestamp_writes()),
+                false => None,
+            };
+
+            // Start encoding render pass (all render views rendering to the window are drawn in one pass)
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor { // Use the encoder to create a RenderPass
+                label: Some("render_pass"),
+                color_attachments: &[Some(color_attachment)],
+                depth_stencil_attachment: Some(depth_stencil_attachment),
+                timestamp_writes,
+                occlusion_query_set: None,
+            });
+
+            self.record_render_views(
+                &mut render_pass,
+                render_views.iter().zip(first_instances.iter().copied()).filter(|(render_view, _)| render_view.render_target_texture_handle.is_none()),
+                (self.surface_configuration.width, self.surface_configuration.height),
+                camera_component_storage,
+            );
         }  
 
+        // Post-process HDR image of the scene into the surface
+        let color_grading_lut = match color_grading_lut_handle {
+            Some(v) => Some(&self.renderer_resource_storage.textures.get(v).ok_or(RendererError::RendererResourceNotFound)?.texture),
+            None => None,
+        };
+        post_processor.record_commands(&self.device, &self.queue, &mut encoder, post_process_settings, color_grading_lut, &view);
+
         // Render egui UI
         self.egui_renderer.draw(
             &self.device,
@@ -427,91 +848,231 @@ impl State {
                 size_in_pixels: [self.surface_configuration.width, self.surface_configuration.height],
                 pixels_per_point: self.egui_renderer.window_scale_factor,
             },
-            egui_ui, 
+            self.gpu_timer.as_ref().map(|gpu_timer| gpu_timer.get_end_timestamp_writes()),
         );
 
+        // Resolve GPU timings
+        if let Some(gpu_timer) = self.gpu_timer.as_mut() {
+            gpu_timer.resolve(&mut encoder);
+        }
+
         self.queue.submit(iter::once(encoder.finish())); // Finish command buffer and submit it to the GPU's render queue
         frame.present();
 
+        // Start reading GPU timings back
+        if let Some(gpu_timer) = self.gpu_timer.as_mut() {
+            gpu_timer.request_readback();
+        }
+
         Ok(())
     }
+
+    // Records drawing of render views to viewports of their cameras, first instance is index of the first instance of the view in instance buffer
+    fn record_render_views<'a>(
+        &'a self,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        render_views: impl Iterator<Item = (&'a RenderView, u32)>,
+        target_size: (u32, u32),
+        camera_component_storage: &ComponentStorage<CameraComponent>,
+    ) {
+        let clear_pipeline = self.clear_pipeline.as_ref().expect("Critical: Clear pipelines not set");
+
+        for (render_view, first_instance) in render_views {
+            let camera_component = get_component(camera_component_storage, render_view.camera_entity_handle);
+            let renderer_camera = self.renderer_resource_storage.cameras.get(get_renderer_resource_handle_from_camera_component(camera_component)).unwrap();
+
+            // Restrict drawing to viewport of the camera (skip cameras with empty viewport)
+            let (x, y, width, height) = get_viewport_rect(&camera_component.viewport, target_size.0, target_size.1);
+            if width == 0 || height == 0 {
+                continue;
+            }
+            render_pass.set_viewport(x as f32, y as f32, width as f32, height as f32, 0.0, 1.0);
+            render_pass.set_scissor_rect(x, y, width, height);
+
+            // Clear viewport
+            match camera_component.clear_mode {
+                CameraClearMode::ColorAndDepth => {
+                    let clear_color = camera_component.clear_color;
+                    render_pass.set_blend_constant(wgpu::Color { r: clear_color.x as f64, g: clear_color.y as f64, b: clear_color.z as f64, a: 1.0, });
+                    render_pass.set_pipeline(&clear_pipeline.color_and_depth_render_pipeline);
+                    render_pass.draw(0..3, 0..1);
+                },
+                CameraClearMode::Depth => {
+                    render_pass.set_pipeline(&clear_pipeline.depth_render_pipeline);
+                    render_pass.draw(0..3, 0..1);
+                },
+                CameraClearMode::None => {},
+            }
+
+            // Get skybox pipeline and bind group if skybox should be drawn (it replaces clear color)
+            let skybox = match (camera_component.clear_mode, render_view.skybox_texture_handle, self.skybox_pipeline.as_ref()) {
+                (CameraClearMode::ColorAndDepth, Some(skybox_texture_handle), Some(skybox_pipeline)) => 
+                    self.skybox_bind_groups.get(&skybox_texture_handle).map(|v| (&skybox_pipeline.render_pipeline, v)),
+                _ => None,
+            };
+
+            self.mesh_drawer.record_draw_commands(
+                render_pass,
+                &self.renderer_resource_storage, 
+                renderer_camera,
+                skybox,
+                &render_view.render_queue, 
+                first_instance,
+            );
+        }
+    }
 }
 
-pub struct MeshDrawer {
-    current_rendering_order: u8,
-    current_pipeline_handle: Option<RendererPipelineHandle>,
-    current_material_handle: Option<RendererMaterialHandle>,
-    current_mesh_handle: Option<RendererMeshHandle>,
-    current_mesh_index_count: u32,
+// Returns component of the entity from storage
+fn get_component<T>(component_storage: &ComponentStorage<T>, entity_handle: EntityHandle) -> &T {
+    component_storage.data.get(entity_handle.data().index as usize).unwrap().as_ref().unwrap()
+}
+
+// Returns viewport in pixels clamped to render target size
+fn get_viewport_rect(viewport: &CameraViewport, target_width: u32, target_height: u32) -> (u32, u32, u32, u32) {
+    let x = ((viewport.x.clamp(0.0, 1.0) * target_width as f32) as u32).min(target_width);
+    let y = ((viewport.y.clamp(0.0, 1.0) * target_height as f32) as u32).min(target_height);
+    let width = ((viewport.width.max(0.0) * target_width as f32).round() as u32).min(target_width - x);
+    let height = ((viewport.height.max(0.0) * target_height as f32).round() as u32).min(target_height - y);
+
+    (x, y, width, height)
+}
+
+// Returns color attachment cleared before rendering, with MSAA scene is rendered to multisampled texture and resolved to target texture
+fn get_color_attachment<'a>(target_texture_view: &'a wgpu::TextureView, multisampled_color_texture: Option<&'a RendererTexture>) -> wgpu::RenderPassColorAttachment<'a> {
+    let (view, resolve_target, store) = match multisampled_color_texture {
+        Some(multisampled_color_texture) => (&multisampled_color_texture.texture_view, Some(target_texture_view), wgpu::StoreOp::Discard),
+        None => (target_texture_view, None, wgpu::StoreOp::Store),
+    };
+
+    wgpu::RenderPassColorAttachment {
+        view, // Specifies what texture to save the colors to
+        resolve_target, // Specifies what texture will receive the resolved output
+        ops: wgpu::Operations { // Specifies what to do with the colors on the screen
+            load: wgpu::LoadOp::Clear(wgpu::Color::BLACK), // Whole texture is cleared only here, viewports of cameras are cleared by clear pipeline
+            store,
+        },
+    }
+}
 
-    max_instance_count: u32,
+fn get_depth_stencil_attachment(depth_texture: &RendererTexture) -> wgpu::RenderPassDepthStencilAttachment<'_> {
+    wgpu::RenderPassDepthStencilAttachment {
+        view: &depth_texture.texture_view,
+        depth_ops: Some(wgpu::Operations {
+            load: wgpu::LoadOp::Clear(1.0),
+            store: wgpu::StoreOp::Store,
+        }),
+        stencil_ops: None,
+    }
+}
+
+// Creates depth texture and multisampled color texture (only if MSAA is enabled) matching size of the surface or render target texture
+fn create_render_targets(
+    device: &wgpu::Device, 
+    size: (u32, u32), 
+    color_format: wgpu::TextureFormat, 
+    sample_count: u32
+) -> (RendererTexture, Option<RendererTexture>) {
+    let depth_texture = RendererTexture::new_depth_texture(device, size, sample_count, "depth_texture").unwrap();
+    let multisampled_color_texture = match sample_count > 1 {
+        true => Some(RendererTexture::new_multisampled_texture(device, size, color_format, sample_count, "multisampled_color_texture").unwrap()),
+        false => None,
+    };
+
+    (depth_texture, multisampled_color_texture)
+}
+
+// Returns present mode for vsync mode, falls back to modes that are always supported
+fn get_present_mode(vsync_mode: VsyncMode, supported_present_modes: &[wgpu::PresentMode]) -> wgpu::PresentMode {
+    let preferred_present_modes: &[wgpu::PresentMode] = match vsync_mode {
+        VsyncMode::On => &[wgpu::PresentMode::Fifo],
+        VsyncMode::Off => &[wgpu::PresentMode::Mailbox, wgpu::PresentMode::Immediate],
+        VsyncMode::Adaptive => &[wgpu::PresentMode::FifoRelaxed],
+    };
+
+    preferred_present_modes.iter().copied()
+        .find(|present_mode| supported_present_modes.contains(present_mode))
+        .unwrap_or(wgpu::PresentMode::Fifo) // Fifo is supported everywhere
+}
+
+pub struct MeshDrawer {
+    max_instance_count: u32, // Maximal number of instances drawn with one draw call
     instances: Vec::<Instance>,
     instance_buffer: wgpu::Buffer,
-    instance_range: Range<u32>,
+    instance_buffer_capacity: usize,
 }
 
 impl MeshDrawer {
     pub fn new(device: &wgpu::Device, max_instance_count: u32) -> Self {
 
         // Create instance buffer
-        let buffer_size = (size_of::<Instance>() * max_instance_count as usize) as u64;
-        let instance_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("instance_buffer"),
-            size: buffer_size,
-            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
+        let instance_buffer = Self::create_instance_buffer(device, max_instance_count as usize);
 
         MeshDrawer {
-            current_rendering_order: 0,
-            current_pipeline_handle: None,
-            current_material_handle: None,
-            current_mesh_handle: None,
-            current_mesh_index_count: 0,
-
             max_instance_count,
             instances: Vec::<Instance>::with_capacity(INITIAL_INSTANCE_VECTOR_CAPACITY), 
             instance_buffer,
-            instance_range: 0..0, // Start inclusive, end exclusive (e.g. 0..3 means indices 0, 1, 2.  e.g. 5..7 means indices 5, 6)
+            instance_buffer_capacity: max_instance_count as usize,
         }
     }
 
-    pub fn record_draw_commands(
+    // Loads instance data of all render views to instance buffer, instances of each view follow instances of the previous one
+    pub fn write_instances(
         &mut self, 
-        // Resources
+        device: &wgpu::Device, 
         queue: &wgpu::Queue, 
-        encoder: &mut wgpu::CommandEncoder, 
-        renderer_resource_storage: &RendererResourceStorage, 
-        color_attachment: wgpu::RenderPassColorAttachment, 
-        depth_stencil_attachment: wgpu::RenderPassDepthStencilAttachment,
-        // Rendring data
-        camera: &RendererCamera,
-        render_queue: &Vec::<RenderQueueItem>, 
-        transform_component_storage: &ComponentStorage<TransformComponent>
+        render_views: &[RenderView], 
+        transform_component_storage: &ComponentStorage<TransformComponent>,
     ) {
-        // Prepare instance data and load it to buffer
-        let render_queue_iter = render_queue.iter();
-        for render_queue_item in render_queue_iter {
-            let transform_slot =  transform_component_storage.data.get(render_queue_item.entity_index as usize).unwrap();
-            let transform_component = transform_slot.as_ref().unwrap();
-            self.instances.push(Instance::new(transform_component));
+        // Prepare instance data
+        for render_view in render_views.iter() {
+            for render_queue_item in render_view.render_queue.iter() {
+                let transform_slot = transform_component_storage.data.get(render_queue_item.entity_index as usize).unwrap();
+                let transform_component = transform_slot.as_ref().unwrap();
+                self.instances.push(Instance::new(transform_component));
+            }
+        }
+
+        // Grow instance buffer if instances do not fit in it
+        if self.instances.len() > self.instance_buffer_capacity {
+            self.instance_buffer_capacity = self.instances.len().next_power_of_two();
+            self.instance_buffer = Self::create_instance_buffer(device, self.instance_buffer_capacity);
         }
+
         queue.write_buffer(&self.instance_buffer, 0, bytemuck::cast_slice(&self.instances)); // Update instance buffer
         self.instances.clear();
+    }
 
-        // Start encoding render pass
-        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor { // Use the encoder to create a RenderPass
-            label: Some("render_pass"),
-            color_attachments: &[Some(color_attachment)],
-            depth_stencil_attachment: Some(depth_stencil_attachment),
-            timestamp_writes: None,
-            occlusion_query_set: None,
-        });
+    // Records drawing of render queue of one render view, first instance is index of its first instance in instance buffer
+    pub fn record_draw_commands<'a>(
+        &'a self, 
+        render_pass: &mut wgpu::RenderPass<'a>,
+        // Resources
+        renderer_resource_storage: &'a RendererResourceStorage, 
+        // Rendring data
+        camera: &'a RendererCamera,
+        skybox: Option<(&'a wgpu::RenderPipeline, &'a wgpu::BindGroup)>,
+        render_queue: &[RenderQueueItem], 
+        first_instance: u32,
+    ) {
+        // Draw skybox first so that it is behind everything (it covers whole viewport with one triangle)
+        if let Some((skybox_pipeline, skybox_bind_group)) = skybox {
+            render_pass.set_pipeline(skybox_pipeline);
+            render_pass.set_bind_group(0, skybox_bind_group, &[]);
+            render_pass.set_bind_group(1, &camera.bind_group, &[]);
+            render_pass.draw(0..3, 0..1);
+        }
 
         render_pass.set_vertex_buffer(1, self.instance_buffer.slice(..)); // Set instance buffer
 
-        let render_queue_iter = render_queue.iter();
-        for render_queue_item in render_queue_iter {
+        let mut current_rendering_order = RENDER_QUEUE_KEY_ORDER.max as u8;
+        let mut current_pipeline_handle: Option<RendererPipelineHandle> = None;
+        let mut current_material_handle: Option<RendererMaterialHandle> = None;
+        let mut current_mesh_handle: Option<RendererMeshHandle> = None;
+        let mut current_mesh_index_count: u32 = 0;
+        let mut instance_range = first_instance..first_instance; // Start inclusive, end exclusive (e.g. 0..3 means indices 0, 1, 2.  e.g. 5..7 means indices 5, 6)
+
+        for render_queue_item in render_queue.iter() {
             
             let render_queue_key_fields = pill_engine::internal::decompose_render_queue_key(render_queue_item.key).unwrap();
 
@@ -520,30 +1081,30 @@ impl MeshDrawer {
             let renderer_mesh_handle = RendererMeshHandle::new(render_queue_key_fields.mesh_index.into(), NonZeroU32::new(render_queue_key_fields.mesh_version.into()).unwrap());
 
             // Check rendering order
-            if self.current_rendering_order > render_queue_key_fields.order {
-                if self.get_accumulated_instance_count() > 0 {
-                    render_pass.draw_indexed(0..self.current_mesh_index_count, 0, self.instance_range.clone());         
-                    self.instance_range = self.instance_range.end..self.instance_range.end;
+            if current_rendering_order > render_queue_key_fields.order {
+                if get_instance_count(&instance_range) > 0 {
+                    render_pass.draw_indexed(0..current_mesh_index_count, 0, instance_range.clone());         
+                    instance_range = instance_range.end..instance_range.end;
                 }
                 // Set new order
-                self.current_rendering_order = render_queue_key_fields.order;
+                current_rendering_order = render_queue_key_fields.order;
             }
 
             // Check material
-            if self.current_material_handle != Some(renderer_material_handle) {
+            if current_material_handle != Some(renderer_material_handle) {
                 // Render accumulated instances
-                if self.get_accumulated_instance_count() > 0 {
-                    render_pass.draw_indexed(0..self.current_mesh_index_count, 0, self.instance_range.clone());            
-                    self.instance_range = self.instance_range.end..self.instance_range.end;
+                if get_instance_count(&instance_range) > 0 {
+                    render_pass.draw_indexed(0..current_mesh_index_count, 0, instance_range.clone());            
+                    instance_range = instance_range.end..instance_range.end;
                 }
                 // Set new material
-                self.current_material_handle = Some(renderer_material_handle);
-                let material = renderer_resource_storage.materials.get(self.current_material_handle.unwrap()).unwrap();
+                current_material_handle = Some(renderer_material_handle);
+                let material = renderer_resource_storage.materials.get(renderer_material_handle).unwrap();
                
                 // Set pipeline if new material is using different one
-                if self.current_pipeline_handle != Some(material.pipeline_handle) {
-                    self.current_pipeline_handle = Some(material.pipeline_handle);
-                    let pipeline = renderer_resource_storage.pipelines.get( self.current_pipeline_handle.unwrap()).unwrap();
+                if current_pipeline_handle != Some(material.pipeline_handle) {
+                    current_pipeline_handle = Some(material.pipeline_handle);
+                    let pipeline = renderer_resource_storage.pipelines.get(material.pipeline_handle).unwrap();
                     render_pass.set_pipeline(&pipeline.render_pipeline);
                 }
 
@@ -553,47 +1114,47 @@ impl MeshDrawer {
             }
 
             // Check mesh
-            if self.current_mesh_handle != Some(renderer_mesh_handle) {
+            if current_mesh_handle != Some(renderer_mesh_handle) {
                 // Render accumulated instances
-                if self.get_accumulated_instance_count() > 0 {
-                    render_pass.draw_indexed(0..self.current_mesh_index_count, 0, self.instance_range.clone());      
-                    self.instance_range = self.instance_range.end..self.instance_range.end; 
+                if get_instance_count(&instance_range) > 0 {
+                    render_pass.draw_indexed(0..current_mesh_index_count, 0, instance_range.clone());      
+                    instance_range = instance_range.end..instance_range.end; 
                 }
                 // Set new mesh
-                self.current_mesh_handle = Some(renderer_mesh_handle);               
-                let mesh = renderer_resource_storage.meshes.get(self.current_mesh_handle.unwrap()).unwrap();
-                self.current_mesh_index_count = mesh.index_count;
+                current_mesh_handle = Some(renderer_mesh_handle);               
+                let mesh = renderer_resource_storage.meshes.get(renderer_mesh_handle).unwrap();
+                current_mesh_index_count = mesh.index_count;
                 render_pass.set_vertex_buffer(0, mesh.vertex_buffer.slice(..)); 
                 render_pass.set_index_buffer(mesh.index_buffer.slice(..), wgpu::IndexFormat::Uint32); 
             }
 
             // Check max instance per draw call count
-            if self.get_accumulated_instance_count() >= self.max_instance_count {
-                render_pass.draw_indexed(0..self.current_mesh_index_count, 0, self.instance_range.clone());      
-                self.instance_range = self.instance_range.end..self.instance_range.end; 
+            if get_instance_count(&instance_range) >= self.max_instance_count {
+                render_pass.draw_indexed(0..current_mesh_index_count, 0, instance_range.clone());      
+                instance_range = instance_range.end..instance_range.end; 
             } 
-            else {
-                // Add new instance
-                self.instance_range = self.instance_range.start..self.instance_range.end + 1;
-            }
+
+            // Add new instance
+            instance_range = instance_range.start..instance_range.end + 1;
         }
 
         // End of render queue so draw remaining saved objects
-        if self.get_accumulated_instance_count() > 0 {
-            render_pass.draw_indexed(0..self.current_mesh_index_count, 0, self.instance_range.clone());    
-            self.instance_range = self.instance_range.end..self.instance_range.end; 
+        if get_instance_count(&instance_range) > 0 {
+            render_pass.draw_indexed(0..current_mesh_index_count, 0, instance_range.clone());    
         }
-
-        // Reset state of mesh drawer
-        self.current_rendering_order = RENDER_QUEUE_KEY_ORDER.max as u8;
-        self.current_pipeline_handle = None;
-        self.current_material_handle = None;
-        self.current_mesh_handle = None;
-        self.current_mesh_index_count = 0;
-        self.instance_range = 0..0; 
     }
 
-    fn get_accumulated_instance_count(&self) -> u32 {
-        self.instance_range.end - self.instance_range.start 
+    fn create_instance_buffer(device: &wgpu::Device, instance_count: usize) -> wgpu::Buffer {
+        let buffer_size = (size_of::<Instance>() * instance_count) as u64;
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("instance_buffer"),
+            size: buffer_size,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
     }
-}
\ No newline at end of file
+}
+
+fn get_instance_count(instance_range: &Range<u32>) -> u32 {
+    instance_range.end - instance_range.start 
+}
@@ -1,5 +1,8 @@
 use crate::resources::{
     RendererPipeline, 
+    RendererShader,
+    RendererVertexLayout,
+    RendererBlendState,
     RendererMaterial, 
     RendererTexture, 
     RendererMesh, 
@@ -8,15 +11,19 @@ use crate::resources::{
 
 use pill_core::PillSlotMap;
 
+use std::collections::HashMap;
+
 use pill_engine::internal::{
     RendererCameraHandle,
     RendererMaterialHandle,
     RendererMeshHandle,
     RendererPipelineHandle,
+    RendererShaderHandle,
     RendererTextureHandle, 
 };
 
 pub const MAX_PIPELINES: usize = 10;
+pub const MAX_SHADERS: usize = 10;
 pub const MAX_TEXTURES: usize = 10;
 pub const MAX_MATERIALS: usize = 10;
 pub const MAX_MESHES: usize = 10;
@@ -24,6 +31,8 @@ pub const MAX_CAMERAS: usize = 10;
 
 pub struct RendererResourceStorage {
     pub(crate) pipelines: PillSlotMap::<RendererPipelineHandle, RendererPipeline>,
+    pub(crate) pipeline_cache: HashMap::<(RendererShaderHandle, RendererVertexLayout, RendererBlendState), RendererPipelineHandle>, // One pipeline per shader, vertex layout and blend state
+    pub(crate) shaders: PillSlotMap::<RendererShaderHandle, RendererShader>,
     pub(crate) materials: PillSlotMap::<RendererMaterialHandle, RendererMaterial>,
     pub(crate) textures: PillSlotMap<RendererTextureHandle, RendererTexture>,
     pub(crate) meshes: PillSlotMap::<RendererMeshHandle, RendererMesh>,
@@ -33,6 +42,7 @@ pub struct RendererResourceStorage {
 impl RendererResourceStorage {
     pub fn new(config: &config::Config) -> Self {
         let max_pipeline_count = config.get_int("MAX_PIPELINES").unwrap_or(MAX_PIPELINES as i64) as usize;
+        let max_shader_count = config.get_int("MAX_SHADERS").unwrap_or(MAX_SHADERS as i64) as usize;
         let max_texture_count = config.get_int("MAX_TEXTURES").unwrap_or(MAX_TEXTURES as i64) as usize;
         let max_material_count = config.get_int("MAX_MATERIALS").unwrap_or(MAX_MATERIALS as i64) as usize;
         let max_mesh_count = config.get_int("MAX_MESHS").unwrap_or(MAX_MESHES as i64) as usize;
@@ -40,6 +50,8 @@ impl RendererResourceStorage {
 
         RendererResourceStorage {
             pipelines: PillSlotMap::<RendererPipelineHandle, RendererPipeline>::with_capacity_and_key(max_pipeline_count), 
+            pipeline_cache: HashMap::<(RendererShaderHandle, RendererVertexLayout, RendererBlendState), RendererPipelineHandle>::new(),
+            shaders: PillSlotMap::<RendererShaderHandle, RendererShader>::with_capacity_and_key(max_shader_count),
             textures: PillSlotMap::<RendererTextureHandle, RendererTexture>::with_capacity_and_key(max_texture_count),
             materials: PillSlotMap::<RendererMaterialHandle, RendererMaterial>::with_capacity_and_key(max_material_count),
             meshes: PillSlotMap::<RendererMeshHandle, RendererMesh>::with_capacity_and_key(max_mesh_count),
@@ -23,6 +23,7 @@ pub const OPENGL_TO_WGPU_MATRIX: cgmath::Matrix4<f32> = cgmath::Matrix4::new(
 pub(crate) struct CameraUniform {
     pub(crate) position: [f32; 4], // Camera position
     pub(crate) view_projection_matrix: [[f32; 4]; 4], // Perspective manipulation
+    pub(crate) inverse_skybox_view_projection_matrix: [[f32; 4]; 4], // Maps screen positions to directions in world space (view without translation)
 }
 
 impl CameraUniform {
@@ -30,6 +31,7 @@ impl CameraUniform {
         Self {
             position: cgmath::Vector4::zero().into(),
             view_projection_matrix: cgmath::Matrix4::identity().into(),
+            inverse_skybox_view_projection_matrix: cgmath::Matrix4::identity().into(),
         }
     }
 
@@ -43,32 +45,13 @@ impl CameraUniform {
         }.into();
 
         // Update view-projection
-        self.view_projection_matrix = (CameraUniform::calculate_projection_matrix(camera_component) * CameraUniform::calculate_view_matrix(transform_component)).into();
-    }
-
-    fn calculate_view_matrix(transform_component: &TransformComponent) -> cgmath::Matrix4::<f32> {
-        let position = cgmath::Point3::from_vec(transform_component.position);
-
-        let roll_matrix  = cgmath::Matrix3::from_angle_z(cgmath::Deg(transform_component.rotation.z));
-        let yaw_matrix  = cgmath::Matrix3::from_angle_y(cgmath::Deg(transform_component.rotation.y));
-        let pitch_matrix  = cgmath::Matrix3::from_angle_x(cgmath::Deg(transform_component.rotation.x));
-        let rotation_matrix = yaw_matrix * pitch_matrix * roll_matrix;
-        let direction  = rotation_matrix * cgmath::Vector3::<f32>::unit_z();
-
-        cgmath::Matrix4::look_to_rh(
-            position,
-            direction,
-            cgmath::Vector3::unit_y()
-        )
-    }
+        self.view_projection_matrix = (OPENGL_TO_WGPU_MATRIX * camera_component.get_view_projection_matrix(transform_component)).into();
 
-    fn calculate_projection_matrix(camera_component: &CameraComponent) -> cgmath::Matrix4::<f32> {
-        OPENGL_TO_WGPU_MATRIX * cgmath::perspective(
-            cgmath::Deg(camera_component.fov), 
-            camera_component.aspect.get_value(), 
-            camera_component.range.start,
-            camera_component.range.end
-        )
+        // Update skybox view-projection (skybox is always centered at camera so translation is removed)
+        let mut skybox_view_matrix = camera_component.get_view_matrix(transform_component);
+        skybox_view_matrix.w = cgmath::Vector4::unit_w();
+        let skybox_view_projection_matrix = OPENGL_TO_WGPU_MATRIX * camera_component.get_projection_matrix() * skybox_view_matrix;
+        self.inverse_skybox_view_projection_matrix = skybox_view_projection_matrix.invert().unwrap_or(cgmath::Matrix4::identity()).into();
     }
 }
 
@@ -82,6 +65,23 @@ pub struct RendererCamera {
 }
 
 impl RendererCamera {
+    // Camera bind group layout is shared by all pipelines so that the same camera bind group can be used
+    pub fn create_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+        device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("camera_bind_group_layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false, // Specifies if this buffer will be changing size or not
+                    min_binding_size: None,
+                },
+                count: None,
+            }]
+        })
+    }
+
     pub fn new(device: &wgpu::Device, camera_bind_group_layout: &wgpu::BindGroupLayout) -> Result<Self> {
 
         let uniform = CameraUniform::new();
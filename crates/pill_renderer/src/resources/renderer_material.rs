@@ -1,77 +1,63 @@
-use crate::RendererResourceStorage;
+use crate::{
+    RendererResourceStorage,
+    resources::RendererShader,
+};
 
 use pill_engine::internal::{
     MaterialParameterMap,
     RendererError,
     MaterialTextureMap,
     RendererMaterialHandle,
-    RendererPipelineHandle, 
-    MASTER_SHADER_COLOR_TEXTURE_SLOT,    
-    MASTER_SHADER_NORMAL_TEXTURE_SLOT,
-    MASTER_SHADER_TINT_PARAMETER_SLOT, get_default_texture_handles, get_renderer_texture_handle_from_material_texture, MASTER_SHADER_SPECULARITY_PARAMETER_SLOT,
+    RendererPipelineHandle,
+    RendererShaderHandle,
+    ShaderLayout,
+    ShaderParameterType,
+    get_default_texture_handles,
+    get_renderer_texture_handle_from_material_texture,
 };
 
 use wgpu::util::DeviceExt;
 use anyhow::{ Result, Error};
 
-// --- Material Uniform ---
-
-#[repr(C)]
-#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
-pub(crate) struct MaterialUniform {
-    pub(crate) tint: [f32; 3],
-    pub(crate) specularity: f32,
-}
-
-impl MaterialUniform {
-    pub fn new() -> Self {
-        Self {
-            tint: cgmath::Vector3::<f32>::new(0.0,0.0,0.0).into(),
-            specularity: 0.0,
-        }
-    }
-}
-
 // --- Material ---
 
 pub struct RendererMaterial {
     pub name: String,
+    pub shader_handle: RendererShaderHandle,
     pub pipeline_handle: RendererPipelineHandle,
     pub texture_bind_group: wgpu::BindGroup,
     pub parameter_bind_group: wgpu::BindGroup,
-    pub(crate) uniform: MaterialUniform,
-    buffer: wgpu::Buffer,
+    buffer: Option<wgpu::Buffer>, // None if shader has no parameters
 }
 
 impl RendererMaterial {
     pub fn new(
         device: &wgpu::Device,
-        queue: &wgpu::Queue, 
         rendering_resource_storage: &RendererResourceStorage,
         name: &str,
+        shader_handle: RendererShaderHandle,
         pipeline_handle: RendererPipelineHandle,
-        texture_bind_group_layout: &wgpu::BindGroupLayout,
         textures: &MaterialTextureMap,
-        parameter_bind_group_layout: &wgpu::BindGroupLayout,
         parameters: &MaterialParameterMap,
     ) -> Result<Self> {
+        let shader = rendering_resource_storage.shaders.get(shader_handle).ok_or(Error::new(RendererError::RendererResourceNotFound))?;
 
         // Create parameter buffer and write data to it
-        let mut uniform = MaterialUniform::new();
-        let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("material_buffer"),
-            contents: bytemuck::cast_slice(&[uniform]),
-            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-        });
-        uniform.tint = parameters.get_color(MASTER_SHADER_TINT_PARAMETER_SLOT).unwrap().into();
-        uniform.specularity = parameters.get_scalar(MASTER_SHADER_SPECULARITY_PARAMETER_SLOT).unwrap().into();
-        queue.write_buffer(&buffer, 0, bytemuck::cast_slice(&[uniform]));
+        let parameter_data = Self::get_parameter_data(&shader.layout, parameters)?;
+        let buffer = match parameter_data.is_empty() {
+            true => None,
+            false => Some(device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("material_buffer"),
+                contents: &parameter_data,
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            })),
+        };
 
         // Create texture binding group
         let texture_bind_group = Self::create_texture_bind_group(
             device,
             rendering_resource_storage,
-            &texture_bind_group_layout,
+            shader,
             &(name.to_owned() + "_textures"),
             textures,
         )?;
@@ -79,17 +65,17 @@ impl RendererMaterial {
         // Create parameter binding group
         let parameter_bind_group = Self::create_parameter_bind_group(
             device,
-            &parameter_bind_group_layout,
+            &shader.parameter_bind_group_layout,
             &(name.to_owned() + "_parameters"),
-            &buffer,
+            buffer.as_ref(),
         )?;
 
         let renderer_material = Self {
             name: name.to_string(),
+            shader_handle,
             pipeline_handle,
             texture_bind_group,
             parameter_bind_group,
-            uniform,
             buffer,
         };
 
@@ -97,20 +83,20 @@ impl RendererMaterial {
     }
 
     pub fn update_textures(
-        device: &wgpu::Device, 
+        device: &wgpu::Device,
         material_renderer_handle: RendererMaterialHandle,
-        rendering_resource_storage: &mut RendererResourceStorage, 
+        rendering_resource_storage: &mut RendererResourceStorage,
         textures: &MaterialTextureMap
     ) -> Result<()> {
         let material = rendering_resource_storage.materials.get(material_renderer_handle).ok_or(Error::new(RendererError::RendererResourceNotFound))?;
         let material_name = material.name.clone();
-        let pipeline = rendering_resource_storage.pipelines.get(material.pipeline_handle).ok_or(Error::new(RendererError::RendererResourceNotFound))?;
+        let shader = rendering_resource_storage.shaders.get(material.shader_handle).ok_or(Error::new(RendererError::RendererResourceNotFound))?;
 
         let texture_bind_group = RendererMaterial::create_texture_bind_group(
-            &device, 
-            &rendering_resource_storage, 
-            &pipeline.material_texture_bind_group_layout, 
-            &(material_name.to_owned() + "_textures"), 
+            &device,
+            &rendering_resource_storage,
+            shader,
+            &(material_name.to_owned() + "_textures"),
             textures
         )?;
 
@@ -120,48 +106,35 @@ impl RendererMaterial {
     }
 
     pub fn create_texture_bind_group(
-        device: &wgpu::Device, 
-        rendering_resource_storage: &RendererResourceStorage, 
-        texture_bind_group_layout: &wgpu::BindGroupLayout,
+        device: &wgpu::Device,
+        rendering_resource_storage: &RendererResourceStorage,
+        shader: &RendererShader,
         name: &str,
         textures: &MaterialTextureMap
     ) -> Result<wgpu::BindGroup> {
 
-        // Get texture renderer handles, if is it None use default texture for this type of slot
-        let color_texture = textures.data.get(MASTER_SHADER_COLOR_TEXTURE_SLOT).unwrap();
-        let color_renderer_texture_handle = 
-            get_renderer_texture_handle_from_material_texture(color_texture)
-            .unwrap_or_else(|| get_default_texture_handles(color_texture.texture_type).1);
-
-        let normal_texture = textures.data.get(MASTER_SHADER_NORMAL_TEXTURE_SLOT).unwrap();
-        let normal_renderer_textur_handle = 
-            get_renderer_texture_handle_from_material_texture(normal_texture)
-            .unwrap_or_else(|| get_default_texture_handles(normal_texture.texture_type).1);
-
-        let color_texture = rendering_resource_storage.textures.get(color_renderer_texture_handle).unwrap();
-        let normal_texture = rendering_resource_storage.textures.get(normal_renderer_textur_handle).unwrap(); 
+        // Get texture for each slot of the shader, if is it not set use default texture for this type of slot
+        let mut entries = Vec::<wgpu::BindGroupEntry>::new();
+        for texture_slot in shader.layout.texture_slots.iter() {
+            let renderer_texture_handle = textures.data.get(&texture_slot.name)
+                .and_then(|v| *get_renderer_texture_handle_from_material_texture(v))
+                .unwrap_or_else(|| get_default_texture_handles(texture_slot.texture_type).1);
+            let texture = rendering_resource_storage.textures.get(renderer_texture_handle).ok_or(Error::new(RendererError::RendererResourceNotFound))?;
+
+            entries.push(wgpu::BindGroupEntry {
+                binding: texture_slot.texture_binding,
+                resource: wgpu::BindingResource::TextureView(&texture.texture_view),
+            });
+            entries.push(wgpu::BindGroupEntry {
+                binding: texture_slot.sampler_binding,
+                resource: wgpu::BindingResource::Sampler(&texture.sampler),
+            });
+        }
 
         // Set texture resources to the bind group
         let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            layout: &texture_bind_group_layout,
-            entries: &[
-                wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: wgpu::BindingResource::TextureView(&color_texture.texture_view),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 1,
-                    resource: wgpu::BindingResource::Sampler(&color_texture.sampler),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 2,
-                    resource: wgpu::BindingResource::TextureView(&normal_texture.texture_view),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 3,
-                    resource: wgpu::BindingResource::Sampler(&normal_texture.sampler),
-                },
-            ],
+            layout: &shader.texture_bind_group_layout,
+            entries: &entries,
             label: Some(name),
         });
 
@@ -169,43 +142,94 @@ impl RendererMaterial {
     }
 
     pub fn update_parameters(
-        device: &wgpu::Device, 
-        queue: &wgpu::Queue, 
+        queue: &wgpu::Queue,
         material_renderer_handle: RendererMaterialHandle,
-        rendering_resource_storage: &mut RendererResourceStorage, 
+        rendering_resource_storage: &mut RendererResourceStorage,
         parameters: &MaterialParameterMap
     ) -> Result<()> {
-        let material = rendering_resource_storage.materials.get_mut(material_renderer_handle).ok_or(Error::new(RendererError::RendererResourceNotFound))?;
-        let pipeline = rendering_resource_storage.pipelines.get(material.pipeline_handle).ok_or(Error::new(RendererError::RendererResourceNotFound))?;
+        let material = rendering_resource_storage.materials.get(material_renderer_handle).ok_or(Error::new(RendererError::RendererResourceNotFound))?;
+        let shader = rendering_resource_storage.shaders.get(material.shader_handle).ok_or(Error::new(RendererError::RendererResourceNotFound))?;
+
+        if let Some(buffer) = material.buffer.as_ref() {
+            let parameter_data = Self::get_parameter_data(&shader.layout, parameters)?;
+            queue.write_buffer(buffer, 0, &parameter_data);
+        }
 
-        material.uniform.tint = parameters.get_color(MASTER_SHADER_TINT_PARAMETER_SLOT).unwrap().into();
-        material.uniform.specularity = parameters.get_scalar(MASTER_SHADER_SPECULARITY_PARAMETER_SLOT).unwrap().into();
-        queue.write_buffer(&material.buffer, 0, bytemuck::cast_slice(&[material.uniform]));
+        Ok(())
+    }
+
+    // Creates parameter buffer and binding group matching current layout of the shader
+    pub fn update_parameter_buffer(
+        device: &wgpu::Device,
+        material_renderer_handle: RendererMaterialHandle,
+        rendering_resource_storage: &mut RendererResourceStorage,
+    ) -> Result<()> {
+        let material = rendering_resource_storage.materials.get(material_renderer_handle).ok_or(Error::new(RendererError::RendererResourceNotFound))?;
+        let shader = rendering_resource_storage.shaders.get(material.shader_handle).ok_or(Error::new(RendererError::RendererResourceNotFound))?;
+
+        let buffer = match shader.layout.parameter_buffer_size {
+            0 => None,
+            buffer_size => Some(device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("material_buffer"),
+                size: buffer_size as wgpu::BufferAddress,
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            })),
+        };
 
-        material.parameter_bind_group = RendererMaterial::create_parameter_bind_group(
-            device, 
-            &pipeline.material_parameter_bind_group_layout, 
-            &(material.name.to_owned() + "_parameter"), 
-            &material.buffer
+        let parameter_bind_group = Self::create_parameter_bind_group(
+            device,
+            &shader.parameter_bind_group_layout,
+            &(material.name.to_owned() + "_parameters"),
+            buffer.as_ref(),
         )?;
 
+        let material = rendering_resource_storage.materials.get_mut(material_renderer_handle).ok_or(Error::new(RendererError::RendererResourceNotFound))?;
+        material.buffer = buffer;
+        material.parameter_bind_group = parameter_bind_group;
+
         Ok(())
     }
 
+    // Writes parameter values at offsets of their slots in parameter buffer
+    fn get_parameter_data(shader_layout: &ShaderLayout, parameters: &MaterialParameterMap) -> Result<Vec<u8>> {
+        let mut parameter_data = vec![0u8; shader_layout.parameter_buffer_size as usize];
+        for parameter_slot in shader_layout.parameter_slots.iter() {
+            let value_data = match parameter_slot.parameter_type {
+                ShaderParameterType::Scalar => parameters.get_scalar(&parameter_slot.name)?.to_le_bytes().to_vec(),
+                ShaderParameterType::Bool => (parameters.get_bool(&parameter_slot.name)? as u32).to_le_bytes().to_vec(),
+                ShaderParameterType::Color => {
+                    let color: [f32; 3] = parameters.get_color(&parameter_slot.name)?.into();
+                    color.iter().flat_map(|v| v.to_le_bytes()).collect::<Vec<u8>>()
+                },
+            };
+
+            let offset = parameter_slot.offset as usize;
+            parameter_data[offset..offset + value_data.len()].copy_from_slice(&value_data);
+        }
+
+        Ok(parameter_data)
+    }
+
     fn create_parameter_bind_group(
-        device: &wgpu::Device, 
+        device: &wgpu::Device,
         parameter_bind_group_layout: &wgpu::BindGroupLayout,
         name: &str,
-        buffer: &wgpu::Buffer,
+        buffer: Option<&wgpu::Buffer>,
     ) -> Result<wgpu::BindGroup> {
-        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            layout: &parameter_bind_group_layout,
-            entries: &[
+        let entries = match buffer {
+            Some(v) => vec![
                 wgpu::BindGroupEntry {
                     binding: 0,
-                    resource: buffer.as_entire_binding(),
+                    resource: v.as_entire_binding(),
                 },
             ],
+            None => Vec::<wgpu::BindGroupEntry>::new(),
+        };
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &parameter_bind_group_layout,
+            entries: &entries,
             label: Some(name),
         });
 
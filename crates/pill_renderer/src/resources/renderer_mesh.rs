@@ -13,6 +13,7 @@ pub trait Vertex {
 // --- Mesh ---
 
 pub struct RendererMesh {
+    pub name: String,
     pub vertex_buffer: wgpu::Buffer,
     pub index_buffer: wgpu::Buffer,
     pub index_count: u32,
@@ -20,27 +21,55 @@ pub struct RendererMesh {
 
 impl RendererMesh {
     pub fn new(device: &wgpu::Device, name: &str, mesh_data: &MeshData) -> Result<Self> { 
-
-        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some(&format!("{:?}_vertex_buffer", name)),
-            contents: bytemuck::cast_slice(&mesh_data.vertices),
-            usage: wgpu::BufferUsages::VERTEX,
-        });
-
-        let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some(&format!("{:?}_vertex_buffer", name)),
-            contents: bytemuck::cast_slice(&mesh_data.indices),
-            usage: wgpu::BufferUsages::INDEX,
-        });
+        let vertex_bytes: &[u8] = bytemuck::cast_slice(&mesh_data.vertices);
+        let index_bytes: &[u8] = bytemuck::cast_slice(&mesh_data.indices);
 
         let renderer_mesh = Self {
-            vertex_buffer,
-            index_buffer,
+            name: name.to_string(),
+            vertex_buffer: create_buffer(device, &format!("{:?}_vertex_buffer", name), vertex_bytes, vertex_bytes.len(), wgpu::BufferUsages::VERTEX),
+            index_buffer: create_buffer(device, &format!("{:?}_index_buffer", name), index_bytes, index_bytes.len(), wgpu::BufferUsages::INDEX),
             index_count: mesh_data.indices.len() as u32,
         };
 
         Ok(renderer_mesh)
     }
+
+    // Writes new data to existing buffers, buffers are reallocated only if data does not fit in them
+    // (with extra space so mesh can keep growing without reallocating on every update)
+    pub fn update(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, mesh_data: &MeshData) -> Result<()> {
+        let vertex_bytes: &[u8] = bytemuck::cast_slice(&mesh_data.vertices);
+        let index_bytes: &[u8] = bytemuck::cast_slice(&mesh_data.indices);
+
+        // Update vertex buffer
+        match vertex_bytes.len() as wgpu::BufferAddress <= self.vertex_buffer.size() {
+            true => queue.write_buffer(&self.vertex_buffer, 0, vertex_bytes),
+            false => self.vertex_buffer = create_buffer(device, &format!("{:?}_vertex_buffer", self.name), vertex_bytes, vertex_bytes.len().next_power_of_two(), wgpu::BufferUsages::VERTEX),
+        }
+
+        // Update index buffer
+        match index_bytes.len() as wgpu::BufferAddress <= self.index_buffer.size() {
+            true => queue.write_buffer(&self.index_buffer, 0, index_bytes),
+            false => self.index_buffer = create_buffer(device, &format!("{:?}_index_buffer", self.name), index_bytes, index_bytes.len().next_power_of_two(), wgpu::BufferUsages::INDEX),
+        }
+        self.index_count = mesh_data.indices.len() as u32;
+
+        Ok(())
+    }
+}
+
+// Creates buffer that can be updated later and fills it with data
+fn create_buffer(device: &wgpu::Device, label: &str, contents: &[u8], size: usize, usage: wgpu::BufferUsages) -> wgpu::Buffer {
+    let size = (size as wgpu::BufferAddress).max(wgpu::COPY_BUFFER_ALIGNMENT);
+    let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some(label),
+        size,
+        usage: usage | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: true,
+    });
+    buffer.slice(..).get_mapped_range_mut()[..contents.len()].copy_from_slice(contents);
+    buffer.unmap();
+
+    buffer
 }
 
 impl Vertex for RendererMesh {
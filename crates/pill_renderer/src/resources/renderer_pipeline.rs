@@ -1,101 +1,97 @@
+use crate::{
+    resources::{ RendererMesh, RendererShader, Vertex },
+    instance::Instance,
+};
+
+use pill_engine::internal::{ BlendMode, RendererShaderHandle };
+
 use anyhow::{ Result };
 
+// --- Vertex layout ---
+
+// Vertex layout pipeline is created for, pipelines are cached per shader and vertex layout
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RendererVertexLayout {
+    MeshInstanced, // Mesh vertices with per instance model matrix
+}
+
+impl RendererVertexLayout {
+    pub fn get_descriptors(&self) -> Vec<wgpu::VertexBufferLayout<'static>> {
+        match self {
+            RendererVertexLayout::MeshInstanced => vec![RendererMesh::data_layout_descriptor(), Instance::data_layout_descriptor()],
+        }
+    }
+}
+
+// --- Blend state ---
+
+// Blend state pipeline is created for, alpha cutout uses opaque state since its fragments are discarded by shader
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RendererBlendState {
+    Opaque,
+    AlphaBlend,
+    Additive,
+}
+
+impl RendererBlendState {
+    pub fn from_blend_mode(blend_mode: BlendMode) -> Self {
+        match blend_mode {
+            BlendMode::Opaque | BlendMode::AlphaCutout(_) => RendererBlendState::Opaque,
+            BlendMode::AlphaBlend => RendererBlendState::AlphaBlend,
+            BlendMode::Additive => RendererBlendState::Additive,
+        }
+    }
+
+    fn get_blend_state(&self) -> wgpu::BlendState {
+        match self {
+            RendererBlendState::Opaque => wgpu::BlendState::REPLACE,
+            RendererBlendState::AlphaBlend => wgpu::BlendState::ALPHA_BLENDING,
+            RendererBlendState::Additive => wgpu::BlendState {
+                color: wgpu::BlendComponent {
+                    src_factor: wgpu::BlendFactor::SrcAlpha,
+                    dst_factor: wgpu::BlendFactor::One,
+                    operation: wgpu::BlendOperation::Add,
+                },
+                alpha: wgpu::BlendComponent::OVER,
+            },
+        }
+    }
+
+    // Transparent pipelines test depth but do not write it, so that transparent objects behind them are still drawn
+    fn is_depth_write_enabled(&self) -> bool {
+        matches!(self, RendererBlendState::Opaque)
+    }
+}
+
 // --- Pipeline ---
 
 pub struct RendererPipeline {
     pub render_pipeline: wgpu::RenderPipeline,
-    pub material_texture_bind_group_layout: wgpu::BindGroupLayout,
-    pub material_parameter_bind_group_layout: wgpu::BindGroupLayout,
-    pub camera_bind_group_layout: wgpu::BindGroupLayout,
+    pub shader_handle: RendererShaderHandle,
+    pub vertex_layout: RendererVertexLayout,
+    pub blend_state: RendererBlendState,
 }
 
 impl RendererPipeline {
     pub fn new(
         device: &wgpu::Device,
-        vertex_shader: wgpu::ShaderModule,
-        fragment_shader: wgpu::ShaderModule,
+        shader_handle: RendererShaderHandle,
+        shader: &RendererShader,
+        vertex_layout: RendererVertexLayout,
+        blend_state: RendererBlendState,
         color_format: wgpu::TextureFormat,
         depth_format: Option<wgpu::TextureFormat>,
-        vertex_layouts: &[wgpu::VertexBufferLayout],
+        sample_count: u32,
+        camera_bind_group_layout: &wgpu::BindGroupLayout,
     ) -> Result<Self> {
 
-        // Define material bind group layout (Describes a set of resources and how they can be accessed by a shader)
-        let material_texture_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor { 
-            label: Some("material_texture_bind_group_layout"),
-            entries: &[
-                wgpu::BindGroupLayoutEntry { // Entry for the sampled texture at binding 0
-                    binding: 0,
-                    visibility: wgpu::ShaderStages::FRAGMENT, // Visible only to fragment shader
-                    ty: wgpu::BindingType::Texture {
-                        multisampled: false,
-                        view_dimension: wgpu::TextureViewDimension::D2,
-                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
-                    },
-                    count: None,
-                },
-                wgpu::BindGroupLayoutEntry { // Entry for the sampler at binding 1
-                    binding: 1,
-                    visibility: wgpu::ShaderStages::FRAGMENT, // Visible only to fragment shader
-                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
-                    count: None,
-                },
-                wgpu::BindGroupLayoutEntry { // Normal map
-                    binding: 2,
-                    visibility: wgpu::ShaderStages::FRAGMENT,
-                    ty: wgpu::BindingType::Texture {
-                        multisampled: false,
-                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
-                        view_dimension: wgpu::TextureViewDimension::D2,
-                    },
-                    count: None,
-                },
-                wgpu::BindGroupLayoutEntry {
-                    binding: 3,
-                    visibility: wgpu::ShaderStages::FRAGMENT,
-                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
-                    count: None,
-                }, 
-            ],
-        });
-
-        let material_parameter_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor { 
-            label: Some("material_parameter_bind_group_layout"),
-            entries: &[
-                wgpu::BindGroupLayoutEntry {
-                    binding: 0,
-                    visibility: wgpu::ShaderStages::FRAGMENT,
-                    ty: wgpu::BindingType::Buffer {
-                        ty: wgpu::BufferBindingType::Uniform,
-                        has_dynamic_offset: false, // Specifies if this buffer will be changing size or not
-                        min_binding_size: None,
-                    },
-                    count: None,
-                },
-            ],
-        });
-
-        // Define camera bind group layout
-        let camera_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-            label: Some("camera_bind_group_layout"),
-            entries: &[wgpu::BindGroupLayoutEntry {
-                binding: 0,
-                visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
-                ty: wgpu::BindingType::Buffer {
-                    ty: wgpu::BufferBindingType::Uniform,
-                    has_dynamic_offset: false, // Specifies if this buffer will be changing size or not
-                    min_binding_size: None,
-                },
-                count: None,
-            }]
-        });
-
         // Create pipeline layout descriptor
         let pipeline_layout_descriptor = wgpu::PipelineLayoutDescriptor {
             label: Some("render_pipeline_layout"),
             bind_group_layouts: &[
-                &material_texture_bind_group_layout,
-                &material_parameter_bind_group_layout,
-                &camera_bind_group_layout,
+                &shader.texture_bind_group_layout,
+                &shader.parameter_bind_group_layout,
+                camera_bind_group_layout,
             ],
             push_constant_ranges: &[],
         };
@@ -106,24 +102,22 @@ impl RendererPipeline {
         // Create color target states that specifies what what color outputs wgpu should set up
         let color_target_states = &[Some(wgpu::ColorTargetState { 
             format: color_format,
-            blend: Some(wgpu::BlendState {
-                alpha: wgpu::BlendComponent::REPLACE,
-                color: wgpu::BlendComponent::REPLACE,
-            }),
+            blend: Some(blend_state.get_blend_state()),
             write_mask: wgpu::ColorWrites::ALL,
         })];
 
+        let vertex_layouts = vertex_layout.get_descriptors();
         let render_pipeline_descriptor = wgpu::RenderPipelineDescriptor {
-            label: Some("render_pipeline"),
+            label: Some(&format!("{}_render_pipeline", shader.name)),
             layout: Some(&layout),
             vertex: wgpu::VertexState { 
-                module: &vertex_shader,
+                module: &shader.vertex_shader,
                 entry_point: "main",
-                buffers: vertex_layouts, // Specifies structure of vertices that will be passed to the vertex shader
+                buffers: &vertex_layouts, // Specifies structure of vertices that will be passed to the vertex shader
                 compilation_options: Default::default(),
             },
             fragment: Some(wgpu::FragmentState {
-                module: &fragment_shader,
+                module: &shader.fragment_shader,
                 entry_point: "main",
                 targets: color_target_states,
                 compilation_options: Default::default(),
@@ -139,13 +133,13 @@ impl RendererPipeline {
             },
             depth_stencil: depth_format.map(|format| wgpu::DepthStencilState {
                 format,
-                depth_write_enabled: true,
+                depth_write_enabled: blend_state.is_depth_write_enabled(),
                 depth_compare: wgpu::CompareFunction::Less, // Specifies when to discard a new pixel. Using LESS means pixels will be drawn front to back
                 stencil: wgpu::StencilState::default(),
                 bias: wgpu::DepthBiasState::default(),
             }),
             multisample: wgpu::MultisampleState {
-                count: 1, // Determines how many samples pipeline will use (Multisampling)
+                count: sample_count, // Determines how many samples pipeline will use (Multisampling)
                 mask: !0, // Specifies which samples should be active
                 alpha_to_coverage_enabled: false,
             },
@@ -157,11 +151,288 @@ impl RendererPipeline {
 
         let pipeline = Self { 
             render_pipeline,
-            material_texture_bind_group_layout,
-            material_parameter_bind_group_layout,
-            camera_bind_group_layout,
+            shader_handle,
+            vertex_layout,
+            blend_state,
+        };
+
+        Ok(pipeline)
+    }
+}
+
+// --- Skybox pipeline ---
+
+pub struct RendererSkyboxPipeline {
+    pub render_pipeline: wgpu::RenderPipeline,
+    pub texture_bind_group_layout: wgpu::BindGroupLayout,
+    vertex_shader: wgpu::ShaderModule, // Shaders are kept so that pipeline can be recreated
+    fragment_shader: wgpu::ShaderModule,
+}
+
+impl RendererSkyboxPipeline {
+    // Camera bind group layout is shared with material pipelines so that the same camera bind group can be used
+    pub fn new(
+        device: &wgpu::Device,
+        vertex_shader: wgpu::ShaderModule,
+        fragment_shader: wgpu::ShaderModule,
+        color_format: wgpu::TextureFormat,
+        depth_format: wgpu::TextureFormat,
+        sample_count: u32,
+        camera_bind_group_layout: &wgpu::BindGroupLayout,
+    ) -> Result<Self> {
+
+        // Define skybox texture bind group layout
+        let texture_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor { 
+            label: Some("skybox_texture_bind_group_layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: wgpu::TextureViewDimension::Cube,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+
+        let render_pipeline = Self::create_render_pipeline(
+            device, 
+            &vertex_shader, 
+            &fragment_shader, 
+            &texture_bind_group_layout, 
+            color_format, 
+            depth_format, 
+            sample_count, 
+            camera_bind_group_layout
+        );
+
+        let pipeline = Self { 
+            render_pipeline,
+            texture_bind_group_layout,
+            vertex_shader,
+            fragment_shader,
+        };
+
+        Ok(pipeline)
+    }
+
+    // Recreates pipeline for new sample count, texture bind group layout stays the same so existing bind groups remain valid
+    pub fn set_sample_count(
+        &mut self,
+        device: &wgpu::Device,
+        color_format: wgpu::TextureFormat,
+        depth_format: wgpu::TextureFormat,
+        sample_count: u32,
+        camera_bind_group_layout: &wgpu::BindGroupLayout,
+    ) {
+        self.render_pipeline = Self::create_render_pipeline(
+            device, 
+            &self.vertex_shader, 
+            &self.fragment_shader, 
+            &self.texture_bind_group_layout, 
+            color_format, 
+            depth_format, 
+            sample_count, 
+            camera_bind_group_layout
+        );
+    }
+
+    fn create_render_pipeline(
+        device: &wgpu::Device,
+        vertex_shader: &wgpu::ShaderModule,
+        fragment_shader: &wgpu::ShaderModule,
+        texture_bind_group_layout: &wgpu::BindGroupLayout,
+        color_format: wgpu::TextureFormat,
+        depth_format: wgpu::TextureFormat,
+        sample_count: u32,
+        camera_bind_group_layout: &wgpu::BindGroupLayout,
+    ) -> wgpu::RenderPipeline {
+
+        // Create pipeline layout
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("skybox_pipeline_layout"),
+            bind_group_layouts: &[
+                texture_bind_group_layout,
+                camera_bind_group_layout,
+            ],
+            push_constant_ranges: &[],
+        });
+
+        let color_target_states = &[Some(wgpu::ColorTargetState { 
+            format: color_format,
+            blend: Some(wgpu::BlendState::REPLACE),
+            write_mask: wgpu::ColorWrites::ALL,
+        })];
+
+        let render_pipeline_descriptor = wgpu::RenderPipelineDescriptor {
+            label: Some("skybox_pipeline"),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState { 
+                module: vertex_shader,
+                entry_point: "main",
+                buffers: &[], // Full screen triangle is created in vertex shader
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: fragment_shader,
+                entry_point: "main",
+                targets: color_target_states,
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                conservative: false,
+                unclipped_depth: false,
+            },
+            depth_stencil: Some(wgpu::DepthStencilState { // Skybox is drawn first and does not write depth so that meshes are always drawn over it
+                format: depth_format,
+                depth_write_enabled: false,
+                depth_compare: wgpu::CompareFunction::Always,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+        };
+
+        device.create_render_pipeline(&render_pipeline_descriptor)
+    }
+}
+
+// --- Clear pipeline ---
+
+// Clears viewport of a camera by drawing full screen triangle on far plane (load operations of render pass would clear whole texture)
+pub struct RendererClearPipeline {
+    pub color_and_depth_render_pipeline: wgpu::RenderPipeline, // Color is taken from blend constant of render pass
+    pub depth_render_pipeline: wgpu::RenderPipeline,
+    vertex_shader: wgpu::ShaderModule, // Shaders are kept so that pipelines can be recreated
+    fragment_shader: wgpu::ShaderModule,
+}
+
+impl RendererClearPipeline {
+    pub fn new(
+        device: &wgpu::Device,
+        vertex_shader: wgpu::ShaderModule,
+        fragment_shader: wgpu::ShaderModule,
+        color_format: wgpu::TextureFormat,
+        depth_format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> Result<Self> {
+        let color_and_depth_render_pipeline = Self::create_render_pipeline(device, &vertex_shader, &fragment_shader, color_format, depth_format, sample_count, wgpu::ColorWrites::ALL);
+        let depth_render_pipeline = Self::create_render_pipeline(device, &vertex_shader, &fragment_shader, color_format, depth_format, sample_count, wgpu::ColorWrites::empty());
+
+        let pipeline = Self {
+            color_and_depth_render_pipeline,
+            depth_render_pipeline,
+            vertex_shader,
+            fragment_shader,
         };
 
         Ok(pipeline)
     }
+
+    pub fn set_sample_count(
+        &mut self,
+        device: &wgpu::Device,
+        color_format: wgpu::TextureFormat,
+        depth_format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) {
+        self.color_and_depth_render_pipeline = Self::create_render_pipeline(device, &self.vertex_shader, &self.fragment_shader, color_format, depth_format, sample_count, wgpu::ColorWrites::ALL);
+        self.depth_render_pipeline = Self::create_render_pipeline(device, &self.vertex_shader, &self.fragment_shader, color_format, depth_format, sample_count, wgpu::ColorWrites::empty());
+    }
+
+    fn create_render_pipeline(
+        device: &wgpu::Device,
+        vertex_shader: &wgpu::ShaderModule,
+        fragment_shader: &wgpu::ShaderModule,
+        color_format: wgpu::TextureFormat,
+        depth_format: wgpu::TextureFormat,
+        sample_count: u32,
+        write_mask: wgpu::ColorWrites,
+    ) -> wgpu::RenderPipeline {
+
+        // Create pipeline layout (clear pipeline uses no resources)
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("clear_pipeline_layout"),
+            bind_group_layouts: &[],
+            push_constant_ranges: &[],
+        });
+
+        // Output of fragment shader is replaced with blend constant
+        let color_target_states = &[Some(wgpu::ColorTargetState { 
+            format: color_format,
+            blend: Some(wgpu::BlendState {
+                color: wgpu::BlendComponent {
+                    src_factor: wgpu::BlendFactor::Constant,
+                    dst_factor: wgpu::BlendFactor::Zero,
+                    operation: wgpu::BlendOperation::Add,
+                },
+                alpha: wgpu::BlendComponent {
+                    src_factor: wgpu::BlendFactor::Constant,
+                    dst_factor: wgpu::BlendFactor::Zero,
+                    operation: wgpu::BlendOperation::Add,
+                },
+            }),
+            write_mask,
+        })];
+
+        let render_pipeline_descriptor = wgpu::RenderPipelineDescriptor {
+            label: Some("clear_pipeline"),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState { 
+                module: vertex_shader,
+                entry_point: "main",
+                buffers: &[], // Full screen triangle is created in vertex shader
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: fragment_shader,
+                entry_point: "main",
+                targets: color_target_states,
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                conservative: false,
+                unclipped_depth: false,
+            },
+            depth_stencil: Some(wgpu::DepthStencilState { // Depth is always overwritten with far plane depth
+                format: depth_format,
+                depth_write_enabled: true,
+                depth_compare: wgpu::CompareFunction::Always,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+        };
+
+        device.create_render_pipeline(&render_pipeline_descriptor)
+    }
 }
@@ -0,0 +1,104 @@
+use pill_engine::internal::{
+    ShaderData,
+    ShaderLayout,
+    ShaderSource,
+    TextureType,
+};
+
+use anyhow::{ Result };
+
+// --- Shader ---
+
+pub struct RendererShader {
+    pub name: String,
+    pub vertex_shader: wgpu::ShaderModule,
+    pub fragment_shader: wgpu::ShaderModule,
+    pub layout: ShaderLayout,
+    pub texture_bind_group_layout: wgpu::BindGroupLayout,
+    pub parameter_bind_group_layout: wgpu::BindGroupLayout,
+}
+
+impl RendererShader {
+    pub fn new(device: &wgpu::Device, name: &str, shader_data: &ShaderData) -> Result<Self> {
+
+        // Create shader modules
+        let vertex_shader = Self::create_shader_module(device, &format!("{}_vertex_shader", name), &shader_data.vertex_source);
+        let fragment_shader = Self::create_shader_module(device, &format!("{}_fragment_shader", name), &shader_data.fragment_source);
+
+        // Define texture bind group layout from texture slots (each texture is followed by its sampler)
+        let texture_bind_group_layout_entries = shader_data.layout.texture_slots.iter()
+            .flat_map(|texture_slot| {
+                let view_dimension = match texture_slot.texture_type {
+                    TextureType::Cubemap => wgpu::TextureViewDimension::Cube,
+                    TextureType::Color | TextureType::Normal => wgpu::TextureViewDimension::D2,
+                };
+                [
+                    wgpu::BindGroupLayoutEntry {
+                        binding: texture_slot.texture_binding,
+                        visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            multisampled: false,
+                            view_dimension,
+                            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: texture_slot.sampler_binding,
+                        visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                        count: None,
+                    },
+                ]
+            })
+            .collect::<Vec<wgpu::BindGroupLayoutEntry>>();
+
+        let texture_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some(&format!("{}_texture_bind_group_layout", name)),
+            entries: &texture_bind_group_layout_entries,
+        });
+
+        // Define parameter bind group layout (empty if shader has no parameters)
+        let mut parameter_bind_group_layout_entries = Vec::<wgpu::BindGroupLayoutEntry>::new();
+        if shader_data.layout.parameter_buffer_size > 0 {
+            parameter_bind_group_layout_entries.push(wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false, // Specifies if this buffer will be changing size or not
+                    min_binding_size: None,
+                },
+                count: None,
+            });
+        }
+
+        let parameter_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some(&format!("{}_parameter_bind_group_layout", name)),
+            entries: &parameter_bind_group_layout_entries,
+        });
+
+        let shader = Self {
+            name: name.to_string(),
+            vertex_shader,
+            fragment_shader,
+            layout: shader_data.layout.clone(),
+            texture_bind_group_layout,
+            parameter_bind_group_layout,
+        };
+
+        Ok(shader)
+    }
+
+    fn create_shader_module(device: &wgpu::Device, label: &str, shader_source: &ShaderSource) -> wgpu::ShaderModule {
+        let source = match shader_source {
+            ShaderSource::SpirV(bytes) => wgpu::util::make_spirv(bytes),
+            ShaderSource::Wgsl(code) => wgpu::ShaderSource::Wgsl(code.as_str().into()),
+        };
+
+        device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some(label),
+            source,
+        })
+    }
+}
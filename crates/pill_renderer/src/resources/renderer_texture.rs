@@ -1,4 +1,5 @@
-use pill_engine::internal::TextureType;
+use pill_engine::internal::{ TextureSettings, TextureColorSpace, TextureWrapMode, TextureFilter, TextureData, HdrImage, CompressedImage, CubemapImage };
+use pill_core::TextureCompressionFormat;
 
 use anyhow::*;
 use image::GenericImageView;
@@ -20,33 +21,154 @@ impl RendererTexture {
     pub const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
 
     pub fn new_texture(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        name: Option<&str>,
+        texture_data: &TextureData,
+        settings: &TextureSettings,
+    ) -> Result<Self> {
+        match texture_data {
+            TextureData::Image(image_data) => Self::new_image_texture(device, queue, name, image_data, settings),
+            TextureData::Hdr(hdr_image) => Self::new_hdr_texture(device, queue, name, hdr_image, settings),
+            TextureData::Compressed(compressed_image) => Self::new_compressed_texture(device, queue, name, compressed_image, settings),
+            TextureData::Cubemap(cubemap_image) => Self::new_cubemap_texture(device, queue, name, cubemap_image, settings),
+        }
+    }
+
+    fn new_image_texture(
         device: &wgpu::Device,
         queue: &wgpu::Queue,
         name: Option<&str>,
         image_data: &image::DynamicImage,
-        texture_type: TextureType,
+        settings: &TextureSettings,
     ) -> Result<Self> {
         let dimensions = image_data.dimensions();
-        let rgba = image_data.to_rgba8();
 
+        // Create mip levels by downscaling previous level (each level is half the size of the previous one, down to 1x1)
+        // Levels are filtered in linear space, sRGB colors averaged directly would make smaller levels too dark
+        let mip_level_count = match settings.generate_mipmaps {
+            true => 32 - dimensions.0.max(dimensions.1).max(1).leading_zeros(),
+            false => 1,
+        };
+        let srgb = settings.color_space == TextureColorSpace::Srgb;
+        let mut mip_levels = vec![image_data.to_rgba8()];
+        let mut linear_mip_level_data = match mip_level_count > 1 {
+            true => Some(to_linear_image(&mip_levels[0], srgb)),
+            false => None,
+        };
+        for mip_level in 1..mip_level_count {
+            let width = (dimensions.0 >> mip_level).max(1);
+            let height = (dimensions.1 >> mip_level).max(1);
+            let previous_mip_level_data = linear_mip_level_data.as_ref().expect("Critical: No linear mip level data");
+            let resized_mip_level_data = image::imageops::resize(previous_mip_level_data, width, height, image::imageops::FilterType::Triangle);
+            mip_levels.push(from_linear_image(&resized_mip_level_data, srgb));
+            linear_mip_level_data = Some(resized_mip_level_data);
+        }
+
+        // Specify texture format
+        let format = match settings.color_space {
+            TextureColorSpace::Srgb => wgpu::TextureFormat::Rgba8UnormSrgb,
+            TextureColorSpace::Linear => wgpu::TextureFormat::Rgba8Unorm,
+        };
+
+        let mip_levels = mip_levels.iter().map(|v| v.as_raw().as_slice()).collect::<Vec<&[u8]>>();
+        Self::new_texture_with_mip_levels(device, queue, name, dimensions, format, &[mip_levels], settings)
+    }
+
+    // Mipmaps are not generated for HDR textures
+    fn new_hdr_texture(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        name: Option<&str>,
+        hdr_image: &HdrImage,
+        settings: &TextureSettings,
+    ) -> Result<Self> {
+        let dimensions = (hdr_image.width, hdr_image.height);
+        let data = hdr_image.to_half_float_bytes();
+        Self::new_texture_with_mip_levels(device, queue, name, dimensions, wgpu::TextureFormat::Rgba16Float, &[vec![data.as_slice()]], settings)
+    }
+
+    // Mip levels of faces are prepared with cubemap data (used only if mipmaps are enabled), faces are stored as layers of the texture
+    fn new_cubemap_texture(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        name: Option<&str>,
+        cubemap_image: &CubemapImage,
+        settings: &TextureSettings,
+    ) -> Result<Self> {
+        let dimensions = (cubemap_image.size, cubemap_image.size);
+
+        // Specify texture format
+        let format = match (cubemap_image.hdr, settings.color_space) {
+            (true, _) => wgpu::TextureFormat::Rgba16Float,
+            (false, TextureColorSpace::Srgb) => wgpu::TextureFormat::Rgba8UnormSrgb,
+            (false, TextureColorSpace::Linear) => wgpu::TextureFormat::Rgba8Unorm,
+        };
+
+        // Use mip levels of faces only if mipmaps are enabled
+        let mip_level_count = match settings.generate_mipmaps {
+            true => cubemap_image.faces[0].len(),
+            false => 1,
+        };
+        let layers = cubemap_image.faces.iter()
+            .map(|v| v.iter().take(mip_level_count).map(|v| v.as_slice()).collect())
+            .collect::<Vec<Vec<&[u8]>>>();
+        Self::new_texture_with_mip_levels(device, queue, name, dimensions, format, &layers, settings)
+    }
+
+    // Compressed data is uploaded as it is if GPU supports it, otherwise it is decompressed
+    fn new_compressed_texture(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        name: Option<&str>,
+        compressed_image: &CompressedImage,
+        settings: &TextureSettings,
+    ) -> Result<Self> {
+        let dimensions = (compressed_image.width, compressed_image.height);
+        let srgb = settings.color_space == TextureColorSpace::Srgb;
+
+        // Size of compressed texture has to be multiple of block size
+        let compression_supported = device.features().contains(wgpu::Features::TEXTURE_COMPRESSION_BC) && 
+            dimensions.0 % 4 == 0 && dimensions.1 % 4 == 0;
+
+        match compression_supported {
+            true => {
+                let format = get_compressed_texture_format(compressed_image.format, srgb);
+                let mip_levels = compressed_image.mip_levels.iter().map(|v| v.as_slice()).collect::<Vec<&[u8]>>();
+                Self::new_texture_with_mip_levels(device, queue, name, dimensions, format, &[mip_levels], settings)
+            },
+            false => {
+                let format = get_decompressed_texture_format(compressed_image.format, srgb);
+                let decompressed_mip_levels = compressed_image.decompress()?;
+                let mip_levels = decompressed_mip_levels.iter().map(|v| v.as_slice()).collect::<Vec<&[u8]>>();
+                Self::new_texture_with_mip_levels(device, queue, name, dimensions, format, &[mip_levels], settings)
+            },
+        }
+    }
+
+    // Data is given for each layer and its mip levels, texture with six layers is a cubemap
+    fn new_texture_with_mip_levels(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        name: Option<&str>,
+        dimensions: (u32, u32),
+        format: wgpu::TextureFormat,
+        layers: &[Vec<&[u8]>],
+        settings: &TextureSettings,
+    ) -> Result<Self> {
         // Get size
         let size = wgpu::Extent3d {
             width: dimensions.0,
             height: dimensions.1,
-            depth_or_array_layers: 1,
-        };
-
-        // Specify texture format
-        let format = match texture_type {
-            TextureType::Color => wgpu::TextureFormat::Rgba8UnormSrgb,
-            TextureType::Normal => wgpu::TextureFormat::Rgba8Unorm,
+            depth_or_array_layers: layers.len() as u32,
         };
+        let mip_level_count = layers[0].len() as u32;
 
         // Create texture
         let texture = device.create_texture(&wgpu::TextureDescriptor {
             label: name,
             size,
-            mip_level_count: 1,
+            mip_level_count,
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
             format,
@@ -54,38 +176,45 @@ impl RendererTexture {
             view_formats: &[],
         });
 
-        // Write data to texture
-        queue.write_texture(
-            wgpu::ImageCopyTexture {
-                aspect: wgpu::TextureAspect::All,
-                texture: &texture,
-                mip_level: 0,
-                origin: wgpu::Origin3d::ZERO,
-            },
-            &rgba,
-            wgpu::ImageDataLayout {
-                offset: 0,
-                bytes_per_row: Some(4 * dimensions.0),
-                rows_per_image: Some(dimensions.1),
-            },
-            size,
-        );
+        // Write data of all mip levels to texture (for compressed formats rows are rows of blocks)
+        let (block_width, block_height) = format.block_dimensions();
+        let block_size = format.block_copy_size(None).unwrap();
+        for (layer, mip_levels) in layers.iter().enumerate() {
+            for (mip_level, data) in mip_levels.iter().enumerate() {
+                // Mip levels of compressed textures are copied as whole blocks, even if they are smaller than a block
+                let mip_level_size = wgpu::Extent3d { depth_or_array_layers: 1, ..size }.mip_level_size(mip_level as u32, wgpu::TextureDimension::D2).physical_size(format);
+                let block_count = (mip_level_size.width / block_width, mip_level_size.height / block_height);
+
+                queue.write_texture(
+                    wgpu::ImageCopyTexture {
+                        aspect: wgpu::TextureAspect::All,
+                        texture: &texture,
+                        mip_level: mip_level as u32,
+                        origin: wgpu::Origin3d { x: 0, y: 0, z: layer as u32 },
+                    },
+                    data,
+                    wgpu::ImageDataLayout {
+                        offset: 0,
+                        bytes_per_row: Some(block_count.0 * block_size),
+                        rows_per_image: Some(block_count.1),
+                    },
+                    mip_level_size,
+                );
+            }
+        }
 
         // Create texture view
-        let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
-        
-        // Create sampler
-        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
-            address_mode_u: wgpu::AddressMode::Repeat,
-            address_mode_v: wgpu::AddressMode::Repeat,
-            address_mode_w: wgpu::AddressMode::Repeat,
-            mag_filter: wgpu::FilterMode::Linear,
-            min_filter: wgpu::FilterMode::Nearest,
-            mipmap_filter: wgpu::FilterMode::Nearest,
-            lod_min_clamp: 0.0,  // Fix: Must be 0.0 or greater
-            lod_max_clamp: 100.0, // You can set this based on your texture's mipmap levels
+        let view_dimension = match layers.len() {
+            6 => wgpu::TextureViewDimension::Cube,
+            _ => wgpu::TextureViewDimension::D2,
+        };
+        let texture_view = texture.create_view(&wgpu::TextureViewDescriptor {
+            dimension: Some(view_dimension),
             ..Default::default()
         });
+        
+        // Create sampler
+        let sampler = create_sampler(device, settings, mip_level_count);
 
         // Create final texture
         let texture  = Self {
@@ -97,16 +226,61 @@ impl RendererTexture {
         Ok(texture)
     }
 
+    // Creates texture that cameras render to and materials sample, it has no mip levels since it changes every frame
+    pub fn new_render_target_texture(
+        device: &wgpu::Device,
+        name: Option<&str>,
+        dimensions: (u32, u32),
+        format: wgpu::TextureFormat,
+        settings: &TextureSettings,
+    ) -> Result<Self> {
+
+        // Get size
+        let size = wgpu::Extent3d {
+            width: dimensions.0,
+            height: dimensions.1,
+            depth_or_array_layers: 1,
+        };
+
+        // Create texture
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: name,
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+
+        // Create texture view
+        let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        // Create sampler
+        let sampler = create_sampler(device, settings, 1);
+
+        // Create final texture
+        let texture = Self {
+            texture,
+            texture_view,
+            sampler,
+        };
+
+        Ok(texture)
+    }
+
     pub fn new_depth_texture(
         device: &wgpu::Device,
-        surface_configuration: &wgpu::SurfaceConfiguration,
+        dimensions: (u32, u32),
+        sample_count: u32, // Has to match sample count of color target it is used with
         label: &str,
     ) -> Result<Self> {
 
         // Get size
-        let size = wgpu::Extent3d { // Depth texture needs to be the same size as window
-            width: surface_configuration.width,
-            height: surface_configuration.height,
+        let size = wgpu::Extent3d { // Depth texture needs to be the same size as color target
+            width: dimensions.0,
+            height: dimensions.1,
             depth_or_array_layers: 1,
         };
 
@@ -115,7 +289,7 @@ impl RendererTexture {
             label: Some(label),
             size,
             mip_level_count: 1,
-            sample_count: 1,
+            sample_count,
             dimension: wgpu::TextureDimension::D2,
             format: Self::DEPTH_FORMAT,
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING, // Rendering to this texture so RENDER_ATTACHMENT flag is needed
@@ -148,4 +322,162 @@ impl RendererTexture {
 
         Ok(texture)
     }
-}
\ No newline at end of file
+
+    // Creates multisampled texture that scene is rendered to before it is resolved to single sampled one
+    pub fn new_multisampled_texture(
+        device: &wgpu::Device,
+        dimensions: (u32, u32),
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+        label: &str,
+    ) -> Result<Self> {
+
+        // Get size
+        let size = wgpu::Extent3d { // Multisampled texture needs to be the same size as texture it is resolved to
+            width: dimensions.0,
+            height: dimensions.1,
+            depth_or_array_layers: 1,
+        };
+
+        // Create texture
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some(label),
+            size,
+            mip_level_count: 1,
+            sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT, // Multisampled texture is only resolved, never sampled
+            view_formats: &[],
+        });
+
+        // Create texture view
+        let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        // Create sampler (unused, but every renderer texture has one)
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor::default());
+
+        // Create final texture
+        let texture = Self {
+            texture,
+            texture_view,
+            sampler,
+        };
+
+        Ok(texture)
+    }
+}
+
+fn create_sampler(device: &wgpu::Device, settings: &TextureSettings, mip_level_count: u32) -> wgpu::Sampler {
+    let address_mode = match settings.wrap_mode {
+        TextureWrapMode::Repeat => wgpu::AddressMode::Repeat,
+        TextureWrapMode::MirrorRepeat => wgpu::AddressMode::MirrorRepeat,
+        TextureWrapMode::ClampToEdge => wgpu::AddressMode::ClampToEdge,
+    };
+    let filter = get_filter_mode(settings.filter);
+    let mipmap_filter = get_filter_mode(settings.mipmap_filter);
+
+    // Anisotropic filtering requires all filters to be linear
+    let anisotropy_clamp = match filter == wgpu::FilterMode::Linear && mipmap_filter == wgpu::FilterMode::Linear {
+        true => settings.anisotropy.clamp(1, 16),
+        false => 1,
+    };
+
+    device.create_sampler(&wgpu::SamplerDescriptor {
+        address_mode_u: address_mode,
+        address_mode_v: address_mode,
+        address_mode_w: address_mode,
+        mag_filter: filter,
+        min_filter: filter,
+        mipmap_filter,
+        lod_min_clamp: 0.0,
+        lod_max_clamp: mip_level_count as f32,
+        anisotropy_clamp,
+        ..Default::default()
+    })
+}
+
+fn get_filter_mode(filter: TextureFilter) -> wgpu::FilterMode {
+    match filter {
+        TextureFilter::Nearest => wgpu::FilterMode::Nearest,
+        TextureFilter::Linear => wgpu::FilterMode::Linear,
+    }
+}
+
+fn get_compressed_texture_format(format: TextureCompressionFormat, srgb: bool) -> wgpu::TextureFormat {
+    match (format, srgb) {
+        (TextureCompressionFormat::Bc1, true) => wgpu::TextureFormat::Bc1RgbaUnormSrgb,
+        (TextureCompressionFormat::Bc1, false) => wgpu::TextureFormat::Bc1RgbaUnorm,
+        (TextureCompressionFormat::Bc2, true) => wgpu::TextureFormat::Bc2RgbaUnormSrgb,
+        (TextureCompressionFormat::Bc2, false) => wgpu::TextureFormat::Bc2RgbaUnorm,
+        (TextureCompressionFormat::Bc3, true) => wgpu::TextureFormat::Bc3RgbaUnormSrgb,
+        (TextureCompressionFormat::Bc3, false) => wgpu::TextureFormat::Bc3RgbaUnorm,
+        (TextureCompressionFormat::Bc4, _) => wgpu::TextureFormat::Bc4RUnorm,
+        (TextureCompressionFormat::Bc4Signed, _) => wgpu::TextureFormat::Bc4RSnorm,
+        (TextureCompressionFormat::Bc5, _) => wgpu::TextureFormat::Bc5RgUnorm,
+        (TextureCompressionFormat::Bc5Signed, _) => wgpu::TextureFormat::Bc5RgSnorm,
+        (TextureCompressionFormat::Bc6h, _) => wgpu::TextureFormat::Bc6hRgbUfloat,
+        (TextureCompressionFormat::Bc6hSigned, _) => wgpu::TextureFormat::Bc6hRgbFloat,
+        (TextureCompressionFormat::Bc7, true) => wgpu::TextureFormat::Bc7RgbaUnormSrgb,
+        (TextureCompressionFormat::Bc7, false) => wgpu::TextureFormat::Bc7RgbaUnorm,
+    }
+}
+
+fn get_decompressed_texture_format(format: TextureCompressionFormat, srgb: bool) -> wgpu::TextureFormat {
+    if format.is_hdr() {
+        wgpu::TextureFormat::Rgba16Float
+    }
+    else if format.is_signed() {
+        wgpu::TextureFormat::Rgba8Snorm
+    }
+    else {
+        match srgb {
+            true => wgpu::TextureFormat::Rgba8UnormSrgb,
+            false => wgpu::TextureFormat::Rgba8Unorm,
+        }
+    }
+}
+
+// Converts 8-bit image to floating point one with linear color channels (alpha is always linear)
+fn to_linear_image(image: &image::RgbaImage, srgb: bool) -> image::ImageBuffer<image::Rgba<f32>, Vec<f32>> {
+    image::ImageBuffer::from_fn(image.width(), image.height(), |x, y| {
+        let pixel = image.get_pixel(x, y).0;
+        let channel = |index: usize| {
+            let value = pixel[index] as f32 / 255.0;
+            match srgb && index < 3 {
+                true => srgb_to_linear(value),
+                false => value,
+            }
+        };
+        image::Rgba([channel(0), channel(1), channel(2), channel(3)])
+    })
+}
+
+// Converts floating point image with linear color channels back to 8-bit one
+fn from_linear_image(image: &image::ImageBuffer<image::Rgba<f32>, Vec<f32>>, srgb: bool) -> image::RgbaImage {
+    image::ImageBuffer::from_fn(image.width(), image.height(), |x, y| {
+        let pixel = image.get_pixel(x, y).0;
+        let channel = |index: usize| {
+            let value = match srgb && index < 3 {
+                true => linear_to_srgb(pixel[index]),
+                false => pixel[index],
+            };
+            (value.clamp(0.0, 1.0) * 255.0).round() as u8
+        };
+        image::Rgba([channel(0), channel(1), channel(2), channel(3)])
+    })
+}
+
+fn srgb_to_linear(value: f32) -> f32 {
+    match value <= 0.04045 {
+        true => value / 12.92,
+        false => ((value + 0.055) / 1.055).powf(2.4),
+    }
+}
+
+fn linear_to_srgb(value: f32) -> f32 {
+    match value <= 0.0031308 {
+        true => value * 12.92,
+        false => 1.055 * value.powf(1.0 / 2.4) - 0.055,
+    }
+}
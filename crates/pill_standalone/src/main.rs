@@ -117,7 +117,16 @@ fn main() {
     let _ = window_event_loop.run(move |event, event_loop_window_target| { // Run function takes closure
         match event {
             Event::AboutToWait => {
-                window.request_redraw();
+                // Wait until next frame if frame rate is limited, otherwise redraw immediately
+                match engine.get_min_frame_time() {
+                    Some(min_frame_time) if last_render_time.elapsed() < min_frame_time => {
+                        event_loop_window_target.set_control_flow(winit::event_loop::ControlFlow::WaitUntil(last_render_time + min_frame_time));
+                    },
+                    _ => {
+                        event_loop_window_target.set_control_flow(winit::event_loop::ControlFlow::Wait);
+                        window.request_redraw();
+                    },
+                }
             }
 
             // Handle device events
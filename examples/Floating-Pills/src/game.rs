@@ -74,15 +74,15 @@ impl PillGame for Game {
         // --- Create resources ---
 
         // Add meshes
-        let pill_mesh = Mesh::new("Pill", "./res/models/Pill.obj".into());
+        let pill_mesh = Mesh::new("Pill", ResourceLoadType::Path("./res/models/Pill.obj".into()));
         let pill_mesh_handle = engine.add_resource(pill_mesh)?;
-        let cube_mesh = Mesh::new("Cube", "./res/models/Cube.obj".into());
+        let cube_mesh = Mesh::new("Cube", ResourceLoadType::Path("./res/models/Cube.obj".into()));
         let cube_mesh_handle = engine.add_resource(cube_mesh)?;
-        let torus_mesh = Mesh::new("Torus", "./res/models/Torus.obj".into());
+        let torus_mesh = Mesh::new("Torus", ResourceLoadType::Path("./res/models/Torus.obj".into()));
         let torus_mesh_handle = engine.add_resource(torus_mesh)?;
 
         // Add sounds
-        let ambient_music = Sound::new("Ambient", "./res/audio/TestMusic.mp3".into());
+        let ambient_music = Sound::new("Ambient", ResourceLoadType::Path("./res/audio/TestMusic.mp3".into()));
         let ambient_music_handle = engine.add_resource(ambient_music)?;
 
         // Add textures
@@ -106,30 +106,30 @@ impl PillGame for Game {
 
         // Add materials
         let mut fabric_material = Material::new("Fabric");
-        fabric_material.set_texture("Color", fabric_color_texture_handle)?;
+        fabric_material.set_texture("BaseColor", fabric_color_texture_handle)?;
         fabric_material.set_texture("Normal", fabric_normal_texture_handle)?;
-        fabric_material.set_color("Tint", Color::new( 1.0, 0.1, 0.1))?;
+        fabric_material.set_color("BaseColor", Color::new( 1.0, 0.1, 0.1))?;
         let fabric_material_handle = engine.add_resource::<Material>(fabric_material)?; 
 
         let mut stones_material = Material::new("Stones");
-        stones_material.set_texture("Color", stones_color_texture_handle)?;
+        stones_material.set_texture("BaseColor", stones_color_texture_handle)?;
         stones_material.set_texture("Normal", stones_normal_texture_handle)?;
         let stones_material_handle = engine.add_resource::<Material>(stones_material)?; 
 
         let mut organic_material = Material::new("Organic");
-        organic_material.set_texture("Color", organic_color_texture_handle)?;
+        organic_material.set_texture("BaseColor", organic_color_texture_handle)?;
         organic_material.set_texture("Normal", organic_normal_texture_handle)?;
-        organic_material.set_color("Tint", Color::new( 0.26, 0.87, 0.9))?;
-        organic_material.set_scalar("Specularity", 3.0)?;
+        organic_material.set_color("BaseColor", Color::new( 0.26, 0.87, 0.9))?;
+        organic_material.set_scalar("Roughness", 0.2)?;
         let organic_material_handle = engine.add_resource::<Material>(organic_material)?; 
 
 
         let mut yellow_material = Material::new("Yellow");
-        yellow_material.set_color("Tint", Color::new( 1.0, 0.88, 0.0))?;
+        yellow_material.set_color("BaseColor", Color::new( 1.0, 0.88, 0.0))?;
         let yellow_material_handle = engine.add_resource::<Material>(yellow_material)?; 
 
         let mut blue_material = Material::new("Blue");
-        blue_material.set_color("Tint", Color::new( 0.26, 0.87, 0.9))?;
+        blue_material.set_color("BaseColor", Color::new( 0.26, 0.87, 0.9))?;
         let blue_material_handle = engine.add_resource::<Material>(blue_material)?; 
 
         let white_material = Material::new("White");
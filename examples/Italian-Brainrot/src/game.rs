@@ -28,7 +28,7 @@ impl PillGame for Game {
 
 		// Add meshes
         let chimpanzini_bananini_mesh_handle = engine.add_resource(
-			Mesh::new("ChimpanziniBananini", "./res/models/ChimpanziniBananini.obj".into())
+			Mesh::new("ChimpanziniBananini", ResourceLoadType::Path("./res/models/ChimpanziniBananini.obj".into()))
 		)?;
 
 		// Add textures
@@ -43,9 +43,9 @@ impl PillGame for Game {
 		// Add materials
 		let chimpanzini_bananini_material_handle = engine.add_resource::<Material>(
 			Material::builder("ChimpanziniBananini")
-				.texture("Color", chimpanzini_bananini_color_texture_handle)?
-				.color("Tint", Color::new(1.0, 1.0, 1.0))?
-				.scalar("Specularity", 0.5)?
+				.texture("BaseColor", chimpanzini_bananini_color_texture_handle)?
+				.color("BaseColor", Color::new(1.0, 1.0, 1.0))?
+				.scalar("Roughness", 0.5)?
 				.build()
 		)?;
 